@@ -6,6 +6,8 @@
 void tx_full_handler(uint32_t id)
 {
     printf("get irq id %d!\n", id);
+    // ack full interrupt, int status is write 1 to clear
+    etha_reg_write(TX_RING(INT_STATUS, 0), SET_RING_INT_STATUS_FULL(1));
 }
 
 int main(int argc, const char *argv[])
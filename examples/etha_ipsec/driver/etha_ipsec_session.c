@@ -22,7 +22,7 @@ void etha_ipsec_session_cfg(EthaIpsecSession *self, const uint8_t *cipher_key, u
         etha_ipsec_reg_write(SEC_SESSION(HASH_KEY_HI, self->id), (uint32_t)((uint64_t)(hash_key) >> 32));
     }
     etha_ipsec_reg_write(SEC_SESSION(SALT, self->id), salt);
-    etha_ipsec_reg_write(SEC_SESSION(CTX, self->id), SET_XFORM_CTX_SALT_LEN(salt_len) | SET_XFORM_CTX_IV_LEN(iv_len) | SET_XFORM_CTX_CIPHER_ALG(cipher_alg) | SET_XFORM_CTX_CIPHER_MODE(cipher_mode) | SET_XFORM_CTX_HMAC_ALG(hmac_alg));
+    etha_ipsec_reg_write(SEC_SESSION(CTX, self->id), SET_XFORM_CTX_SALT_LEN(salt_len) | SET_XFORM_CTX_IV_LEN(iv_len) | SET_XFORM_CTX_CIPHER_ALG(cipher_alg) | SET_XFORM_CTX_CIPHER_MODE(cipher_mode) | SET_XFORM_CTX_CIPHER_MODE_HI(cipher_mode >> 2) | SET_XFORM_CTX_HMAC_ALG(hmac_alg));
     etha_ipsec_session_enable(self);
 }
 void etha_ipsec_session_enable(EthaIpsecSession *self)
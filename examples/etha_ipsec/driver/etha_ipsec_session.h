@@ -6,6 +6,7 @@ typedef enum
     CipherAlgNull = 0,
     AES128 = 1,
     AES256 = 2,
+    ChaCha20 = 3,
 } CipherAlg;
 
 typedef enum
@@ -13,6 +14,9 @@ typedef enum
     CBC = 0,
     CCM = 1,
     GCM = 2,
+    Poly1305 = 3,
+    CTR = 4,
+    GMAC = 5,
 } CipherMode;
 
 typedef enum
@@ -34,8 +34,27 @@ impl RegisterAccess {
     pub(crate) fn to_define_csr(&self) -> TokenStream {
         match self {
             RegisterAccess::RO => quote! {RO},
-            RegisterAccess::WO | RegisterAccess::RW1S | RegisterAccess::RW1C => quote! {WO},
-            RegisterAccess::RW => quote! {RW},
+            RegisterAccess::WO => quote! {WO},
+            //write 1 to set or clear is applied by bus_write on top of plain rw
+            RegisterAccess::RW | RegisterAccess::RW1S | RegisterAccess::RW1C => quote! {RW},
+        }
+    }
+    //bus write of the register at offset, None if written as is
+    pub(crate) fn expand_bus_write(&self, offset: &LitInt) -> Option<TokenStream> {
+        match self {
+            RegisterAccess::RW1S => Some(quote! {
+                #offset => {
+                    let v = self.read(addr)?;
+                    self.write(addr, v | data)
+                }
+            }),
+            RegisterAccess::RW1C => Some(quote! {
+                #offset => {
+                    let v = self.read(addr)?;
+                    self.write(addr, v & !data)
+                }
+            }),
+            _ => None,
         }
     }
     pub(crate) fn expand(&self) -> TokenStream {
@@ -34,7 +34,9 @@ impl RegMap {
             quote! {}
         };
         let iter_name = format_ident!("{}Iter", name);
+        let locked_name = format_ident!("Locked{}", name);
         let (regs, iters) = self.regs.expand()?;
+        let bus_writes = self.regs.expand_bus_write();
         Ok(quote! {
             csr_map! {
                 #vis #name (0, #hi) {
@@ -61,6 +63,15 @@ impl RegMap {
                     #iter_name::new()
                 }
             }
+            impl #locked_name {
+                //write from the register bus, with write 1 to set or clear applied
+                pub fn bus_write(&self, addr: u64, data: u64) -> Option<()> {
+                    match addr {
+                        #bus_writes
+                        _ => self.write(addr, data),
+                    }
+                }
+            }
             impl GenHeader for #name {
                 fn render_name() -> &'static str {
                     #name_s
@@ -134,6 +145,15 @@ impl Regs {
     }
 }
 
+impl Regs {
+    fn expand_bus_write(&self) -> TokenStream {
+        self.regs
+            .iter()
+            .filter_map(|r| r.access.expand_bus_write(&r.offset))
+            .collect()
+    }
+}
+
 impl Parse for Regs {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Regs {
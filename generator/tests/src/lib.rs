@@ -120,6 +120,16 @@ mod reg_tests {
         println!("{}", String::from_utf8_lossy(&c_header));
     }
 
+    #[test]
+    fn reg_map_rw1c_test() {
+        let regs = LockedEtherRx::new(32);
+        regs.write(1, 0x8000_ffff).unwrap();
+        regs.bus_write(1, 0x0000_00ff).unwrap();
+        assert_eq!(regs.read(1), Some(0x8000_ff00));
+        regs.bus_write(1, 0).unwrap();
+        assert_eq!(regs.read(1), Some(0x8000_ff00));
+    }
+
     #[test]
     fn gen_reg_map_test() {
         let mut c_header = vec![];
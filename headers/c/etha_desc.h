@@ -15,29 +15,46 @@ typedef struct {
 } __attribute__((packed)) FrameDesc;
 
 
+typedef struct {
+    uint32_t ts_lo;
+    uint32_t ts_hi;
+} __attribute__((packed)) TsDesc;
+
+
 typedef struct {
     uint32_t resp_en: 1;
-    uint32_t :31;
+    uint32_t l3_csum_en: 1;
+    uint32_t l4_csum_en: 1;
+    uint32_t tso_en: 1;
+    uint32_t vlan_insert: 1;
+    uint32_t ts_en: 1;
+    uint32_t :10;
+    uint32_t mss: 16;
+    uint32_t vlan_tci: 16;
+    uint32_t :16;
 } __attribute__((packed)) TxCtrlDesc;
 
 
 typedef struct {
     uint32_t too_large: 1;
     uint32_t too_small: 1;
-    uint32_t :30;
-    uint32_t padding[1];
+    uint32_t ts_valid: 1;
+    uint32_t :29;
+    uint32_t segs: 16;
+    uint32_t :16;
 } __attribute__((packed)) TxStatusDesc;
 
 
 typedef struct {
     FrameDesc frame;
     TxCtrlDesc ctrl;
-    uint8_t padding[12];
+    uint8_t padding[8];
 } TxReqDesc;
 
 
 typedef struct {
     TxStatusDesc status;
+    TsDesc ts;
 } TxResultDesc;
 
 
@@ -51,7 +68,12 @@ typedef struct {
     uint32_t l2_etype: 16;
     uint32_t l2_header_len: 8;
     uint32_t l2_is_vlan: 1;
-    uint32_t :23;
+    uint32_t l2_is_svlan: 1;
+    uint32_t l2_vlan_stripped: 1;
+    uint32_t :1;
+    uint32_t l2_svlan_flags: 4;
+    uint32_t l2_svlan_vid: 12;
+    uint32_t :4;
     uint32_t l2_payload_len: 24;
     uint32_t :8;
 } __attribute__((packed)) RxResultL2Desc;
@@ -70,7 +92,8 @@ typedef struct {
     uint32_t l3_version: 8;
     uint32_t l3_header_len: 16;
     uint32_t l3_payload_len: 24;
-    uint32_t :8;
+    uint32_t l3_is_frag: 1;
+    uint32_t :7;
 } __attribute__((packed)) RxResultL3Desc;
 
 
@@ -78,25 +101,79 @@ typedef struct {
     uint32_t l4_src_port: 16;
     uint32_t l4_dst_port: 16;
     uint32_t l4_header_len: 16;
-    uint32_t :16;
+    uint32_t l4_tcp_flags: 9;
+    uint32_t :7;
     uint32_t l4_payload_len: 24;
-    uint32_t :8;
+    uint32_t l4_lro_segs: 8;
 } __attribute__((packed)) RxResultL4Desc;
 
 
 typedef struct {
     uint32_t too_large: 1;
-    uint32_t :31;
+    uint32_t l3_csum_valid: 1;
+    uint32_t l3_csum_err: 1;
+    uint32_t l4_csum_valid: 1;
+    uint32_t l4_csum_err: 1;
+    uint32_t ts_valid: 1;
+    uint32_t :26;
 } __attribute__((packed)) RxStatusDesc;
 
 
+typedef struct {
+    uint32_t rss_hash;
+    uint32_t rss_type: 4;
+    uint32_t :28;
+} __attribute__((packed)) RxRssDesc;
+
+
+typedef struct {
+    uint32_t tunnel_type: 4;
+    uint32_t tunnel_inner_is_l2: 1;
+    uint32_t :27;
+    uint32_t tunnel_id;
+    uint32_t tunnel_inner_l2_offset: 16;
+    uint32_t tunnel_inner_l3_offset: 16;
+    uint32_t tunnel_inner_l4_offset: 16;
+    uint32_t tunnel_inner_protocol: 8;
+    uint32_t tunnel_inner_version: 4;
+    uint32_t :4;
+} __attribute__((packed)) RxTunnelDesc;
+
+
+typedef struct {
+    uint32_t ecpri_valid: 1;
+    uint32_t ecpri_concat: 1;
+    uint32_t :2;
+    uint32_t ecpri_revision: 4;
+    uint32_t ecpri_msg_type: 8;
+    uint32_t ecpri_payload_size: 16;
+    uint32_t ecpri_id: 16;
+    uint32_t ecpri_seq_id: 16;
+} __attribute__((packed)) RxEcpriDesc;
+
+
+typedef struct {
+    uint32_t hdr_addr_lo;
+    uint32_t hdr_addr_hi;
+    uint32_t hdr_len: 16;
+    uint32_t hdr_split: 1;
+    uint32_t hdr_fallback: 1;
+    uint32_t :14;
+    uint32_t padding[1];
+} __attribute__((packed)) RxHdrSplitDesc;
+
+
 typedef struct {
     FrameDesc frame;
     RxResultL2Desc l2;
     RxResultL3Desc l3;
     RxResultL4Desc l4;
     RxStatusDesc status;
-    uint8_t padding[32];
+    RxRssDesc rss;
+    RxTunnelDesc tunnel;
+    RxEcpriDesc ecpri;
+    RxHdrSplitDesc hdr;
+    TsDesc ts;
 } RxResultDesc;
 
 #endif
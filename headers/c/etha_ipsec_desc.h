@@ -13,7 +13,8 @@ typedef struct {
     uint32_t resp_en: 1;
     uint32_t aad_copy: 1;
     uint32_t iv_copy: 1;
-    uint32_t :4;
+    uint32_t esp_en: 1;
+    uint32_t :3;
 } __attribute__((packed)) IpsecFrameCfgDesc;
 
 
@@ -38,6 +39,7 @@ typedef struct {
     IpsecCfgDesc cfg;
 } IpsecReqDesc;
 
+// seq_overflow: the outbound sequence number of the sa is exhausted, a new sa has to be set up
 
 typedef struct {
     uint32_t src_err: 1;
@@ -45,8 +47,12 @@ typedef struct {
     uint32_t invalid_session: 1;
     uint32_t ciper_err: 1;
     uint32_t auth_fail: 1;
-    uint32_t :27;
-    uint32_t padding[1];
+    uint32_t proto_err: 1;
+    uint32_t replay_fail: 1;
+    uint32_t expired: 1;
+    uint32_t seq_overflow: 1;
+    uint32_t :23;
+    uint32_t dst_len;
 } __attribute__((packed)) IpsecStatusDesc;
 
 
@@ -8,6 +8,7 @@ typedef enum {
     EthaIpsecChIrq1 = 1,
     EthaIpsecChIrq2 = 2,
     EthaIpsecChIrq3 = 3,
+    EthaIpsecLifeIrq = 4,
 } EthaIpsecIrqs;
 
 #endif
@@ -20,6 +20,61 @@
 #define SEC_SESSION_HASH_KEY_HI_OFFSET 0x5
 
 
+#define SEC_SESSION_EXT_SIZE 0x20
+// macros: ESP_CTRL*
+#define SEC_SESSION_EXT_ESP_CTRL_OFFSET 0x0
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_ESP_SPI_OFFSET 0x1
+// macros: ESP_SEQ*
+#define SEC_SESSION_EXT_ESP_SEQ_OFFSET 0x2
+// macros: ESP_SEQ*
+#define SEC_SESSION_EXT_ESP_SEQ_HI_OFFSET 0x3
+// macros: ESP_SEQ*
+#define SEC_SESSION_EXT_ESP_REPLAY_TOP_LO_OFFSET 0x4
+// macros: ESP_SEQ*
+#define SEC_SESSION_EXT_ESP_REPLAY_TOP_HI_OFFSET 0x5
+// macros: ESP_REPLAY_IDX*
+#define SEC_SESSION_EXT_ESP_REPLAY_IDX_OFFSET 0x6
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_ESP_REPLAY_BITMAP_OFFSET 0x7
+// macros: LIFE_CNT*
+#define SEC_SESSION_EXT_PKT_CNT_OFFSET 0x8
+// macros: LIFE_STATUS*
+#define SEC_SESSION_EXT_LIFE_STATUS_OFFSET 0x9
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_SRC0_OFFSET 0xa
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_SRC1_OFFSET 0xb
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_SRC2_OFFSET 0xc
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_SRC3_OFFSET 0xd
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_DST0_OFFSET 0xe
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_DST1_OFFSET 0xf
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_DST2_OFFSET 0x10
+// macros: KEY_VALUE*
+#define SEC_SESSION_EXT_TUNNEL_DST3_OFFSET 0x11
+// macros: LIFE_CNT*
+#define SEC_SESSION_EXT_BYTE_CNT_LO_OFFSET 0x12
+// macros: LIFE_CNT*
+#define SEC_SESSION_EXT_BYTE_CNT_HI_OFFSET 0x13
+// macros: LIFE_LIMIT*
+#define SEC_SESSION_EXT_SOFT_BYTES_LO_OFFSET 0x14
+// macros: LIFE_LIMIT*
+#define SEC_SESSION_EXT_SOFT_BYTES_HI_OFFSET 0x15
+// macros: LIFE_LIMIT*
+#define SEC_SESSION_EXT_HARD_BYTES_LO_OFFSET 0x16
+// macros: LIFE_LIMIT*
+#define SEC_SESSION_EXT_HARD_BYTES_HI_OFFSET 0x17
+// macros: LIFE_LIMIT*
+#define SEC_SESSION_EXT_SOFT_PKTS_OFFSET 0x18
+// macros: LIFE_LIMIT*
+#define SEC_SESSION_EXT_HARD_PKTS_OFFSET 0x19
+
+
 #define XFORM_CTX_VALID_POS 0
 #define XFORM_CTX_VALID_FLAGS 0x1
 //Enum: invalid : 0x0; valid : 0x1;
@@ -29,14 +84,14 @@
 
 #define XFORM_CTX_CIPHER_ALG_POS 1
 #define XFORM_CTX_CIPHER_ALG_FLAGS 0x3
-//Enum: null : 0x0; aes_128 : 0x1; aes_256 : 0x2;
+//Enum: null : 0x0; aes_128 : 0x1; aes_256 : 0x2; chacha20 : 0x3;
 #define XFORM_CTX_CIPHER_ALG(x) (((x) >> XFORM_CTX_CIPHER_ALG_POS) & XFORM_CTX_CIPHER_ALG_FLAGS)
 #define SET_XFORM_CTX_CIPHER_ALG(x) (((x) & XFORM_CTX_CIPHER_ALG_FLAGS) << XFORM_CTX_CIPHER_ALG_POS)
 
 
 #define XFORM_CTX_CIPHER_MODE_POS 3
 #define XFORM_CTX_CIPHER_MODE_FLAGS 0x3
-//Enum: cbc : 0x0; ccm : 0x1; gcm : 0x2;
+//Enum: cbc : 0x0; ccm : 0x1; gcm : 0x2; poly1305 : 0x3;
 #define XFORM_CTX_CIPHER_MODE(x) (((x) >> XFORM_CTX_CIPHER_MODE_POS) & XFORM_CTX_CIPHER_MODE_FLAGS)
 #define SET_XFORM_CTX_CIPHER_MODE(x) (((x) & XFORM_CTX_CIPHER_MODE_FLAGS) << XFORM_CTX_CIPHER_MODE_POS)
 
@@ -65,12 +120,62 @@
 #define XFORM_CTX_ICV_LEN(x) (((x) >> XFORM_CTX_ICV_LEN_POS) & XFORM_CTX_ICV_LEN_FLAGS)
 #define SET_XFORM_CTX_ICV_LEN(x) (((x) & XFORM_CTX_ICV_LEN_FLAGS) << XFORM_CTX_ICV_LEN_POS)
 
+
+#define XFORM_CTX_CIPHER_MODE_HI_POS 28
+#define XFORM_CTX_CIPHER_MODE_HI_FLAGS 0x1
+//Enum: std : 0x0; ctr_gmac : 0x1;
+#define XFORM_CTX_CIPHER_MODE_HI(x) (((x) >> XFORM_CTX_CIPHER_MODE_HI_POS) & XFORM_CTX_CIPHER_MODE_HI_FLAGS)
+#define SET_XFORM_CTX_CIPHER_MODE_HI(x) (((x) & XFORM_CTX_CIPHER_MODE_HI_FLAGS) << XFORM_CTX_CIPHER_MODE_HI_POS)
+
+
+#define ESP_CTRL_MODE_POS 0
+#define ESP_CTRL_MODE_FLAGS 0x1
+//Enum: transport : 0x0; tunnel : 0x1;
+#define ESP_CTRL_MODE(x) (((x) >> ESP_CTRL_MODE_POS) & ESP_CTRL_MODE_FLAGS)
+#define SET_ESP_CTRL_MODE(x) (((x) & ESP_CTRL_MODE_FLAGS) << ESP_CTRL_MODE_POS)
+
+
+#define ESP_CTRL_TUNNEL_IP_POS 1
+#define ESP_CTRL_TUNNEL_IP_FLAGS 0x1
+//Enum: ipv4 : 0x0; ipv6 : 0x1;
+#define ESP_CTRL_TUNNEL_IP(x) (((x) >> ESP_CTRL_TUNNEL_IP_POS) & ESP_CTRL_TUNNEL_IP_FLAGS)
+#define SET_ESP_CTRL_TUNNEL_IP(x) (((x) & ESP_CTRL_TUNNEL_IP_FLAGS) << ESP_CTRL_TUNNEL_IP_POS)
+
+
+#define ESP_CTRL_ESN_POS 2
+#define ESP_CTRL_ESN_FLAGS 0x1
+#define ESP_CTRL_ESN(x) (((x) >> ESP_CTRL_ESN_POS) & ESP_CTRL_ESN_FLAGS)
+#define SET_ESP_CTRL_ESN(x) (((x) & ESP_CTRL_ESN_FLAGS) << ESP_CTRL_ESN_POS)
+
+
+#define ESP_CTRL_REPLAY_WIN_POS 4
+#define ESP_CTRL_REPLAY_WIN_FLAGS 0x7ff
+#define ESP_CTRL_REPLAY_WIN(x) (((x) >> ESP_CTRL_REPLAY_WIN_POS) & ESP_CTRL_REPLAY_WIN_FLAGS)
+#define SET_ESP_CTRL_REPLAY_WIN(x) (((x) & ESP_CTRL_REPLAY_WIN_FLAGS) << ESP_CTRL_REPLAY_WIN_POS)
+
+
+#define LIFE_STATUS_SOFT_POS 0
+#define LIFE_STATUS_SOFT_FLAGS 0x1
+#define LIFE_STATUS_SOFT(x) (((x) >> LIFE_STATUS_SOFT_POS) & LIFE_STATUS_SOFT_FLAGS)
+#define SET_LIFE_STATUS_SOFT(x) (((x) & LIFE_STATUS_SOFT_FLAGS) << LIFE_STATUS_SOFT_POS)
+
+
+#define LIFE_STATUS_HARD_POS 1
+#define LIFE_STATUS_HARD_FLAGS 0x1
+#define LIFE_STATUS_HARD(x) (((x) >> LIFE_STATUS_HARD_POS) & LIFE_STATUS_HARD_FLAGS)
+#define SET_LIFE_STATUS_HARD(x) (((x) & LIFE_STATUS_HARD_FLAGS) << LIFE_STATUS_HARD_POS)
+
 #define SEC_SESSIONS_NUM 64
+#define ESP_REPLAY_WIN_MAX 1024
 #define SEC_SESSION_OFFSET(base, name, i) ((base) + (SEC_SESSION_SIZE * i) + SEC_SESSION_##name##_OFFSET)
+#define SEC_SESSION_EXT_OFFSET(base, name, i) ((base) + (SEC_SESSION_EXT_SIZE * i) + SEC_SESSION_EXT_##name##_OFFSET)
 #define SEC_SESSIONS_OFFSET 0x0
+#define SEC_SESSIONS_EXT_OFFSET 0xc00
 #define QUEUE_NUM 4
 #define QUEUE_REGS_OFFSET 0x800
 #define RING(name, i) (QUEUE_REGS_OFFSET + (RING_REGS_SIZE * i) + RING_REGS_##name##_OFFSET)
+// RING INT_STATUS and INT_COAL are not decoded, irqs are raised by STATUS & INT_MASK
 #define SEC_SESSION(name, i) SEC_SESSION_OFFSET(SEC_SESSIONS_OFFSET, name, i)
+#define SEC_SESSION_EXT(name, i) SEC_SESSION_EXT_OFFSET(SEC_SESSIONS_EXT_OFFSET, name, i)
 
 #endif
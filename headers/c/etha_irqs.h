@@ -36,6 +36,74 @@ typedef enum {
     EthaTxChIrq13 = 29,
     EthaTxChIrq14 = 30,
     EthaTxChIrq15 = 31,
+    EthaErrIrq = 32,
+    EthaLinkIrq = 33,
 } EthaIrqs;
+typedef enum {
+    EthaIrqsVec0 = 0,
+    EthaIrqsVec1 = 1,
+    EthaIrqsVec2 = 2,
+    EthaIrqsVec3 = 3,
+    EthaIrqsVec4 = 4,
+    EthaIrqsVec5 = 5,
+    EthaIrqsVec6 = 6,
+    EthaIrqsVec7 = 7,
+    EthaIrqsVec8 = 8,
+    EthaIrqsVec9 = 9,
+    EthaIrqsVec10 = 10,
+    EthaIrqsVec11 = 11,
+    EthaIrqsVec12 = 12,
+    EthaIrqsVec13 = 13,
+    EthaIrqsVec14 = 14,
+    EthaIrqsVec15 = 15,
+    EthaIrqsVec16 = 16,
+    EthaIrqsVec17 = 17,
+    EthaIrqsVec18 = 18,
+    EthaIrqsVec19 = 19,
+    EthaIrqsVec20 = 20,
+    EthaIrqsVec21 = 21,
+    EthaIrqsVec22 = 22,
+    EthaIrqsVec23 = 23,
+    EthaIrqsVec24 = 24,
+    EthaIrqsVec25 = 25,
+    EthaIrqsVec26 = 26,
+    EthaIrqsVec27 = 27,
+    EthaIrqsVec28 = 28,
+    EthaIrqsVec29 = 29,
+    EthaIrqsVec30 = 30,
+    EthaIrqsVec31 = 31,
+    EthaIrqsVec32 = 32,
+    EthaIrqsVec33 = 33,
+    EthaIrqsVec34 = 34,
+    EthaIrqsVec35 = 35,
+    EthaIrqsVec36 = 36,
+    EthaIrqsVec37 = 37,
+    EthaIrqsVec38 = 38,
+    EthaIrqsVec39 = 39,
+    EthaIrqsVec40 = 40,
+    EthaIrqsVec41 = 41,
+    EthaIrqsVec42 = 42,
+    EthaIrqsVec43 = 43,
+    EthaIrqsVec44 = 44,
+    EthaIrqsVec45 = 45,
+    EthaIrqsVec46 = 46,
+    EthaIrqsVec47 = 47,
+    EthaIrqsVec48 = 48,
+    EthaIrqsVec49 = 49,
+    EthaIrqsVec50 = 50,
+    EthaIrqsVec51 = 51,
+    EthaIrqsVec52 = 52,
+    EthaIrqsVec53 = 53,
+    EthaIrqsVec54 = 54,
+    EthaIrqsVec55 = 55,
+    EthaIrqsVec56 = 56,
+    EthaIrqsVec57 = 57,
+    EthaIrqsVec58 = 58,
+    EthaIrqsVec59 = 59,
+    EthaIrqsVec60 = 60,
+    EthaIrqsVec61 = 61,
+    EthaIrqsVec62 = 62,
+    EthaIrqsVec63 = 63,
+} EthaIrqsVec;
 
 #endif
@@ -20,7 +20,9 @@ extern void etha_simulate_loopback(int32_t core_id);
 extern void etha_simulate_raw_socket(const char *socket_file, int32_t core_id);
 extern void etha_simulate_tap(const char *tap_file, int32_t core_id);
 extern void etha_abort(void);
+// id is a vector of EthaIrqsVec, causes are mapped to vectors by IRQ_MAP
 extern void etha_register_irq_handler(uint32_t id, etha_irq_handler f);
+extern void etha_set_link(uint32_t port, bool up);
 extern void etha_reg_write(uint32_t addr, uint32_t value);
 extern uint32_t etha_reg_read(uint32_t addr);
 
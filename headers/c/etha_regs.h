@@ -15,6 +15,64 @@
 #define ETHA_GLOBAL_REGS_RX_EN_OFFSET 0x0
 // macros: ETHA_EN*
 #define ETHA_GLOBAL_REGS_TX_EN_OFFSET 0x1
+// macros: ETHA_EN*
+#define ETHA_GLOBAL_REGS_RX_VLAN_STRIP_OFFSET 0x2
+// macros: TX_ARB_CTRL*
+#define ETHA_GLOBAL_REGS_TX_ARB_OFFSET 0x3
+// macros: TX_ARB_PRI*
+#define ETHA_GLOBAL_REGS_TX_ARB_PRI0_OFFSET 0x4
+// macros: TX_ARB_PRI*
+#define ETHA_GLOBAL_REGS_TX_ARB_PRI1_OFFSET 0x5
+// macros: TX_ARB_WEIGHT*
+#define ETHA_GLOBAL_REGS_TX_ARB_WEIGHT0_OFFSET 0x6
+// macros: TX_ARB_WEIGHT*
+#define ETHA_GLOBAL_REGS_TX_ARB_WEIGHT1_OFFSET 0x7
+// macros: TX_ARB_WEIGHT*
+#define ETHA_GLOBAL_REGS_TX_ARB_WEIGHT2_OFFSET 0x8
+// macros: TX_ARB_WEIGHT*
+#define ETHA_GLOBAL_REGS_TX_ARB_WEIGHT3_OFFSET 0x9
+// macros: CLOCK_CTRL*
+#define ETHA_GLOBAL_REGS_CLOCK_CTRL_OFFSET 0xa
+// macros: CLOCK_COUNTER*
+#define ETHA_GLOBAL_REGS_CLOCK_LO_OFFSET 0xb
+// macros: CLOCK_COUNTER*
+#define ETHA_GLOBAL_REGS_CLOCK_HI_OFFSET 0xc
+// macros: RX_MODE*
+#define ETHA_GLOBAL_REGS_RX_MODE_OFFSET 0xd
+// macros: MC_HASH*
+#define ETHA_GLOBAL_REGS_MC_HASH_LO_OFFSET 0xe
+// macros: MC_HASH*
+#define ETHA_GLOBAL_REGS_MC_HASH_HI_OFFSET 0xf
+// macros: STATION_ADDR_LO*
+#define ETHA_GLOBAL_REGS_STATION_LO0_OFFSET 0x10
+// macros: STATION_ADDR_HI*
+#define ETHA_GLOBAL_REGS_STATION_HI0_OFFSET 0x11
+// macros: STATION_ADDR_LO*
+#define ETHA_GLOBAL_REGS_STATION_LO1_OFFSET 0x12
+// macros: STATION_ADDR_HI*
+#define ETHA_GLOBAL_REGS_STATION_HI1_OFFSET 0x13
+// macros: STATION_ADDR_LO*
+#define ETHA_GLOBAL_REGS_STATION_LO2_OFFSET 0x14
+// macros: STATION_ADDR_HI*
+#define ETHA_GLOBAL_REGS_STATION_HI2_OFFSET 0x15
+// macros: STATION_ADDR_LO*
+#define ETHA_GLOBAL_REGS_STATION_LO3_OFFSET 0x16
+// macros: STATION_ADDR_HI*
+#define ETHA_GLOBAL_REGS_STATION_HI3_OFFSET 0x17
+// macros: PTP_CTRL*
+#define ETHA_GLOBAL_REGS_PTP_CTRL_OFFSET 0x18
+// macros: CLOCK_COUNTER*
+#define ETHA_GLOBAL_REGS_PTP_TIME_LO_OFFSET 0x19
+// macros: CLOCK_COUNTER*
+#define ETHA_GLOBAL_REGS_PTP_TIME_HI_OFFSET 0x1a
+// macros: CLOCK_COUNTER*
+#define ETHA_GLOBAL_REGS_PTP_SET_LO_OFFSET 0x1b
+// macros: CLOCK_COUNTER*
+#define ETHA_GLOBAL_REGS_PTP_SET_HI_OFFSET 0x1c
+// macros: PTP_ADJ*
+#define ETHA_GLOBAL_REGS_PTP_ADJ_OFFSET 0x1d
+// macros: PTP_FREQ*
+#define ETHA_GLOBAL_REGS_PTP_FREQ_OFFSET 0x1e
 
 
 #define ETHA_EN_EN_POS 0
@@ -22,12 +80,474 @@
 #define ETHA_EN_EN(x) (((x) >> ETHA_EN_EN_POS) & ETHA_EN_EN_FLAGS)
 #define SET_ETHA_EN_EN(x) (((x) & ETHA_EN_EN_FLAGS) << ETHA_EN_EN_POS)
 
+
+#define TX_ARB_CTRL_MODE_POS 0
+#define TX_ARB_CTRL_MODE_FLAGS 0x3
+//Enum: rr : 0x0; sp : 0x1; dwrr : 0x2; hybrid : 0x3;
+#define TX_ARB_CTRL_MODE(x) (((x) >> TX_ARB_CTRL_MODE_POS) & TX_ARB_CTRL_MODE_FLAGS)
+#define SET_TX_ARB_CTRL_MODE(x) (((x) & TX_ARB_CTRL_MODE_FLAGS) << TX_ARB_CTRL_MODE_POS)
+
+
+#define TX_ARB_PRI_PRI0_POS 0
+#define TX_ARB_PRI_PRI0_FLAGS 0x7
+#define TX_ARB_PRI_PRI0(x) (((x) >> TX_ARB_PRI_PRI0_POS) & TX_ARB_PRI_PRI0_FLAGS)
+#define SET_TX_ARB_PRI_PRI0(x) (((x) & TX_ARB_PRI_PRI0_FLAGS) << TX_ARB_PRI_PRI0_POS)
+
+
+#define TX_ARB_PRI_PRI1_POS 4
+#define TX_ARB_PRI_PRI1_FLAGS 0x7
+#define TX_ARB_PRI_PRI1(x) (((x) >> TX_ARB_PRI_PRI1_POS) & TX_ARB_PRI_PRI1_FLAGS)
+#define SET_TX_ARB_PRI_PRI1(x) (((x) & TX_ARB_PRI_PRI1_FLAGS) << TX_ARB_PRI_PRI1_POS)
+
+
+#define TX_ARB_PRI_PRI2_POS 8
+#define TX_ARB_PRI_PRI2_FLAGS 0x7
+#define TX_ARB_PRI_PRI2(x) (((x) >> TX_ARB_PRI_PRI2_POS) & TX_ARB_PRI_PRI2_FLAGS)
+#define SET_TX_ARB_PRI_PRI2(x) (((x) & TX_ARB_PRI_PRI2_FLAGS) << TX_ARB_PRI_PRI2_POS)
+
+
+#define TX_ARB_PRI_PRI3_POS 12
+#define TX_ARB_PRI_PRI3_FLAGS 0x7
+#define TX_ARB_PRI_PRI3(x) (((x) >> TX_ARB_PRI_PRI3_POS) & TX_ARB_PRI_PRI3_FLAGS)
+#define SET_TX_ARB_PRI_PRI3(x) (((x) & TX_ARB_PRI_PRI3_FLAGS) << TX_ARB_PRI_PRI3_POS)
+
+
+#define TX_ARB_PRI_PRI4_POS 16
+#define TX_ARB_PRI_PRI4_FLAGS 0x7
+#define TX_ARB_PRI_PRI4(x) (((x) >> TX_ARB_PRI_PRI4_POS) & TX_ARB_PRI_PRI4_FLAGS)
+#define SET_TX_ARB_PRI_PRI4(x) (((x) & TX_ARB_PRI_PRI4_FLAGS) << TX_ARB_PRI_PRI4_POS)
+
+
+#define TX_ARB_PRI_PRI5_POS 20
+#define TX_ARB_PRI_PRI5_FLAGS 0x7
+#define TX_ARB_PRI_PRI5(x) (((x) >> TX_ARB_PRI_PRI5_POS) & TX_ARB_PRI_PRI5_FLAGS)
+#define SET_TX_ARB_PRI_PRI5(x) (((x) & TX_ARB_PRI_PRI5_FLAGS) << TX_ARB_PRI_PRI5_POS)
+
+
+#define TX_ARB_PRI_PRI6_POS 24
+#define TX_ARB_PRI_PRI6_FLAGS 0x7
+#define TX_ARB_PRI_PRI6(x) (((x) >> TX_ARB_PRI_PRI6_POS) & TX_ARB_PRI_PRI6_FLAGS)
+#define SET_TX_ARB_PRI_PRI6(x) (((x) & TX_ARB_PRI_PRI6_FLAGS) << TX_ARB_PRI_PRI6_POS)
+
+
+#define TX_ARB_PRI_PRI7_POS 28
+#define TX_ARB_PRI_PRI7_FLAGS 0x7
+#define TX_ARB_PRI_PRI7(x) (((x) >> TX_ARB_PRI_PRI7_POS) & TX_ARB_PRI_PRI7_FLAGS)
+#define SET_TX_ARB_PRI_PRI7(x) (((x) & TX_ARB_PRI_PRI7_FLAGS) << TX_ARB_PRI_PRI7_POS)
+
+
+#define TX_ARB_WEIGHT_WEIGHT0_POS 0
+#define TX_ARB_WEIGHT_WEIGHT0_FLAGS 0xff
+#define TX_ARB_WEIGHT_WEIGHT0(x) (((x) >> TX_ARB_WEIGHT_WEIGHT0_POS) & TX_ARB_WEIGHT_WEIGHT0_FLAGS)
+#define SET_TX_ARB_WEIGHT_WEIGHT0(x) (((x) & TX_ARB_WEIGHT_WEIGHT0_FLAGS) << TX_ARB_WEIGHT_WEIGHT0_POS)
+
+
+#define TX_ARB_WEIGHT_WEIGHT1_POS 8
+#define TX_ARB_WEIGHT_WEIGHT1_FLAGS 0xff
+#define TX_ARB_WEIGHT_WEIGHT1(x) (((x) >> TX_ARB_WEIGHT_WEIGHT1_POS) & TX_ARB_WEIGHT_WEIGHT1_FLAGS)
+#define SET_TX_ARB_WEIGHT_WEIGHT1(x) (((x) & TX_ARB_WEIGHT_WEIGHT1_FLAGS) << TX_ARB_WEIGHT_WEIGHT1_POS)
+
+
+#define TX_ARB_WEIGHT_WEIGHT2_POS 16
+#define TX_ARB_WEIGHT_WEIGHT2_FLAGS 0xff
+#define TX_ARB_WEIGHT_WEIGHT2(x) (((x) >> TX_ARB_WEIGHT_WEIGHT2_POS) & TX_ARB_WEIGHT_WEIGHT2_FLAGS)
+#define SET_TX_ARB_WEIGHT_WEIGHT2(x) (((x) & TX_ARB_WEIGHT_WEIGHT2_FLAGS) << TX_ARB_WEIGHT_WEIGHT2_POS)
+
+
+#define TX_ARB_WEIGHT_WEIGHT3_POS 24
+#define TX_ARB_WEIGHT_WEIGHT3_FLAGS 0xff
+#define TX_ARB_WEIGHT_WEIGHT3(x) (((x) >> TX_ARB_WEIGHT_WEIGHT3_POS) & TX_ARB_WEIGHT_WEIGHT3_FLAGS)
+#define SET_TX_ARB_WEIGHT_WEIGHT3(x) (((x) & TX_ARB_WEIGHT_WEIGHT3_FLAGS) << TX_ARB_WEIGHT_WEIGHT3_POS)
+
+
+#define CLOCK_CTRL_VIRTUAL_EN_POS 0
+#define CLOCK_CTRL_VIRTUAL_EN_FLAGS 0x1
+#define CLOCK_CTRL_VIRTUAL_EN(x) (((x) >> CLOCK_CTRL_VIRTUAL_EN_POS) & CLOCK_CTRL_VIRTUAL_EN_FLAGS)
+#define SET_CLOCK_CTRL_VIRTUAL_EN(x) (((x) & CLOCK_CTRL_VIRTUAL_EN_FLAGS) << CLOCK_CTRL_VIRTUAL_EN_POS)
+
+
+#define CLOCK_COUNTER_CNT_POS 0
+#define CLOCK_COUNTER_CNT_FLAGS 0xffffffff
+#define CLOCK_COUNTER_CNT(x) (((x) >> CLOCK_COUNTER_CNT_POS) & CLOCK_COUNTER_CNT_FLAGS)
+#define SET_CLOCK_COUNTER_CNT(x) (((x) & CLOCK_COUNTER_CNT_FLAGS) << CLOCK_COUNTER_CNT_POS)
+
+
+#define RX_MODE_EN_POS 0
+#define RX_MODE_EN_FLAGS 0x1
+#define RX_MODE_EN(x) (((x) >> RX_MODE_EN_POS) & RX_MODE_EN_FLAGS)
+#define SET_RX_MODE_EN(x) (((x) & RX_MODE_EN_FLAGS) << RX_MODE_EN_POS)
+
+
+#define RX_MODE_BCAST_EN_POS 1
+#define RX_MODE_BCAST_EN_FLAGS 0x1
+#define RX_MODE_BCAST_EN(x) (((x) >> RX_MODE_BCAST_EN_POS) & RX_MODE_BCAST_EN_FLAGS)
+#define SET_RX_MODE_BCAST_EN(x) (((x) & RX_MODE_BCAST_EN_FLAGS) << RX_MODE_BCAST_EN_POS)
+
+
+#define RX_MODE_PROMISC_POS 2
+#define RX_MODE_PROMISC_FLAGS 0x1
+#define RX_MODE_PROMISC(x) (((x) >> RX_MODE_PROMISC_POS) & RX_MODE_PROMISC_FLAGS)
+#define SET_RX_MODE_PROMISC(x) (((x) & RX_MODE_PROMISC_FLAGS) << RX_MODE_PROMISC_POS)
+
+
+#define RX_MODE_ALLMULTI_POS 3
+#define RX_MODE_ALLMULTI_FLAGS 0x1
+#define RX_MODE_ALLMULTI(x) (((x) >> RX_MODE_ALLMULTI_POS) & RX_MODE_ALLMULTI_FLAGS)
+#define SET_RX_MODE_ALLMULTI(x) (((x) & RX_MODE_ALLMULTI_FLAGS) << RX_MODE_ALLMULTI_POS)
+
+
+#define MC_HASH_BITS_POS 0
+#define MC_HASH_BITS_FLAGS 0xffffffff
+#define MC_HASH_BITS(x) (((x) >> MC_HASH_BITS_POS) & MC_HASH_BITS_FLAGS)
+#define SET_MC_HASH_BITS(x) (((x) & MC_HASH_BITS_FLAGS) << MC_HASH_BITS_POS)
+
+
+#define STATION_ADDR_LO_MAC_POS 0
+#define STATION_ADDR_LO_MAC_FLAGS 0xffffffff
+#define STATION_ADDR_LO_MAC(x) (((x) >> STATION_ADDR_LO_MAC_POS) & STATION_ADDR_LO_MAC_FLAGS)
+#define SET_STATION_ADDR_LO_MAC(x) (((x) & STATION_ADDR_LO_MAC_FLAGS) << STATION_ADDR_LO_MAC_POS)
+
+
+#define STATION_ADDR_HI_MAC_POS 0
+#define STATION_ADDR_HI_MAC_FLAGS 0xffff
+#define STATION_ADDR_HI_MAC(x) (((x) >> STATION_ADDR_HI_MAC_POS) & STATION_ADDR_HI_MAC_FLAGS)
+#define SET_STATION_ADDR_HI_MAC(x) (((x) & STATION_ADDR_HI_MAC_FLAGS) << STATION_ADDR_HI_MAC_POS)
+
+
+#define STATION_ADDR_HI_EN_POS 31
+#define STATION_ADDR_HI_EN_FLAGS 0x1
+#define STATION_ADDR_HI_EN(x) (((x) >> STATION_ADDR_HI_EN_POS) & STATION_ADDR_HI_EN_FLAGS)
+#define SET_STATION_ADDR_HI_EN(x) (((x) & STATION_ADDR_HI_EN_FLAGS) << STATION_ADDR_HI_EN_POS)
+
+
+#define PTP_CTRL_RX_TS_POS 0
+#define PTP_CTRL_RX_TS_FLAGS 0x3
+//Enum: none : 0x0; ptp : 0x1; all : 0x2;
+#define PTP_CTRL_RX_TS(x) (((x) >> PTP_CTRL_RX_TS_POS) & PTP_CTRL_RX_TS_FLAGS)
+#define SET_PTP_CTRL_RX_TS(x) (((x) & PTP_CTRL_RX_TS_FLAGS) << PTP_CTRL_RX_TS_POS)
+
+
+#define PTP_ADJ_DELTA_POS 0
+#define PTP_ADJ_DELTA_FLAGS 0xffffffff
+#define PTP_ADJ_DELTA(x) (((x) >> PTP_ADJ_DELTA_POS) & PTP_ADJ_DELTA_FLAGS)
+#define SET_PTP_ADJ_DELTA(x) (((x) & PTP_ADJ_DELTA_FLAGS) << PTP_ADJ_DELTA_POS)
+
+
+#define PTP_FREQ_PPB_POS 0
+#define PTP_FREQ_PPB_FLAGS 0xffffffff
+#define PTP_FREQ_PPB(x) (((x) >> PTP_FREQ_PPB_POS) & PTP_FREQ_PPB_FLAGS)
+#define SET_PTP_FREQ_PPB(x) (((x) & PTP_FREQ_PPB_FLAGS) << PTP_FREQ_PPB_POS)
+
+#define RX_STATION_ADDRS 4
+#define STATS_REGS_OFFSET 0x1400
+
+#define STATS_CTRL_REGS_SIZE 0x1
+// macros: STATS_CTRL*
+#define STATS_CTRL_REGS_STATS_CTRL_OFFSET 0x0
+
+
+#define STATS_CTRL_CLEAR_ON_READ_POS 0
+#define STATS_CTRL_CLEAR_ON_READ_FLAGS 0x1
+#define STATS_CTRL_CLEAR_ON_READ(x) (((x) >> STATS_CTRL_CLEAR_ON_READ_POS) & STATS_CTRL_CLEAR_ON_READ_FLAGS)
+#define SET_STATS_CTRL_CLEAR_ON_READ(x) (((x) & STATS_CTRL_CLEAR_ON_READ_FLAGS) << STATS_CTRL_CLEAR_ON_READ_POS)
+
+
+#define STATS_CTRL_SNAPSHOT_EN_POS 1
+#define STATS_CTRL_SNAPSHOT_EN_FLAGS 0x1
+#define STATS_CTRL_SNAPSHOT_EN(x) (((x) >> STATS_CTRL_SNAPSHOT_EN_POS) & STATS_CTRL_SNAPSHOT_EN_FLAGS)
+#define SET_STATS_CTRL_SNAPSHOT_EN(x) (((x) & STATS_CTRL_SNAPSHOT_EN_FLAGS) << STATS_CTRL_SNAPSHOT_EN_POS)
+
+
+#define STATS_CTRL_SNAPSHOT_POS 2
+#define STATS_CTRL_SNAPSHOT_FLAGS 0x1
+#define STATS_CTRL_SNAPSHOT(x) (((x) >> STATS_CTRL_SNAPSHOT_POS) & STATS_CTRL_SNAPSHOT_FLAGS)
+#define SET_STATS_CTRL_SNAPSHOT(x) (((x) & STATS_CTRL_SNAPSHOT_FLAGS) << STATS_CTRL_SNAPSHOT_POS)
+
+
+#define STATS_CTRL_CLEAR_POS 3
+#define STATS_CTRL_CLEAR_FLAGS 0x1
+#define STATS_CTRL_CLEAR(x) (((x) >> STATS_CTRL_CLEAR_POS) & STATS_CTRL_CLEAR_FLAGS)
+#define SET_STATS_CTRL_CLEAR(x) (((x) & STATS_CTRL_CLEAR_FLAGS) << STATS_CTRL_CLEAR_POS)
+
+#define STATS_COUNTER_SIZE 0x2
+#define STATS_RX_PARSE_ERR_LO_OFFSET 0x20
+#define STATS_RX_PARSE_ERR_HI_OFFSET 0x21
+#define STATS_RX_NO_QUEUE_LO_OFFSET 0x22
+#define STATS_RX_NO_QUEUE_HI_OFFSET 0x23
+#define STATS_TX_TOO_LARGE_LO_OFFSET 0x24
+#define STATS_TX_TOO_LARGE_HI_OFFSET 0x25
+#define STATS_TX_TOO_SMALL_LO_OFFSET 0x26
+#define STATS_TX_TOO_SMALL_HI_OFFSET 0x27
+#define STATS_RX_ADDR_FILTER_LO_OFFSET 0x28
+#define STATS_RX_ADDR_FILTER_HI_OFFSET 0x29
+#define STATS_HIST_BUCKETS 7
+#define STATS_RX_HIST_OFFSET 0x40
+#define STATS_TX_HIST_OFFSET 0x50
+#define STATS_QUEUE_OFFSET 0x80
+#define STATS_QUEUE_SIZE 0x10
+#define STATS_QUEUE_RX_PKTS_LO_OFFSET 0x0
+#define STATS_QUEUE_RX_PKTS_HI_OFFSET 0x1
+#define STATS_QUEUE_RX_BYTES_LO_OFFSET 0x2
+#define STATS_QUEUE_RX_BYTES_HI_OFFSET 0x3
+#define STATS_QUEUE_TX_PKTS_LO_OFFSET 0x4
+#define STATS_QUEUE_TX_PKTS_HI_OFFSET 0x5
+#define STATS_QUEUE_TX_BYTES_LO_OFFSET 0x6
+#define STATS_QUEUE_TX_BYTES_HI_OFFSET 0x7
+#define STATS_QUEUE_CONGESTION_BLOCKING_LO_OFFSET 0x8
+#define STATS_QUEUE_CONGESTION_BLOCKING_HI_OFFSET 0x9
+#define STATS_QUEUE_CONGESTION_DROP_LO_OFFSET 0xa
+#define STATS_QUEUE_CONGESTION_DROP_HI_OFFSET 0xb
+#define STATS_QUEUE_CONGESTION_DEFAULT_LO_OFFSET 0xc
+#define STATS_QUEUE_CONGESTION_DEFAULT_HI_OFFSET 0xd
+#define SWITCH_REGS_OFFSET 0x1800
+
+#define SWITCH_REGS_SIZE 0x8
+// macros: SWITCH_CTRL*
+#define SWITCH_REGS_SWITCH_CTRL_OFFSET 0x0
+// macros: FDB_AGING*
+#define SWITCH_REGS_FDB_AGING_OFFSET 0x1
+// macros: FDB_MAC_LO*
+#define SWITCH_REGS_FDB_MAC_LO_OFFSET 0x2
+// macros: FDB_MAC_HI*
+#define SWITCH_REGS_FDB_MAC_HI_OFFSET 0x3
+// macros: FDB_ENTRY_CTRL*
+#define SWITCH_REGS_FDB_ENTRY_OFFSET 0x4
+// macros: FDB_CMD*
+#define SWITCH_REGS_FDB_CMD_OFFSET 0x5
+// macros: FDB_STATUS*
+#define SWITCH_REGS_FDB_STATUS_OFFSET 0x6
+// macros: SWITCH_INFO*
+#define SWITCH_REGS_SWITCH_INFO_OFFSET 0x7
+
+
+#define SWITCH_CTRL_EN_POS 0
+#define SWITCH_CTRL_EN_FLAGS 0x1
+#define SWITCH_CTRL_EN(x) (((x) >> SWITCH_CTRL_EN_POS) & SWITCH_CTRL_EN_FLAGS)
+#define SET_SWITCH_CTRL_EN(x) (((x) & SWITCH_CTRL_EN_FLAGS) << SWITCH_CTRL_EN_POS)
+
+
+#define SWITCH_CTRL_LEARN_EN_POS 1
+#define SWITCH_CTRL_LEARN_EN_FLAGS 0x1
+#define SWITCH_CTRL_LEARN_EN(x) (((x) >> SWITCH_CTRL_LEARN_EN_POS) & SWITCH_CTRL_LEARN_EN_FLAGS)
+#define SET_SWITCH_CTRL_LEARN_EN(x) (((x) & SWITCH_CTRL_LEARN_EN_FLAGS) << SWITCH_CTRL_LEARN_EN_POS)
+
+
+#define SWITCH_INFO_PORTS_POS 0
+#define SWITCH_INFO_PORTS_FLAGS 0xf
+#define SWITCH_INFO_PORTS(x) (((x) >> SWITCH_INFO_PORTS_POS) & SWITCH_INFO_PORTS_FLAGS)
+#define SET_SWITCH_INFO_PORTS(x) (((x) & SWITCH_INFO_PORTS_FLAGS) << SWITCH_INFO_PORTS_POS)
+
+
+#define FDB_AGING_TIME_POS 0
+#define FDB_AGING_TIME_FLAGS 0xffffffff
+#define FDB_AGING_TIME(x) (((x) >> FDB_AGING_TIME_POS) & FDB_AGING_TIME_FLAGS)
+#define SET_FDB_AGING_TIME(x) (((x) & FDB_AGING_TIME_FLAGS) << FDB_AGING_TIME_POS)
+
+
+#define FDB_MAC_LO_MAC_POS 0
+#define FDB_MAC_LO_MAC_FLAGS 0xffffffff
+#define FDB_MAC_LO_MAC(x) (((x) >> FDB_MAC_LO_MAC_POS) & FDB_MAC_LO_MAC_FLAGS)
+#define SET_FDB_MAC_LO_MAC(x) (((x) & FDB_MAC_LO_MAC_FLAGS) << FDB_MAC_LO_MAC_POS)
+
+
+#define FDB_MAC_HI_MAC_POS 0
+#define FDB_MAC_HI_MAC_FLAGS 0xffff
+#define FDB_MAC_HI_MAC(x) (((x) >> FDB_MAC_HI_MAC_POS) & FDB_MAC_HI_MAC_FLAGS)
+#define SET_FDB_MAC_HI_MAC(x) (((x) & FDB_MAC_HI_MAC_FLAGS) << FDB_MAC_HI_MAC_POS)
+
+
+#define FDB_MAC_HI_VID_POS 16
+#define FDB_MAC_HI_VID_FLAGS 0xfff
+#define FDB_MAC_HI_VID(x) (((x) >> FDB_MAC_HI_VID_POS) & FDB_MAC_HI_VID_FLAGS)
+#define SET_FDB_MAC_HI_VID(x) (((x) & FDB_MAC_HI_VID_FLAGS) << FDB_MAC_HI_VID_POS)
+
+
+#define FDB_ENTRY_CTRL_PORT_POS 0
+#define FDB_ENTRY_CTRL_PORT_FLAGS 0x7
+#define FDB_ENTRY_CTRL_PORT(x) (((x) >> FDB_ENTRY_CTRL_PORT_POS) & FDB_ENTRY_CTRL_PORT_FLAGS)
+#define SET_FDB_ENTRY_CTRL_PORT(x) (((x) & FDB_ENTRY_CTRL_PORT_FLAGS) << FDB_ENTRY_CTRL_PORT_POS)
+
+
+#define FDB_ENTRY_CTRL_IS_STATIC_POS 8
+#define FDB_ENTRY_CTRL_IS_STATIC_FLAGS 0x1
+#define FDB_ENTRY_CTRL_IS_STATIC(x) (((x) >> FDB_ENTRY_CTRL_IS_STATIC_POS) & FDB_ENTRY_CTRL_IS_STATIC_FLAGS)
+#define SET_FDB_ENTRY_CTRL_IS_STATIC(x) (((x) & FDB_ENTRY_CTRL_IS_STATIC_FLAGS) << FDB_ENTRY_CTRL_IS_STATIC_POS)
+
+
+#define FDB_CMD_OP_POS 0
+#define FDB_CMD_OP_FLAGS 0x7
+//Enum: nop : 0x0; add : 0x1; del : 0x2; query : 0x3; flush : 0x4;
+#define FDB_CMD_OP(x) (((x) >> FDB_CMD_OP_POS) & FDB_CMD_OP_FLAGS)
+#define SET_FDB_CMD_OP(x) (((x) & FDB_CMD_OP_FLAGS) << FDB_CMD_OP_POS)
+
+
+#define FDB_STATUS_RESULT_POS 0
+#define FDB_STATUS_RESULT_FLAGS 0xf
+//Enum: ok : 0x0; not_found : 0x1; full : 0x2;
+#define FDB_STATUS_RESULT(x) (((x) >> FDB_STATUS_RESULT_POS) & FDB_STATUS_RESULT_FLAGS)
+#define SET_FDB_STATUS_RESULT(x) (((x) & FDB_STATUS_RESULT_FLAGS) << FDB_STATUS_RESULT_POS)
+
+
+#define FDB_STATUS_COUNT_POS 16
+#define FDB_STATUS_COUNT_FLAGS 0xffff
+#define FDB_STATUS_COUNT(x) (((x) >> FDB_STATUS_COUNT_POS) & FDB_STATUS_COUNT_FLAGS)
+#define SET_FDB_STATUS_COUNT(x) (((x) & FDB_STATUS_COUNT_FLAGS) << FDB_STATUS_COUNT_POS)
+
+#define SWITCH_MAX_PORTS 4
+#define SWITCH_HOST_PORT 4
+#define FDB_SIZE 1024
+#define IRQ_REGS_OFFSET 0x1808
+
+#define IRQ_REGS_SIZE 0x9
+// macros: IRQ_VEC_BITS*
+#define IRQ_REGS_VEC_MASK_LO_OFFSET 0x0
+// macros: IRQ_VEC_BITS*
+#define IRQ_REGS_VEC_MASK_HI_OFFSET 0x1
+// macros: IRQ_VEC_BITS*
+#define IRQ_REGS_VEC_PENDING_LO_OFFSET 0x2
+// macros: IRQ_VEC_BITS*
+#define IRQ_REGS_VEC_PENDING_HI_OFFSET 0x3
+// macros: IRQ_ERR_CAUSE*
+#define IRQ_REGS_ERR_MASK_OFFSET 0x4
+// macros: IRQ_ERR_CAUSE*
+#define IRQ_REGS_ERR_STATUS_OFFSET 0x5
+// macros: IRQ_LINK_CAUSE*
+#define IRQ_REGS_LINK_MASK_OFFSET 0x6
+// macros: IRQ_LINK_CAUSE*
+#define IRQ_REGS_LINK_STATUS_OFFSET 0x7
+// macros: IRQ_LINK_CAUSE*
+#define IRQ_REGS_LINK_UP_OFFSET 0x8
+
+
+#define IRQ_VEC_BITS_BITS_POS 0
+#define IRQ_VEC_BITS_BITS_FLAGS 0xffffffff
+#define IRQ_VEC_BITS_BITS(x) (((x) >> IRQ_VEC_BITS_BITS_POS) & IRQ_VEC_BITS_BITS_FLAGS)
+#define SET_IRQ_VEC_BITS_BITS(x) (((x) & IRQ_VEC_BITS_BITS_FLAGS) << IRQ_VEC_BITS_BITS_POS)
+
+
+#define IRQ_ERR_CAUSE_RX_PARSE_ERR_POS 0
+#define IRQ_ERR_CAUSE_RX_PARSE_ERR_FLAGS 0x1
+#define IRQ_ERR_CAUSE_RX_PARSE_ERR(x) (((x) >> IRQ_ERR_CAUSE_RX_PARSE_ERR_POS) & IRQ_ERR_CAUSE_RX_PARSE_ERR_FLAGS)
+#define SET_IRQ_ERR_CAUSE_RX_PARSE_ERR(x) (((x) & IRQ_ERR_CAUSE_RX_PARSE_ERR_FLAGS) << IRQ_ERR_CAUSE_RX_PARSE_ERR_POS)
+
+
+#define IRQ_ERR_CAUSE_RX_NO_QUEUE_POS 1
+#define IRQ_ERR_CAUSE_RX_NO_QUEUE_FLAGS 0x1
+#define IRQ_ERR_CAUSE_RX_NO_QUEUE(x) (((x) >> IRQ_ERR_CAUSE_RX_NO_QUEUE_POS) & IRQ_ERR_CAUSE_RX_NO_QUEUE_FLAGS)
+#define SET_IRQ_ERR_CAUSE_RX_NO_QUEUE(x) (((x) & IRQ_ERR_CAUSE_RX_NO_QUEUE_FLAGS) << IRQ_ERR_CAUSE_RX_NO_QUEUE_POS)
+
+
+#define IRQ_ERR_CAUSE_TX_TOO_LARGE_POS 2
+#define IRQ_ERR_CAUSE_TX_TOO_LARGE_FLAGS 0x1
+#define IRQ_ERR_CAUSE_TX_TOO_LARGE(x) (((x) >> IRQ_ERR_CAUSE_TX_TOO_LARGE_POS) & IRQ_ERR_CAUSE_TX_TOO_LARGE_FLAGS)
+#define SET_IRQ_ERR_CAUSE_TX_TOO_LARGE(x) (((x) & IRQ_ERR_CAUSE_TX_TOO_LARGE_FLAGS) << IRQ_ERR_CAUSE_TX_TOO_LARGE_POS)
+
+
+#define IRQ_ERR_CAUSE_TX_TOO_SMALL_POS 3
+#define IRQ_ERR_CAUSE_TX_TOO_SMALL_FLAGS 0x1
+#define IRQ_ERR_CAUSE_TX_TOO_SMALL(x) (((x) >> IRQ_ERR_CAUSE_TX_TOO_SMALL_POS) & IRQ_ERR_CAUSE_TX_TOO_SMALL_FLAGS)
+#define SET_IRQ_ERR_CAUSE_TX_TOO_SMALL(x) (((x) & IRQ_ERR_CAUSE_TX_TOO_SMALL_FLAGS) << IRQ_ERR_CAUSE_TX_TOO_SMALL_POS)
+
+
+#define IRQ_ERR_CAUSE_RX_ADDR_FILTER_POS 4
+#define IRQ_ERR_CAUSE_RX_ADDR_FILTER_FLAGS 0x1
+#define IRQ_ERR_CAUSE_RX_ADDR_FILTER(x) (((x) >> IRQ_ERR_CAUSE_RX_ADDR_FILTER_POS) & IRQ_ERR_CAUSE_RX_ADDR_FILTER_FLAGS)
+#define SET_IRQ_ERR_CAUSE_RX_ADDR_FILTER(x) (((x) & IRQ_ERR_CAUSE_RX_ADDR_FILTER_FLAGS) << IRQ_ERR_CAUSE_RX_ADDR_FILTER_POS)
+
+
+#define IRQ_LINK_CAUSE_PORTS_POS 0
+#define IRQ_LINK_CAUSE_PORTS_FLAGS 0xf
+#define IRQ_LINK_CAUSE_PORTS(x) (((x) >> IRQ_LINK_CAUSE_PORTS_POS) & IRQ_LINK_CAUSE_PORTS_FLAGS)
+#define SET_IRQ_LINK_CAUSE_PORTS(x) (((x) & IRQ_LINK_CAUSE_PORTS_FLAGS) << IRQ_LINK_CAUSE_PORTS_POS)
+
+
+#define IRQ_CAUSE_MAP_VEC0_POS 0
+#define IRQ_CAUSE_MAP_VEC0_FLAGS 0x3f
+#define IRQ_CAUSE_MAP_VEC0(x) (((x) >> IRQ_CAUSE_MAP_VEC0_POS) & IRQ_CAUSE_MAP_VEC0_FLAGS)
+#define SET_IRQ_CAUSE_MAP_VEC0(x) (((x) & IRQ_CAUSE_MAP_VEC0_FLAGS) << IRQ_CAUSE_MAP_VEC0_POS)
+
+
+#define IRQ_CAUSE_MAP_VEC1_POS 6
+#define IRQ_CAUSE_MAP_VEC1_FLAGS 0x3f
+#define IRQ_CAUSE_MAP_VEC1(x) (((x) >> IRQ_CAUSE_MAP_VEC1_POS) & IRQ_CAUSE_MAP_VEC1_FLAGS)
+#define SET_IRQ_CAUSE_MAP_VEC1(x) (((x) & IRQ_CAUSE_MAP_VEC1_FLAGS) << IRQ_CAUSE_MAP_VEC1_POS)
+
+
+#define IRQ_CAUSE_MAP_VEC2_POS 12
+#define IRQ_CAUSE_MAP_VEC2_FLAGS 0x3f
+#define IRQ_CAUSE_MAP_VEC2(x) (((x) >> IRQ_CAUSE_MAP_VEC2_POS) & IRQ_CAUSE_MAP_VEC2_FLAGS)
+#define SET_IRQ_CAUSE_MAP_VEC2(x) (((x) & IRQ_CAUSE_MAP_VEC2_FLAGS) << IRQ_CAUSE_MAP_VEC2_POS)
+
+
+#define IRQ_CAUSE_MAP_VEC3_POS 18
+#define IRQ_CAUSE_MAP_VEC3_FLAGS 0x3f
+#define IRQ_CAUSE_MAP_VEC3(x) (((x) >> IRQ_CAUSE_MAP_VEC3_POS) & IRQ_CAUSE_MAP_VEC3_FLAGS)
+#define SET_IRQ_CAUSE_MAP_VEC3(x) (((x) & IRQ_CAUSE_MAP_VEC3_FLAGS) << IRQ_CAUSE_MAP_VEC3_POS)
+
+
+#define IRQ_CAUSE_MAP_VEC4_POS 24
+#define IRQ_CAUSE_MAP_VEC4_FLAGS 0x3f
+#define IRQ_CAUSE_MAP_VEC4(x) (((x) >> IRQ_CAUSE_MAP_VEC4_POS) & IRQ_CAUSE_MAP_VEC4_FLAGS)
+#define SET_IRQ_CAUSE_MAP_VEC4(x) (((x) & IRQ_CAUSE_MAP_VEC4_FLAGS) << IRQ_CAUSE_MAP_VEC4_POS)
+
+#define IRQ_VECTORS 64
+#define IRQ_MAP_OFFSET 0x10
+#define IRQ_MAP_VEC_BITS 6
+#define IRQ_CAUSE_ERR 32
+#define IRQ_CAUSE_LINK 33
+#define IRQ_CAUSES 34
+#define RX_QUEUE_REGS_OFFSET 0x1848
 #define QUEUE_REGS_SIZE 0x20
 #define RX_RINGS_OFFSET 0x0
 #define TX_RINGS_OFFSET (RX_RINGS_OFFSET + RING_REGS_SIZE)
 #define RX_RING_OFFSET(base, name, i) ((base) + (QUEUE_REGS_SIZE * i) + RX_RINGS_OFFSET + RING_REGS_##name##_OFFSET)
 #define TX_RING_OFFSET(base, name, i) ((base) + (QUEUE_REGS_SIZE * i) + TX_RINGS_OFFSET + RING_REGS_##name##_OFFSET)
 
+#define RX_QUEUE_REGS_SIZE 0x5
+// macros: RX_HDR_SPLIT*
+#define RX_QUEUE_REGS_HDR_SPLIT_OFFSET 0x0
+// macros: RING_BASE*
+#define RX_QUEUE_REGS_HDR_BASE_LO_OFFSET 0x1
+// macros: RING_BASE*
+#define RX_QUEUE_REGS_HDR_BASE_HI_OFFSET 0x2
+// macros: RX_LRO*
+#define RX_QUEUE_REGS_LRO_OFFSET 0x3
+// macros: RX_LRO_TIMEOUT*
+#define RX_QUEUE_REGS_LRO_TIMEOUT_OFFSET 0x4
+
+
+#define RX_HDR_SPLIT_EN_POS 0
+#define RX_HDR_SPLIT_EN_FLAGS 0x1
+#define RX_HDR_SPLIT_EN(x) (((x) >> RX_HDR_SPLIT_EN_POS) & RX_HDR_SPLIT_EN_FLAGS)
+#define SET_RX_HDR_SPLIT_EN(x) (((x) & RX_HDR_SPLIT_EN_FLAGS) << RX_HDR_SPLIT_EN_POS)
+
+
+#define RX_HDR_SPLIT_HDR_SIZE_POS 16
+#define RX_HDR_SPLIT_HDR_SIZE_FLAGS 0xffff
+#define RX_HDR_SPLIT_HDR_SIZE(x) (((x) >> RX_HDR_SPLIT_HDR_SIZE_POS) & RX_HDR_SPLIT_HDR_SIZE_FLAGS)
+#define SET_RX_HDR_SPLIT_HDR_SIZE(x) (((x) & RX_HDR_SPLIT_HDR_SIZE_FLAGS) << RX_HDR_SPLIT_HDR_SIZE_POS)
+
+
+#define RX_LRO_EN_POS 0
+#define RX_LRO_EN_FLAGS 0x1
+#define RX_LRO_EN(x) (((x) >> RX_LRO_EN_POS) & RX_LRO_EN_FLAGS)
+#define SET_RX_LRO_EN(x) (((x) & RX_LRO_EN_FLAGS) << RX_LRO_EN_POS)
+
+
+#define RX_LRO_MAX_SIZE_POS 16
+#define RX_LRO_MAX_SIZE_FLAGS 0xffff
+#define RX_LRO_MAX_SIZE(x) (((x) >> RX_LRO_MAX_SIZE_POS) & RX_LRO_MAX_SIZE_FLAGS)
+#define SET_RX_LRO_MAX_SIZE(x) (((x) & RX_LRO_MAX_SIZE_FLAGS) << RX_LRO_MAX_SIZE_POS)
+
+
+#define RX_LRO_TIMEOUT_USECS_POS 0
+#define RX_LRO_TIMEOUT_USECS_FLAGS 0xffffffff
+#define RX_LRO_TIMEOUT_USECS(x) (((x) >> RX_LRO_TIMEOUT_USECS_POS) & RX_LRO_TIMEOUT_USECS_FLAGS)
+#define SET_RX_LRO_TIMEOUT_USECS(x) (((x) & RX_LRO_TIMEOUT_USECS_FLAGS) << RX_LRO_TIMEOUT_USECS_POS)
+
+#define RX_QUEUE_CFG_SIZE 0x8
+#define RX_QUEUE_OFFSET(base, name, i) ((base) + (RX_QUEUE_CFG_SIZE * i) + RX_QUEUE_REGS_##name##_OFFSET)
+
 #define ETHER_TYPE_FILTER_REGS_SIZE 0x1
 // macros: ETHER_TYPE_FILTER*
 #define ETHER_TYPE_FILTER_REGS_ET_FILTER_OFFSET 0x0
@@ -79,6 +599,12 @@
 #define TUPLES_FILTER_REGS_TP5_PORT_OFFSET 0x8
 // macros: TUPLES_FILTER_CTRL*
 #define TUPLES_FILTER_REGS_TP5_CTRL_OFFSET 0x9
+// macros: TUPLES_FILTER_PORT*
+#define TUPLES_FILTER_REGS_TP5_PORT_MAX_OFFSET 0xa
+// macros: TUPLES_FILTER_PREFIX*
+#define TUPLES_FILTER_REGS_TP5_PREFIX_OFFSET 0xb
+// macros: TUPLES_FILTER_TCP_FLAGS*
+#define TUPLES_FILTER_REGS_TP5_TCP_FLAGS_OFFSET 0xc
 
 
 #define TUPLES_FILTER_PORT_SRC_POS 0
@@ -111,6 +637,30 @@
 #define SET_TUPLES_FILTER_CTRL_IPV6(x) (((x) & TUPLES_FILTER_CTRL_IPV6_FLAGS) << TUPLES_FILTER_CTRL_IPV6_POS)
 
 
+#define TUPLES_FILTER_CTRL_INNER_POS 12
+#define TUPLES_FILTER_CTRL_INNER_FLAGS 0x1
+#define TUPLES_FILTER_CTRL_INNER(x) (((x) >> TUPLES_FILTER_CTRL_INNER_POS) & TUPLES_FILTER_CTRL_INNER_FLAGS)
+#define SET_TUPLES_FILTER_CTRL_INNER(x) (((x) & TUPLES_FILTER_CTRL_INNER_FLAGS) << TUPLES_FILTER_CTRL_INNER_POS)
+
+
+#define TUPLES_FILTER_CTRL_SRC_PORT_RANGE_POS 13
+#define TUPLES_FILTER_CTRL_SRC_PORT_RANGE_FLAGS 0x1
+#define TUPLES_FILTER_CTRL_SRC_PORT_RANGE(x) (((x) >> TUPLES_FILTER_CTRL_SRC_PORT_RANGE_POS) & TUPLES_FILTER_CTRL_SRC_PORT_RANGE_FLAGS)
+#define SET_TUPLES_FILTER_CTRL_SRC_PORT_RANGE(x) (((x) & TUPLES_FILTER_CTRL_SRC_PORT_RANGE_FLAGS) << TUPLES_FILTER_CTRL_SRC_PORT_RANGE_POS)
+
+
+#define TUPLES_FILTER_CTRL_DST_PORT_RANGE_POS 14
+#define TUPLES_FILTER_CTRL_DST_PORT_RANGE_FLAGS 0x1
+#define TUPLES_FILTER_CTRL_DST_PORT_RANGE(x) (((x) >> TUPLES_FILTER_CTRL_DST_PORT_RANGE_POS) & TUPLES_FILTER_CTRL_DST_PORT_RANGE_FLAGS)
+#define SET_TUPLES_FILTER_CTRL_DST_PORT_RANGE(x) (((x) & TUPLES_FILTER_CTRL_DST_PORT_RANGE_FLAGS) << TUPLES_FILTER_CTRL_DST_PORT_RANGE_POS)
+
+
+#define TUPLES_FILTER_CTRL_TCP_FLAGS_EN_POS 15
+#define TUPLES_FILTER_CTRL_TCP_FLAGS_EN_FLAGS 0x1
+#define TUPLES_FILTER_CTRL_TCP_FLAGS_EN(x) (((x) >> TUPLES_FILTER_CTRL_TCP_FLAGS_EN_POS) & TUPLES_FILTER_CTRL_TCP_FLAGS_EN_FLAGS)
+#define SET_TUPLES_FILTER_CTRL_TCP_FLAGS_EN(x) (((x) & TUPLES_FILTER_CTRL_TCP_FLAGS_EN_FLAGS) << TUPLES_FILTER_CTRL_TCP_FLAGS_EN_POS)
+
+
 #define TUPLES_FILTER_CTRL_QUEUE_ID_POS 16
 #define TUPLES_FILTER_CTRL_QUEUE_ID_FLAGS 0xff
 #define TUPLES_FILTER_CTRL_QUEUE_ID(x) (((x) >> TUPLES_FILTER_CTRL_QUEUE_ID_POS) & TUPLES_FILTER_CTRL_QUEUE_ID_FLAGS)
@@ -159,12 +709,146 @@
 #define TUPLES_FILTER_CTRL_EN(x) (((x) >> TUPLES_FILTER_CTRL_EN_POS) & TUPLES_FILTER_CTRL_EN_FLAGS)
 #define SET_TUPLES_FILTER_CTRL_EN(x) (((x) & TUPLES_FILTER_CTRL_EN_FLAGS) << TUPLES_FILTER_CTRL_EN_POS)
 
+
+#define TUPLES_FILTER_PREFIX_SRC_PREFIX_POS 0
+#define TUPLES_FILTER_PREFIX_SRC_PREFIX_FLAGS 0xff
+#define TUPLES_FILTER_PREFIX_SRC_PREFIX(x) (((x) >> TUPLES_FILTER_PREFIX_SRC_PREFIX_POS) & TUPLES_FILTER_PREFIX_SRC_PREFIX_FLAGS)
+#define SET_TUPLES_FILTER_PREFIX_SRC_PREFIX(x) (((x) & TUPLES_FILTER_PREFIX_SRC_PREFIX_FLAGS) << TUPLES_FILTER_PREFIX_SRC_PREFIX_POS)
+
+
+#define TUPLES_FILTER_PREFIX_DST_PREFIX_POS 8
+#define TUPLES_FILTER_PREFIX_DST_PREFIX_FLAGS 0xff
+#define TUPLES_FILTER_PREFIX_DST_PREFIX(x) (((x) >> TUPLES_FILTER_PREFIX_DST_PREFIX_POS) & TUPLES_FILTER_PREFIX_DST_PREFIX_FLAGS)
+#define SET_TUPLES_FILTER_PREFIX_DST_PREFIX(x) (((x) & TUPLES_FILTER_PREFIX_DST_PREFIX_FLAGS) << TUPLES_FILTER_PREFIX_DST_PREFIX_POS)
+
+
+#define TUPLES_FILTER_TCP_FLAGS_FLAGS_POS 0
+#define TUPLES_FILTER_TCP_FLAGS_FLAGS_FLAGS 0x1ff
+#define TUPLES_FILTER_TCP_FLAGS_FLAGS(x) (((x) >> TUPLES_FILTER_TCP_FLAGS_FLAGS_POS) & TUPLES_FILTER_TCP_FLAGS_FLAGS_FLAGS)
+#define SET_TUPLES_FILTER_TCP_FLAGS_FLAGS(x) (((x) & TUPLES_FILTER_TCP_FLAGS_FLAGS_FLAGS) << TUPLES_FILTER_TCP_FLAGS_FLAGS_POS)
+
+
+#define TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK_POS 16
+#define TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK_FLAGS 0x1ff
+#define TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK(x) (((x) >> TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK_POS) & TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK_FLAGS)
+#define SET_TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK(x) (((x) & TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK_FLAGS) << TUPLES_FILTER_TCP_FLAGS_FLAGS_MASK_POS)
+
+
+#define VLAN_FILTER_REGS_SIZE 0x1
+// macros: VLAN_FILTER*
+#define VLAN_FILTER_REGS_VLAN_FILTER_OFFSET 0x0
+
+
+#define VLAN_FILTER_VID_POS 0
+#define VLAN_FILTER_VID_FLAGS 0xfff
+#define VLAN_FILTER_VID(x) (((x) >> VLAN_FILTER_VID_POS) & VLAN_FILTER_VID_FLAGS)
+#define SET_VLAN_FILTER_VID(x) (((x) & VLAN_FILTER_VID_FLAGS) << VLAN_FILTER_VID_POS)
+
+
+#define VLAN_FILTER_PCP_POS 12
+#define VLAN_FILTER_PCP_FLAGS 0x7
+#define VLAN_FILTER_PCP(x) (((x) >> VLAN_FILTER_PCP_POS) & VLAN_FILTER_PCP_FLAGS)
+#define SET_VLAN_FILTER_PCP(x) (((x) & VLAN_FILTER_PCP_FLAGS) << VLAN_FILTER_PCP_POS)
+
+
+#define VLAN_FILTER_SVLAN_POS 15
+#define VLAN_FILTER_SVLAN_FLAGS 0x1
+#define VLAN_FILTER_SVLAN(x) (((x) >> VLAN_FILTER_SVLAN_POS) & VLAN_FILTER_SVLAN_FLAGS)
+#define SET_VLAN_FILTER_SVLAN(x) (((x) & VLAN_FILTER_SVLAN_FLAGS) << VLAN_FILTER_SVLAN_POS)
+
+
+#define VLAN_FILTER_QUEUE_ID_POS 16
+#define VLAN_FILTER_QUEUE_ID_FLAGS 0xff
+#define VLAN_FILTER_QUEUE_ID(x) (((x) >> VLAN_FILTER_QUEUE_ID_POS) & VLAN_FILTER_QUEUE_ID_FLAGS)
+#define SET_VLAN_FILTER_QUEUE_ID(x) (((x) & VLAN_FILTER_QUEUE_ID_FLAGS) << VLAN_FILTER_QUEUE_ID_POS)
+
+
+#define VLAN_FILTER_VID_MASK_POS 24
+#define VLAN_FILTER_VID_MASK_FLAGS 0x1
+#define VLAN_FILTER_VID_MASK(x) (((x) >> VLAN_FILTER_VID_MASK_POS) & VLAN_FILTER_VID_MASK_FLAGS)
+#define SET_VLAN_FILTER_VID_MASK(x) (((x) & VLAN_FILTER_VID_MASK_FLAGS) << VLAN_FILTER_VID_MASK_POS)
+
+
+#define VLAN_FILTER_PCP_MASK_POS 25
+#define VLAN_FILTER_PCP_MASK_FLAGS 0x1
+#define VLAN_FILTER_PCP_MASK(x) (((x) >> VLAN_FILTER_PCP_MASK_POS) & VLAN_FILTER_PCP_MASK_FLAGS)
+#define SET_VLAN_FILTER_PCP_MASK(x) (((x) & VLAN_FILTER_PCP_MASK_FLAGS) << VLAN_FILTER_PCP_MASK_POS)
+
+
+#define VLAN_FILTER_CONGESTION_ACTION_POS 29
+#define VLAN_FILTER_CONGESTION_ACTION_FLAGS 0x3
+//Enum: blocking : 0x0; drop : 0x1; default : 0x2;
+#define VLAN_FILTER_CONGESTION_ACTION(x) (((x) >> VLAN_FILTER_CONGESTION_ACTION_POS) & VLAN_FILTER_CONGESTION_ACTION_FLAGS)
+#define SET_VLAN_FILTER_CONGESTION_ACTION(x) (((x) & VLAN_FILTER_CONGESTION_ACTION_FLAGS) << VLAN_FILTER_CONGESTION_ACTION_POS)
+
+
+#define VLAN_FILTER_EN_POS 31
+#define VLAN_FILTER_EN_FLAGS 0x1
+#define VLAN_FILTER_EN(x) (((x) >> VLAN_FILTER_EN_POS) & VLAN_FILTER_EN_FLAGS)
+#define SET_VLAN_FILTER_EN(x) (((x) & VLAN_FILTER_EN_FLAGS) << VLAN_FILTER_EN_POS)
+
+
+#define ECPRI_FILTER_REGS_SIZE 0x2
+// macros: ECPRI_FILTER_CTRL*
+#define ECPRI_FILTER_REGS_ECPRI_FILTER_CTRL_OFFSET 0x0
+// macros: ECPRI_FILTER_RANGE*
+#define ECPRI_FILTER_REGS_ECPRI_FILTER_RANGE_OFFSET 0x1
+
+
+#define ECPRI_FILTER_CTRL_MSG_TYPE_POS 0
+#define ECPRI_FILTER_CTRL_MSG_TYPE_FLAGS 0xff
+#define ECPRI_FILTER_CTRL_MSG_TYPE(x) (((x) >> ECPRI_FILTER_CTRL_MSG_TYPE_POS) & ECPRI_FILTER_CTRL_MSG_TYPE_FLAGS)
+#define SET_ECPRI_FILTER_CTRL_MSG_TYPE(x) (((x) & ECPRI_FILTER_CTRL_MSG_TYPE_FLAGS) << ECPRI_FILTER_CTRL_MSG_TYPE_POS)
+
+
+#define ECPRI_FILTER_CTRL_MSG_TYPE_MASK_POS 8
+#define ECPRI_FILTER_CTRL_MSG_TYPE_MASK_FLAGS 0x1
+#define ECPRI_FILTER_CTRL_MSG_TYPE_MASK(x) (((x) >> ECPRI_FILTER_CTRL_MSG_TYPE_MASK_POS) & ECPRI_FILTER_CTRL_MSG_TYPE_MASK_FLAGS)
+#define SET_ECPRI_FILTER_CTRL_MSG_TYPE_MASK(x) (((x) & ECPRI_FILTER_CTRL_MSG_TYPE_MASK_FLAGS) << ECPRI_FILTER_CTRL_MSG_TYPE_MASK_POS)
+
+
+#define ECPRI_FILTER_CTRL_QUEUE_ID_POS 16
+#define ECPRI_FILTER_CTRL_QUEUE_ID_FLAGS 0xff
+#define ECPRI_FILTER_CTRL_QUEUE_ID(x) (((x) >> ECPRI_FILTER_CTRL_QUEUE_ID_POS) & ECPRI_FILTER_CTRL_QUEUE_ID_FLAGS)
+#define SET_ECPRI_FILTER_CTRL_QUEUE_ID(x) (((x) & ECPRI_FILTER_CTRL_QUEUE_ID_FLAGS) << ECPRI_FILTER_CTRL_QUEUE_ID_POS)
+
+
+#define ECPRI_FILTER_CTRL_CONGESTION_ACTION_POS 29
+#define ECPRI_FILTER_CTRL_CONGESTION_ACTION_FLAGS 0x3
+//Enum: blocking : 0x0; drop : 0x1; default : 0x2;
+#define ECPRI_FILTER_CTRL_CONGESTION_ACTION(x) (((x) >> ECPRI_FILTER_CTRL_CONGESTION_ACTION_POS) & ECPRI_FILTER_CTRL_CONGESTION_ACTION_FLAGS)
+#define SET_ECPRI_FILTER_CTRL_CONGESTION_ACTION(x) (((x) & ECPRI_FILTER_CTRL_CONGESTION_ACTION_FLAGS) << ECPRI_FILTER_CTRL_CONGESTION_ACTION_POS)
+
+
+#define ECPRI_FILTER_CTRL_EN_POS 31
+#define ECPRI_FILTER_CTRL_EN_FLAGS 0x1
+#define ECPRI_FILTER_CTRL_EN(x) (((x) >> ECPRI_FILTER_CTRL_EN_POS) & ECPRI_FILTER_CTRL_EN_FLAGS)
+#define SET_ECPRI_FILTER_CTRL_EN(x) (((x) & ECPRI_FILTER_CTRL_EN_FLAGS) << ECPRI_FILTER_CTRL_EN_POS)
+
+
+#define ECPRI_FILTER_RANGE_ID_MIN_POS 0
+#define ECPRI_FILTER_RANGE_ID_MIN_FLAGS 0xffff
+#define ECPRI_FILTER_RANGE_ID_MIN(x) (((x) >> ECPRI_FILTER_RANGE_ID_MIN_POS) & ECPRI_FILTER_RANGE_ID_MIN_FLAGS)
+#define SET_ECPRI_FILTER_RANGE_ID_MIN(x) (((x) & ECPRI_FILTER_RANGE_ID_MIN_FLAGS) << ECPRI_FILTER_RANGE_ID_MIN_POS)
+
+
+#define ECPRI_FILTER_RANGE_ID_MAX_POS 16
+#define ECPRI_FILTER_RANGE_ID_MAX_FLAGS 0xffff
+#define ECPRI_FILTER_RANGE_ID_MAX(x) (((x) >> ECPRI_FILTER_RANGE_ID_MAX_POS) & ECPRI_FILTER_RANGE_ID_MAX_FLAGS)
+#define SET_ECPRI_FILTER_RANGE_ID_MAX(x) (((x) & ECPRI_FILTER_RANGE_ID_MAX_FLAGS) << ECPRI_FILTER_RANGE_ID_MAX_POS)
+
 #define TP5_FILTERS_NUM 16
 #define TP5_FILTERS_OFFSET 0x0
 #define ET_FILTERS_NUM 4
 #define ET_FILTERS_OFFSET 0x200
+#define VLAN_FILTERS_NUM 8
+#define VLAN_FILTERS_OFFSET 0x240
+#define ECPRI_FILTERS_NUM 8
+#define ECPRI_FILTERS_OFFSET 0x250
 #define TP5_FILTER_OFFSET(base, name, i) ((base) + TP5_FILTERS_OFFSET + (TUPLES_FILETER_REGS_SIZE * i) + TUPLES_FILTER_REGS_##name##_OFFSET)
 #define ET_FILTER_OFFSET(base, i) ((base) + ET_FILTERS_OFFSET + (ETHER_TYPE_FILTER_REGS_SIZE * i))
+#define VLAN_FILTER_OFFSET(base, i) ((base) + VLAN_FILTERS_OFFSET + (VLAN_FILTER_REGS_SIZE * i))
+#define ECPRI_FILTER_OFFSET(base, name, i) ((base) + ECPRI_FILTERS_OFFSET + (ECPRI_FILTER_REGS_SIZE * i) + ECPRI_FILTER_REGS_##name##_OFFSET)
 
 #define DEFAULT_FILTER_REGS_SIZE 0x1
 // macros: DEFAULT_QUEUE*
@@ -190,12 +874,324 @@
 #define SET_DEFAULT_QUEUE_EN(x) (((x) & DEFAULT_QUEUE_EN_FLAGS) << DEFAULT_QUEUE_EN_POS)
 
 #define DEFAULT_Q_OFFSET(base) ((base) + 0x204)
+
+#define RSS_CFG_REGS_SIZE 0x10
+// macros: RSS_CTRL*
+#define RSS_CFG_REGS_RSS_CTRL_OFFSET 0x0
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY0_OFFSET 0x1
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY1_OFFSET 0x2
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY2_OFFSET 0x3
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY3_OFFSET 0x4
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY4_OFFSET 0x5
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY5_OFFSET 0x6
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY6_OFFSET 0x7
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY7_OFFSET 0x8
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY8_OFFSET 0x9
+// macros: RSS_KEY*
+#define RSS_CFG_REGS_RSS_KEY9_OFFSET 0xa
+
+
+#define RSS_CTRL_IPV4_POS 0
+#define RSS_CTRL_IPV4_FLAGS 0x1
+#define RSS_CTRL_IPV4(x) (((x) >> RSS_CTRL_IPV4_POS) & RSS_CTRL_IPV4_FLAGS)
+#define SET_RSS_CTRL_IPV4(x) (((x) & RSS_CTRL_IPV4_FLAGS) << RSS_CTRL_IPV4_POS)
+
+
+#define RSS_CTRL_TCP_IPV4_POS 1
+#define RSS_CTRL_TCP_IPV4_FLAGS 0x1
+#define RSS_CTRL_TCP_IPV4(x) (((x) >> RSS_CTRL_TCP_IPV4_POS) & RSS_CTRL_TCP_IPV4_FLAGS)
+#define SET_RSS_CTRL_TCP_IPV4(x) (((x) & RSS_CTRL_TCP_IPV4_FLAGS) << RSS_CTRL_TCP_IPV4_POS)
+
+
+#define RSS_CTRL_UDP_IPV4_POS 2
+#define RSS_CTRL_UDP_IPV4_FLAGS 0x1
+#define RSS_CTRL_UDP_IPV4(x) (((x) >> RSS_CTRL_UDP_IPV4_POS) & RSS_CTRL_UDP_IPV4_FLAGS)
+#define SET_RSS_CTRL_UDP_IPV4(x) (((x) & RSS_CTRL_UDP_IPV4_FLAGS) << RSS_CTRL_UDP_IPV4_POS)
+
+
+#define RSS_CTRL_IPV6_POS 3
+#define RSS_CTRL_IPV6_FLAGS 0x1
+#define RSS_CTRL_IPV6(x) (((x) >> RSS_CTRL_IPV6_POS) & RSS_CTRL_IPV6_FLAGS)
+#define SET_RSS_CTRL_IPV6(x) (((x) & RSS_CTRL_IPV6_FLAGS) << RSS_CTRL_IPV6_POS)
+
+
+#define RSS_CTRL_TCP_IPV6_POS 4
+#define RSS_CTRL_TCP_IPV6_FLAGS 0x1
+#define RSS_CTRL_TCP_IPV6(x) (((x) >> RSS_CTRL_TCP_IPV6_POS) & RSS_CTRL_TCP_IPV6_FLAGS)
+#define SET_RSS_CTRL_TCP_IPV6(x) (((x) & RSS_CTRL_TCP_IPV6_FLAGS) << RSS_CTRL_TCP_IPV6_POS)
+
+
+#define RSS_CTRL_UDP_IPV6_POS 5
+#define RSS_CTRL_UDP_IPV6_FLAGS 0x1
+#define RSS_CTRL_UDP_IPV6(x) (((x) >> RSS_CTRL_UDP_IPV6_POS) & RSS_CTRL_UDP_IPV6_FLAGS)
+#define SET_RSS_CTRL_UDP_IPV6(x) (((x) & RSS_CTRL_UDP_IPV6_FLAGS) << RSS_CTRL_UDP_IPV6_POS)
+
+
+#define RSS_CTRL_CONGESTION_ACTION_POS 29
+#define RSS_CTRL_CONGESTION_ACTION_FLAGS 0x3
+//Enum: blocking : 0x0; drop : 0x1; default : 0x2;
+#define RSS_CTRL_CONGESTION_ACTION(x) (((x) >> RSS_CTRL_CONGESTION_ACTION_POS) & RSS_CTRL_CONGESTION_ACTION_FLAGS)
+#define SET_RSS_CTRL_CONGESTION_ACTION(x) (((x) & RSS_CTRL_CONGESTION_ACTION_FLAGS) << RSS_CTRL_CONGESTION_ACTION_POS)
+
+
+#define RSS_CTRL_EN_POS 31
+#define RSS_CTRL_EN_FLAGS 0x1
+#define RSS_CTRL_EN(x) (((x) >> RSS_CTRL_EN_POS) & RSS_CTRL_EN_FLAGS)
+#define SET_RSS_CTRL_EN(x) (((x) & RSS_CTRL_EN_FLAGS) << RSS_CTRL_EN_POS)
+
+
+#define RSS_KEY_KEY_POS 0
+#define RSS_KEY_KEY_FLAGS 0xffffffff
+#define RSS_KEY_KEY(x) (((x) >> RSS_KEY_KEY_POS) & RSS_KEY_KEY_FLAGS)
+#define SET_RSS_KEY_KEY(x) (((x) & RSS_KEY_KEY_FLAGS) << RSS_KEY_KEY_POS)
+
+
+#define RSS_RETA_REGS_SIZE 0x1
+// macros: RSS_RETA*
+#define RSS_RETA_REGS_RSS_RETA_OFFSET 0x0
+
+
+#define RSS_RETA_QUEUE_ID0_POS 0
+#define RSS_RETA_QUEUE_ID0_FLAGS 0xff
+#define RSS_RETA_QUEUE_ID0(x) (((x) >> RSS_RETA_QUEUE_ID0_POS) & RSS_RETA_QUEUE_ID0_FLAGS)
+#define SET_RSS_RETA_QUEUE_ID0(x) (((x) & RSS_RETA_QUEUE_ID0_FLAGS) << RSS_RETA_QUEUE_ID0_POS)
+
+
+#define RSS_RETA_QUEUE_ID1_POS 8
+#define RSS_RETA_QUEUE_ID1_FLAGS 0xff
+#define RSS_RETA_QUEUE_ID1(x) (((x) >> RSS_RETA_QUEUE_ID1_POS) & RSS_RETA_QUEUE_ID1_FLAGS)
+#define SET_RSS_RETA_QUEUE_ID1(x) (((x) & RSS_RETA_QUEUE_ID1_FLAGS) << RSS_RETA_QUEUE_ID1_POS)
+
+
+#define RSS_RETA_QUEUE_ID2_POS 16
+#define RSS_RETA_QUEUE_ID2_FLAGS 0xff
+#define RSS_RETA_QUEUE_ID2(x) (((x) >> RSS_RETA_QUEUE_ID2_POS) & RSS_RETA_QUEUE_ID2_FLAGS)
+#define SET_RSS_RETA_QUEUE_ID2(x) (((x) & RSS_RETA_QUEUE_ID2_FLAGS) << RSS_RETA_QUEUE_ID2_POS)
+
+
+#define RSS_RETA_QUEUE_ID3_POS 24
+#define RSS_RETA_QUEUE_ID3_FLAGS 0xff
+#define RSS_RETA_QUEUE_ID3(x) (((x) >> RSS_RETA_QUEUE_ID3_POS) & RSS_RETA_QUEUE_ID3_FLAGS)
+#define SET_RSS_RETA_QUEUE_ID3(x) (((x) & RSS_RETA_QUEUE_ID3_FLAGS) << RSS_RETA_QUEUE_ID3_POS)
+
+#define RSS_KEY_SIZE 40
+#define RSS_RETA_SIZE 128
+#define RSS_RETA_OFFSET 0x10
+#define RSS_CFG_OFFSET(base, name) ((base) + RSS_CFG_REGS_##name##_OFFSET)
+#define RSS_RETA_REG_OFFSET(base, i) ((base) + RSS_RETA_OFFSET + (i) / 4)
+#define RSS_OFFSET(base) ((base) + 0x205)
+
+#define TUNNEL_REGS_SIZE 0x1
+// macros: TUNNEL_CTRL*
+#define TUNNEL_REGS_TUNNEL_CTRL_OFFSET 0x0
+
+
+#define TUNNEL_CTRL_VXLAN_EN_POS 0
+#define TUNNEL_CTRL_VXLAN_EN_FLAGS 0x1
+#define TUNNEL_CTRL_VXLAN_EN(x) (((x) >> TUNNEL_CTRL_VXLAN_EN_POS) & TUNNEL_CTRL_VXLAN_EN_FLAGS)
+#define SET_TUNNEL_CTRL_VXLAN_EN(x) (((x) & TUNNEL_CTRL_VXLAN_EN_FLAGS) << TUNNEL_CTRL_VXLAN_EN_POS)
+
+
+#define TUNNEL_CTRL_GRE_EN_POS 1
+#define TUNNEL_CTRL_GRE_EN_FLAGS 0x1
+#define TUNNEL_CTRL_GRE_EN(x) (((x) >> TUNNEL_CTRL_GRE_EN_POS) & TUNNEL_CTRL_GRE_EN_FLAGS)
+#define SET_TUNNEL_CTRL_GRE_EN(x) (((x) & TUNNEL_CTRL_GRE_EN_FLAGS) << TUNNEL_CTRL_GRE_EN_POS)
+
+
+#define TUNNEL_CTRL_IPIP_EN_POS 2
+#define TUNNEL_CTRL_IPIP_EN_FLAGS 0x1
+#define TUNNEL_CTRL_IPIP_EN(x) (((x) >> TUNNEL_CTRL_IPIP_EN_POS) & TUNNEL_CTRL_IPIP_EN_FLAGS)
+#define SET_TUNNEL_CTRL_IPIP_EN(x) (((x) & TUNNEL_CTRL_IPIP_EN_FLAGS) << TUNNEL_CTRL_IPIP_EN_POS)
+
+
+#define TUNNEL_CTRL_VXLAN_PORT_POS 16
+#define TUNNEL_CTRL_VXLAN_PORT_FLAGS 0xffff
+#define TUNNEL_CTRL_VXLAN_PORT(x) (((x) >> TUNNEL_CTRL_VXLAN_PORT_POS) & TUNNEL_CTRL_VXLAN_PORT_FLAGS)
+#define SET_TUNNEL_CTRL_VXLAN_PORT(x) (((x) & TUNNEL_CTRL_VXLAN_PORT_FLAGS) << TUNNEL_CTRL_VXLAN_PORT_POS)
+
+#define TUNNEL_OFFSET(base) ((base) + 0x235)
+
+#define ECPRI_REGS_SIZE 0x1
+// macros: ECPRI_CTRL*
+#define ECPRI_REGS_ECPRI_CTRL_OFFSET 0x0
+
+
+#define ECPRI_CTRL_UDP_PORT_POS 0
+#define ECPRI_CTRL_UDP_PORT_FLAGS 0xffff
+#define ECPRI_CTRL_UDP_PORT(x) (((x) >> ECPRI_CTRL_UDP_PORT_POS) & ECPRI_CTRL_UDP_PORT_FLAGS)
+#define SET_ECPRI_CTRL_UDP_PORT(x) (((x) & ECPRI_CTRL_UDP_PORT_FLAGS) << ECPRI_CTRL_UDP_PORT_POS)
+
+
+#define ECPRI_CTRL_UDP_EN_POS 31
+#define ECPRI_CTRL_UDP_EN_FLAGS 0x1
+#define ECPRI_CTRL_UDP_EN(x) (((x) >> ECPRI_CTRL_UDP_EN_POS) & ECPRI_CTRL_UDP_EN_FLAGS)
+#define SET_ECPRI_CTRL_UDP_EN(x) (((x) & ECPRI_CTRL_UDP_EN_FLAGS) << ECPRI_CTRL_UDP_EN_POS)
+
+#define ECPRI_OFFSET(base) ((base) + 0x236)
+
+#define FLOW_TABLE_REGS_SIZE 0x10
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_SRC0_OFFSET 0x0
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_SRC1_OFFSET 0x1
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_SRC2_OFFSET 0x2
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_SRC3_OFFSET 0x3
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_DST0_OFFSET 0x4
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_DST1_OFFSET 0x5
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_DST2_OFFSET 0x6
+// macros: TUPLES_FILTER_ADDRESS*
+#define FLOW_TABLE_REGS_FLOW_DST3_OFFSET 0x7
+// macros: TUPLES_FILTER_PORT*
+#define FLOW_TABLE_REGS_FLOW_PORT_OFFSET 0x8
+// macros: FLOW_KEY_CTRL*
+#define FLOW_TABLE_REGS_FLOW_KEY_OFFSET 0x9
+// macros: FLOW_ACTION*
+#define FLOW_TABLE_REGS_FLOW_ACTION_OFFSET 0xa
+// macros: FLOW_CMD*
+#define FLOW_TABLE_REGS_FLOW_CMD_OFFSET 0xb
+// macros: FLOW_STATUS*
+#define FLOW_TABLE_REGS_FLOW_STATUS_OFFSET 0xc
+// macros: FLOW_COUNTER*
+#define FLOW_TABLE_REGS_FLOW_HITS_OFFSET 0xd
+// macros: FLOW_COUNTER*
+#define FLOW_TABLE_REGS_FLOW_TS_LO_OFFSET 0xe
+// macros: FLOW_COUNTER*
+#define FLOW_TABLE_REGS_FLOW_TS_HI_OFFSET 0xf
+
+
+#define FLOW_KEY_CTRL_PROTOCOL_POS 0
+#define FLOW_KEY_CTRL_PROTOCOL_FLAGS 0xff
+#define FLOW_KEY_CTRL_PROTOCOL(x) (((x) >> FLOW_KEY_CTRL_PROTOCOL_POS) & FLOW_KEY_CTRL_PROTOCOL_FLAGS)
+#define SET_FLOW_KEY_CTRL_PROTOCOL(x) (((x) & FLOW_KEY_CTRL_PROTOCOL_FLAGS) << FLOW_KEY_CTRL_PROTOCOL_POS)
+
+
+#define FLOW_KEY_CTRL_IPV6_POS 8
+#define FLOW_KEY_CTRL_IPV6_FLAGS 0x1
+#define FLOW_KEY_CTRL_IPV6(x) (((x) >> FLOW_KEY_CTRL_IPV6_POS) & FLOW_KEY_CTRL_IPV6_FLAGS)
+#define SET_FLOW_KEY_CTRL_IPV6(x) (((x) & FLOW_KEY_CTRL_IPV6_FLAGS) << FLOW_KEY_CTRL_IPV6_POS)
+
+
+#define FLOW_ACTION_QUEUE_ID_POS 16
+#define FLOW_ACTION_QUEUE_ID_FLAGS 0xff
+#define FLOW_ACTION_QUEUE_ID(x) (((x) >> FLOW_ACTION_QUEUE_ID_POS) & FLOW_ACTION_QUEUE_ID_FLAGS)
+#define SET_FLOW_ACTION_QUEUE_ID(x) (((x) & FLOW_ACTION_QUEUE_ID_FLAGS) << FLOW_ACTION_QUEUE_ID_POS)
+
+
+#define FLOW_ACTION_CONGESTION_ACTION_POS 29
+#define FLOW_ACTION_CONGESTION_ACTION_FLAGS 0x3
+//Enum: blocking : 0x0; drop : 0x1; default : 0x2;
+#define FLOW_ACTION_CONGESTION_ACTION(x) (((x) >> FLOW_ACTION_CONGESTION_ACTION_POS) & FLOW_ACTION_CONGESTION_ACTION_FLAGS)
+#define SET_FLOW_ACTION_CONGESTION_ACTION(x) (((x) & FLOW_ACTION_CONGESTION_ACTION_FLAGS) << FLOW_ACTION_CONGESTION_ACTION_POS)
+
+
+#define FLOW_CMD_OP_POS 0
+#define FLOW_CMD_OP_FLAGS 0x3
+//Enum: nop : 0x0; add : 0x1; del : 0x2; query : 0x3;
+#define FLOW_CMD_OP(x) (((x) >> FLOW_CMD_OP_POS) & FLOW_CMD_OP_FLAGS)
+#define SET_FLOW_CMD_OP(x) (((x) & FLOW_CMD_OP_FLAGS) << FLOW_CMD_OP_POS)
+
+
+#define FLOW_STATUS_RESULT_POS 0
+#define FLOW_STATUS_RESULT_FLAGS 0xf
+//Enum: ok : 0x0; not_found : 0x1; exists : 0x2; full : 0x3;
+#define FLOW_STATUS_RESULT(x) (((x) >> FLOW_STATUS_RESULT_POS) & FLOW_STATUS_RESULT_FLAGS)
+#define SET_FLOW_STATUS_RESULT(x) (((x) & FLOW_STATUS_RESULT_FLAGS) << FLOW_STATUS_RESULT_POS)
+
+
+#define FLOW_STATUS_INDEX_POS 16
+#define FLOW_STATUS_INDEX_FLAGS 0xffff
+#define FLOW_STATUS_INDEX(x) (((x) >> FLOW_STATUS_INDEX_POS) & FLOW_STATUS_INDEX_FLAGS)
+#define SET_FLOW_STATUS_INDEX(x) (((x) & FLOW_STATUS_INDEX_FLAGS) << FLOW_STATUS_INDEX_POS)
+
+
+#define FLOW_COUNTER_CNT_POS 0
+#define FLOW_COUNTER_CNT_FLAGS 0xffffffff
+#define FLOW_COUNTER_CNT(x) (((x) >> FLOW_COUNTER_CNT_POS) & FLOW_COUNTER_CNT_FLAGS)
+#define SET_FLOW_COUNTER_CNT(x) (((x) & FLOW_COUNTER_CNT_FLAGS) << FLOW_COUNTER_CNT_POS)
+
+#define FLOW_TABLE_SIZE 4096
+#define FLOW_TABLE_WAYS 4
+#define FLOW_TABLE_OFFSET(base, name) ((base) + 0x260 + FLOW_TABLE_REGS_##name##_OFFSET)
+
+#define TX_SHAPER_REGS_SIZE 0x2
+// macros: TX_SHAPER_RATE*
+#define TX_SHAPER_REGS_SHAPER_RATE_OFFSET 0x0
+// macros: TX_SHAPER_BURST*
+#define TX_SHAPER_REGS_SHAPER_BURST_OFFSET 0x1
+
+
+#define TX_SHAPER_RATE_RATE_POS 0
+#define TX_SHAPER_RATE_RATE_FLAGS 0xffffff
+#define TX_SHAPER_RATE_RATE(x) (((x) >> TX_SHAPER_RATE_RATE_POS) & TX_SHAPER_RATE_RATE_FLAGS)
+#define SET_TX_SHAPER_RATE_RATE(x) (((x) & TX_SHAPER_RATE_RATE_FLAGS) << TX_SHAPER_RATE_RATE_POS)
+
+
+#define TX_SHAPER_RATE_EN_POS 31
+#define TX_SHAPER_RATE_EN_FLAGS 0x1
+#define TX_SHAPER_RATE_EN(x) (((x) >> TX_SHAPER_RATE_EN_POS) & TX_SHAPER_RATE_EN_FLAGS)
+#define SET_TX_SHAPER_RATE_EN(x) (((x) & TX_SHAPER_RATE_EN_FLAGS) << TX_SHAPER_RATE_EN_POS)
+
+
+#define TX_SHAPER_BURST_BURST_POS 0
+#define TX_SHAPER_BURST_BURST_FLAGS 0xffffff
+#define TX_SHAPER_BURST_BURST(x) (((x) >> TX_SHAPER_BURST_BURST_POS) & TX_SHAPER_BURST_BURST_FLAGS)
+#define SET_TX_SHAPER_BURST_BURST(x) (((x) & TX_SHAPER_BURST_BURST_FLAGS) << TX_SHAPER_BURST_BURST_POS)
+
+#define TX_SHAPER_OFFSET(base, name, i) ((base) + 0x0 + (i) * 2 + TX_SHAPER_REGS_##name##_OFFSET)
+#define TX_PORT_SHAPER_OFFSET(base, name) ((base) + 0x20 + TX_SHAPER_REGS_##name##_OFFSET)
 #define TP5_FILTER(name, i) TP5_FILTER_OFFSET(RX_REGS_OFFSET, name, i)
 #define ET_FILTER(i) ET_FILTER_OFFSET(RX_REGS_OFFSET, i)
+#define VLAN_FILTER(i) VLAN_FILTER_OFFSET(RX_REGS_OFFSET, i)
+#define ECPRI_FILTER(name, i) ECPRI_FILTER_OFFSET(RX_REGS_OFFSET, name, i)
 #define DEFAULT_Q DEFAULT_Q_OFFSET(RX_REGS_OFFSET)
+#define RSS_CFG(name) RSS_CFG_OFFSET(RSS_OFFSET(RX_REGS_OFFSET), name)
+#define RSS_RETA_REG(i) RSS_RETA_REG_OFFSET(RSS_OFFSET(RX_REGS_OFFSET), i)
+#define TUNNEL_CTRL TUNNEL_OFFSET(RX_REGS_OFFSET)
+#define ECPRI_CTRL ECPRI_OFFSET(RX_REGS_OFFSET)
+#define FLOW_TABLE(name) FLOW_TABLE_OFFSET(RX_REGS_OFFSET, name)
+#define TX_SHAPER(name, i) TX_SHAPER_OFFSET(TX_REGS_OFFSET, name, i)
+#define TX_PORT_SHAPER(name) TX_PORT_SHAPER_OFFSET(TX_REGS_OFFSET, name)
 #define RX_RING(name, i) RX_RING_OFFSET(QUEUE_REGS_OFFSET, name, i)
 #define TX_RING(name, i) TX_RING_OFFSET(QUEUE_REGS_OFFSET, name, i)
+#define RX_QUEUE(name, i) RX_QUEUE_OFFSET(RX_QUEUE_REGS_OFFSET, name, i)
 #define RX_EN (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_RX_EN_OFFSET)
 #define TX_EN (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_EN_OFFSET)
+#define RX_VLAN_STRIP (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_RX_VLAN_STRIP_OFFSET)
+#define TX_ARB (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_ARB_OFFSET)
+#define TX_ARB_PRI(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_ARB_PRI0_OFFSET + (i) / 8)
+#define TX_ARB_WEIGHT(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_ARB_WEIGHT0_OFFSET + (i) / 4)
+#define CLOCK_CTRL (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_CLOCK_CTRL_OFFSET)
+#define STATS_CTRL (STATS_REGS_OFFSET + STATS_CTRL_REGS_STATS_CTRL_OFFSET)
+#define STATS_LO(name) (STATS_REGS_OFFSET + STATS_##name##_LO_OFFSET)
+#define STATS_HI(name) (STATS_REGS_OFFSET + STATS_##name##_HI_OFFSET)
+#define STATS_RX_HIST_LO(i) (STATS_REGS_OFFSET + STATS_RX_HIST_OFFSET + (i) * STATS_COUNTER_SIZE)
+#define STATS_RX_HIST_HI(i) (STATS_RX_HIST_LO(i) + 1)
+#define STATS_TX_HIST_LO(i) (STATS_REGS_OFFSET + STATS_TX_HIST_OFFSET + (i) * STATS_COUNTER_SIZE)
+#define STATS_TX_HIST_HI(i) (STATS_TX_HIST_LO(i) + 1)
+#define STATS_QUEUE_LO(name, i) (STATS_REGS_OFFSET + STATS_QUEUE_OFFSET + (i) * STATS_QUEUE_SIZE + STATS_QUEUE_##name##_LO_OFFSET)
+#define STATS_QUEUE_HI(name, i) (STATS_REGS_OFFSET + STATS_QUEUE_OFFSET + (i) * STATS_QUEUE_SIZE + STATS_QUEUE_##name##_HI_OFFSET)
+#define CLOCK_LO (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_CLOCK_LO_OFFSET)
+#define CLOCK_HI (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_CLOCK_HI_OFFSET)
+#define SWITCH(name) (SWITCH_REGS_OFFSET + SWITCH_REGS_##name##_OFFSET)
+#define IRQ(name) (IRQ_REGS_OFFSET + IRQ_REGS_##name##_OFFSET)
+#define IRQ_MAP(cause) (IRQ_REGS_OFFSET + IRQ_MAP_OFFSET + (cause))
+#define RX_MODE (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_RX_MODE_OFFSET)
+#define MC_HASH(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_MC_HASH_LO_OFFSET + (i))
+#define STATION_LO(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_STATION_LO0_OFFSET + (i) * 2)
+#define STATION_HI(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_STATION_HI0_OFFSET + (i) * 2)
 
 #endif
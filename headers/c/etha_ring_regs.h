@@ -28,10 +28,14 @@
 #define RING_REGS_STATUS_OFFSET 0x9
 // macros: RING_STATUS*
 #define RING_REGS_INT_MASK_OFFSET 0xa
+// macros: RING_INT_STATUS*
+#define RING_REGS_INT_STATUS_OFFSET 0xb
 // macros: RING_CTRL*
 #define RING_REGS_CTRL_OFFSET 0xc
 // macros: RING_SIZE*
 #define RING_REGS_MEM_SIZE_OFFSET 0xd
+// macros: RING_INT_COAL*
+#define RING_REGS_INT_COAL_OFFSET 0xe
 
 
 
@@ -82,6 +86,58 @@
 #define SET_RING_STATUS_ALMOST_EMPTY(x) (((x) & RING_STATUS_ALMOST_EMPTY_FLAGS) << RING_STATUS_ALMOST_EMPTY_POS)
 
 
+#define RING_STATUS_DONE_POS 4
+#define RING_STATUS_DONE_FLAGS 0x1
+#define RING_STATUS_DONE(x) (((x) >> RING_STATUS_DONE_POS) & RING_STATUS_DONE_FLAGS)
+#define SET_RING_STATUS_DONE(x) (((x) & RING_STATUS_DONE_FLAGS) << RING_STATUS_DONE_POS)
+
+
+
+
+#define RING_INT_STATUS_FULL_POS 0
+#define RING_INT_STATUS_FULL_FLAGS 0x1
+#define RING_INT_STATUS_FULL(x) (((x) >> RING_INT_STATUS_FULL_POS) & RING_INT_STATUS_FULL_FLAGS)
+#define SET_RING_INT_STATUS_FULL(x) (((x) & RING_INT_STATUS_FULL_FLAGS) << RING_INT_STATUS_FULL_POS)
+
+
+#define RING_INT_STATUS_EMPTY_POS 1
+#define RING_INT_STATUS_EMPTY_FLAGS 0x1
+#define RING_INT_STATUS_EMPTY(x) (((x) >> RING_INT_STATUS_EMPTY_POS) & RING_INT_STATUS_EMPTY_FLAGS)
+#define SET_RING_INT_STATUS_EMPTY(x) (((x) & RING_INT_STATUS_EMPTY_FLAGS) << RING_INT_STATUS_EMPTY_POS)
+
+
+#define RING_INT_STATUS_ALMOST_FULL_POS 2
+#define RING_INT_STATUS_ALMOST_FULL_FLAGS 0x1
+#define RING_INT_STATUS_ALMOST_FULL(x) (((x) >> RING_INT_STATUS_ALMOST_FULL_POS) & RING_INT_STATUS_ALMOST_FULL_FLAGS)
+#define SET_RING_INT_STATUS_ALMOST_FULL(x) (((x) & RING_INT_STATUS_ALMOST_FULL_FLAGS) << RING_INT_STATUS_ALMOST_FULL_POS)
+
+
+#define RING_INT_STATUS_ALMOST_EMPTY_POS 3
+#define RING_INT_STATUS_ALMOST_EMPTY_FLAGS 0x1
+#define RING_INT_STATUS_ALMOST_EMPTY(x) (((x) >> RING_INT_STATUS_ALMOST_EMPTY_POS) & RING_INT_STATUS_ALMOST_EMPTY_FLAGS)
+#define SET_RING_INT_STATUS_ALMOST_EMPTY(x) (((x) & RING_INT_STATUS_ALMOST_EMPTY_FLAGS) << RING_INT_STATUS_ALMOST_EMPTY_POS)
+
+
+#define RING_INT_STATUS_DONE_POS 4
+#define RING_INT_STATUS_DONE_FLAGS 0x1
+#define RING_INT_STATUS_DONE(x) (((x) >> RING_INT_STATUS_DONE_POS) & RING_INT_STATUS_DONE_FLAGS)
+#define SET_RING_INT_STATUS_DONE(x) (((x) & RING_INT_STATUS_DONE_FLAGS) << RING_INT_STATUS_DONE_POS)
+
+
+
+
+#define RING_INT_COAL_MAX_FRAMES_POS 0
+#define RING_INT_COAL_MAX_FRAMES_FLAGS 0xffff
+#define RING_INT_COAL_MAX_FRAMES(x) (((x) >> RING_INT_COAL_MAX_FRAMES_POS) & RING_INT_COAL_MAX_FRAMES_FLAGS)
+#define SET_RING_INT_COAL_MAX_FRAMES(x) (((x) & RING_INT_COAL_MAX_FRAMES_FLAGS) << RING_INT_COAL_MAX_FRAMES_POS)
+
+
+#define RING_INT_COAL_USECS_POS 16
+#define RING_INT_COAL_USECS_FLAGS 0xffff
+#define RING_INT_COAL_USECS(x) (((x) >> RING_INT_COAL_USECS_POS) & RING_INT_COAL_USECS_FLAGS)
+#define SET_RING_INT_COAL_USECS(x) (((x) & RING_INT_COAL_USECS_FLAGS) << RING_INT_COAL_USECS_POS)
+
+
 
 
 #define RING_CTRL_ENABLE_POS 0
@@ -7,5 +7,6 @@
 #define QUEUE_NUM 1
 #define QUEUE_REGS_OFFSET 0x0
 #define RING(name, i) (QUEUE_REGS_OFFSET + (RING_REGS_SIZE * i) + RING_REGS_##name##_OFFSET)
+// RING INT_STATUS and INT_COAL are not decoded, irqs are raised by STATUS & INT_MASK
 
 #endif
@@ -1,7 +1,18 @@
 pub trait Arbiter {
-    fn arbit<T: Clone>(&mut self, req: &[Option<T>]) -> Option<(usize, T)>;
+    fn arbit<T: Clone>(&mut self, req: &[Option<T>]) -> Option<(usize, T)> {
+        self.arbit_by(req, |_| 1)
+    }
+    //cost is only used by byte based arbiters
+    fn arbit_by<T: Clone, F: Fn(&T) -> usize>(
+        &mut self,
+        req: &[Option<T>],
+        cost: F,
+    ) -> Option<(usize, T)>;
 }
 
+pub const ARB_PRIORITIES: usize = 8;
+pub const DWRR_QUANTUM: usize = 256;
+
 pub struct RRArbiter<const N: usize> {
     next: usize,
 }
@@ -20,7 +31,11 @@ impl<const N: usize> RRArbiter<N> {
 }
 
 impl<const N: usize> Arbiter for RRArbiter<N> {
-    fn arbit<T: Clone>(&mut self, req: &[Option<T>]) -> Option<(usize, T)> {
+    fn arbit_by<T: Clone, F: Fn(&T) -> usize>(
+        &mut self,
+        req: &[Option<T>],
+        _: F,
+    ) -> Option<(usize, T)> {
         let mut req_v: Vec<Option<T>> = req[..N].to_vec();
         req_v.rotate_left(self.next);
         for (i, req) in req_v.iter_mut().enumerate() {
@@ -33,3 +48,205 @@ impl<const N: usize> Arbiter for RRArbiter<N> {
         None
     }
 }
+
+//requests of the highest priority group only, higher value is higher priority
+fn highest_group<T: Clone, const N: usize>(
+    req: &[Option<T>],
+    priority: &[usize; N],
+) -> Option<(usize, Vec<Option<T>>)> {
+    let pri = (0..N)
+        .filter(|i| req[*i].is_some())
+        .map(|i| priority[i])
+        .max()?;
+    let group = (0..N)
+        .map(|i| req[i].clone().filter(|_| priority[i] == pri))
+        .collect();
+    Some((pri, group))
+}
+
+//round robin within the same priority
+pub struct SPArbiter<const N: usize> {
+    priority: [usize; N],
+    groups: [RRArbiter<N>; ARB_PRIORITIES],
+}
+
+impl<const N: usize> SPArbiter<N> {
+    pub fn new() -> Self {
+        SPArbiter {
+            priority: [0; N],
+            groups: array_init::array_init(|_| RRArbiter::new()),
+        }
+    }
+    pub fn set_priority(&mut self, i: usize, priority: usize) {
+        self.priority[i] = std::cmp::min(priority, ARB_PRIORITIES - 1);
+    }
+}
+
+impl<const N: usize> Default for SPArbiter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Arbiter for SPArbiter<N> {
+    fn arbit_by<T: Clone, F: Fn(&T) -> usize>(
+        &mut self,
+        req: &[Option<T>],
+        cost: F,
+    ) -> Option<(usize, T)> {
+        let (pri, group) = highest_group(req, &self.priority)?;
+        self.groups[pri].arbit_by(&group, cost)
+    }
+}
+
+//deficit weighted round robin, each visit adds weight * DWRR_QUANTUM bytes
+pub struct DwrrArbiter<const N: usize> {
+    weight: [usize; N],
+    deficit: [usize; N],
+    next: usize,
+    visited: bool,
+}
+
+impl<const N: usize> DwrrArbiter<N> {
+    pub fn new() -> Self {
+        DwrrArbiter {
+            weight: [1; N],
+            deficit: [0; N],
+            next: 0,
+            visited: false,
+        }
+    }
+    pub fn set_weight(&mut self, i: usize, weight: usize) {
+        self.weight[i] = std::cmp::max(weight, 1);
+    }
+    fn update(&mut self) {
+        self.next = (self.next + 1) % N;
+        self.visited = false;
+    }
+}
+
+impl<const N: usize> Default for DwrrArbiter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Arbiter for DwrrArbiter<N> {
+    fn arbit_by<T: Clone, F: Fn(&T) -> usize>(
+        &mut self,
+        req: &[Option<T>],
+        cost: F,
+    ) -> Option<(usize, T)> {
+        if req[..N].iter().all(|r| r.is_none()) {
+            return None;
+        }
+        loop {
+            let i = self.next;
+            match &req[i] {
+                //idle queues do not accumulate credits
+                None => self.deficit[i] = 0,
+                Some(d) => {
+                    if !self.visited {
+                        self.deficit[i] += self.weight[i] * DWRR_QUANTUM;
+                        self.visited = true;
+                    }
+                    let c = cost(d);
+                    if self.deficit[i] >= c {
+                        self.deficit[i] -= c;
+                        return Some((i, d.clone()));
+                    }
+                }
+            }
+            self.update();
+        }
+    }
+}
+
+//strict priority between groups, dwrr within the same group
+pub struct HybridArbiter<const N: usize> {
+    priority: [usize; N],
+    groups: [DwrrArbiter<N>; ARB_PRIORITIES],
+}
+
+impl<const N: usize> HybridArbiter<N> {
+    pub fn new() -> Self {
+        HybridArbiter {
+            priority: [0; N],
+            groups: array_init::array_init(|_| DwrrArbiter::new()),
+        }
+    }
+    pub fn set_priority(&mut self, i: usize, priority: usize) {
+        self.priority[i] = std::cmp::min(priority, ARB_PRIORITIES - 1);
+    }
+    pub fn set_weight(&mut self, i: usize, weight: usize) {
+        for g in self.groups.iter_mut() {
+            g.set_weight(i, weight);
+        }
+    }
+}
+
+impl<const N: usize> Default for HybridArbiter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Arbiter for HybridArbiter<N> {
+    fn arbit_by<T: Clone, F: Fn(&T) -> usize>(
+        &mut self,
+        req: &[Option<T>],
+        cost: F,
+    ) -> Option<(usize, T)> {
+        let (pri, group) = highest_group(req, &self.priority)?;
+        self.groups[pri].arbit_by(&group, cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run<A: Arbiter>(arbiter: &mut A, req: &[Option<usize>], n: usize) -> Vec<usize> {
+        (0..n)
+            .map(|_| arbiter.arbit_by(req, |c| *c).unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn sp_arbiter_test() {
+        let mut arbiter = SPArbiter::<4>::new();
+        arbiter.set_priority(1, 3);
+        arbiter.set_priority(2, 3);
+        let req = [Some(1), Some(1), Some(1), Some(1)];
+        assert_eq!(run(&mut arbiter, &req, 4), [1, 2, 1, 2]);
+        let req = [Some(1), None, None, Some(1)];
+        assert_eq!(run(&mut arbiter, &req, 4), [0, 3, 0, 3]);
+        assert!(arbiter.arbit(&[None::<usize>; 4]).is_none());
+    }
+
+    #[test]
+    fn dwrr_arbiter_test() {
+        let mut arbiter = DwrrArbiter::<3>::new();
+        arbiter.set_weight(0, 2);
+        //bytes sent are in proportion to weights regardless of frame size
+        let req = [Some(128), Some(512), Some(64)];
+        let mut bytes = [0; 3];
+        for i in run(&mut arbiter, &req, 1000) {
+            bytes[i] += req[i].unwrap();
+        }
+        assert!(bytes[0].abs_diff(2 * bytes[1]) <= 1024);
+        assert!(bytes[1].abs_diff(bytes[2]) <= 512);
+    }
+
+    #[test]
+    fn hybrid_arbiter_test() {
+        let mut arbiter = HybridArbiter::<4>::new();
+        arbiter.set_priority(2, 1);
+        arbiter.set_priority(3, 1);
+        arbiter.set_weight(3, 3);
+        let req = [Some(256), Some(256), Some(256), Some(256)];
+        assert_eq!(run(&mut arbiter, &req, 8), [2, 3, 3, 3, 2, 3, 3, 3]);
+        let req = [Some(256), Some(256), None, None];
+        assert_eq!(run(&mut arbiter, &req, 4), [0, 1, 0, 1]);
+    }
+}
@@ -103,7 +103,7 @@ fn main() {
 }
 
 fn gen_desc(path: &PathBuf, lang: &str) -> std::io::Result<()> {
-    use etha_model::etha::desc::{buffer::*, rx::*, tx::*};
+    use etha_model::etha::desc::{buffer::*, rx::*, ts::*, tx::*};
     let header_ty = match lang {
         "c" => HeaderType::C,
         &_ => todo!(),
@@ -119,6 +119,7 @@ fn gen_desc(path: &PathBuf, lang: &str) -> std::io::Result<()> {
     writeln!(out_file, "#define __ETHA_DESC_H__")?;
     writeln!(out_file, "#include <stdint.h>")?;
     FrameDesc::gen_header(&header_ty, &mut out_file)?;
+    TsDesc::gen_header(&header_ty, &mut out_file)?;
     TxCtrlDesc::gen_header(&header_ty, &mut out_file)?;
     TxStatusDesc::gen_header(&header_ty, &mut out_file)?;
     TxReqDesc::gen_header(&header_ty, &mut out_file)?;
@@ -127,6 +128,10 @@ fn gen_desc(path: &PathBuf, lang: &str) -> std::io::Result<()> {
     RxResultL3Desc::gen_header(&header_ty, &mut out_file)?;
     RxResultL4Desc::gen_header(&header_ty, &mut out_file)?;
     RxStatusDesc::gen_header(&header_ty, &mut out_file)?;
+    RxRssDesc::gen_header(&header_ty, &mut out_file)?;
+    RxTunnelDesc::gen_header(&header_ty, &mut out_file)?;
+    RxEcpriDesc::gen_header(&header_ty, &mut out_file)?;
+    RxHdrSplitDesc::gen_header(&header_ty, &mut out_file)?;
     RxResultDesc::gen_header(&header_ty, &mut out_file)?;
     writeln!(out_file, "#endif")?;
     println!("Gen {} successfully!", out_file_path.display());
@@ -183,6 +188,10 @@ fn gen_ipsec_desc(path: &PathBuf, lang: &str) -> std::io::Result<()> {
     IpsecFrameFmtDesc::gen_header(&header_ty, &mut out_file)?;
     IpsecCfgDesc::gen_header(&header_ty, &mut out_file)?;
     IpsecReqDesc::gen_header(&header_ty, &mut out_file)?;
+    writeln!(
+        out_file,
+        "// seq_overflow: the outbound sequence number of the sa is exhausted, a new sa has to be set up"
+    )?;
     IpsecStatusDesc::gen_header(&header_ty, &mut out_file)?;
     IpsecResultDesc::gen_header(&header_ty, &mut out_file)?;
     writeln!(out_file, "#endif")?;
@@ -234,6 +243,8 @@ fn gen_ring_regs(path: &PathBuf, lang: &str) -> std::io::Result<()> {
     RingSize::gen_header(&header_ty, &mut out_file)?;
     RingPtr::gen_header(&header_ty, &mut out_file)?;
     RingStatus::gen_header(&header_ty, &mut out_file)?;
+    RingIntStatus::gen_header(&header_ty, &mut out_file)?;
+    RingIntCoal::gen_header(&header_ty, &mut out_file)?;
     RingCtrl::gen_header(&header_ty, &mut out_file)?;
     writeln!(out_file, "#endif")?;
     println!("Gen {} successfully!", out_file_path.display());
@@ -311,8 +322,7 @@ fn gen_rohc_regs(path: &PathBuf, lang: &str) -> std::io::Result<()> {
 }
 
 fn gen_irqs(path: &PathBuf, lang: &str) -> std::io::Result<()> {
-    use etha_model::arbiter::*;
-    use etha_model::etha::{CHS, Etha};
+    use etha_model::etha::Etha;
     let header_ty = match lang {
         "c" => HeaderType::C,
         &_ => todo!(),
@@ -327,10 +337,7 @@ fn gen_irqs(path: &PathBuf, lang: &str) -> std::io::Result<()> {
     writeln!(out_file, "#ifndef __ETHA_IRQS_H__")?;
     writeln!(out_file, "#define __ETHA_IRQS_H__")?;
     writeln!(out_file, "#include <stdint.h>")?;
-    let etha = Etha::new(
-        RRArbiter::<CHS>::new(),
-        smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet),
-    );
+    let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
     etha.irqs()
         .lock()
         .unwrap()
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+//time in ns since the model starts
+//virtual time only moves when the model advances it, so runs are reproducible
+pub struct ModelClock {
+    start: std::time::Instant,
+    virtual_en: AtomicBool,
+    virtual_now: AtomicU64,
+}
+
+impl ModelClock {
+    pub fn new() -> Self {
+        ModelClock {
+            start: std::time::Instant::now(),
+            virtual_en: AtomicBool::new(false),
+            virtual_now: AtomicU64::new(0),
+        }
+    }
+    pub fn is_virtual(&self) -> bool {
+        self.virtual_en.load(Ordering::SeqCst)
+    }
+    //switching modes keeps the time monotonic
+    pub fn set_virtual(&self, en: bool) {
+        if en != self.is_virtual() {
+            let now = self.now();
+            self.virtual_now.store(now, Ordering::SeqCst);
+            self.virtual_en.store(en, Ordering::SeqCst);
+        }
+    }
+    pub fn now(&self) -> u64 {
+        if self.is_virtual() {
+            self.virtual_now.load(Ordering::SeqCst)
+        } else {
+            std::cmp::max(
+                self.start.elapsed().as_nanos() as u64,
+                self.virtual_now.load(Ordering::SeqCst),
+            )
+        }
+    }
+    //wall clock can not be moved
+    pub fn advance_to(&self, t: u64) {
+        if self.is_virtual() {
+            self.virtual_now.fetch_max(t, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Default for ModelClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_clock_test() {
+        let clock = ModelClock::new();
+        clock.advance_to(1_000_000_000);
+        assert!(clock.now() < 1_000_000_000);
+        clock.set_virtual(true);
+        let t = clock.now();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        assert_eq!(clock.now(), t);
+        clock.advance_to(t + 100);
+        clock.advance_to(t + 50);
+        assert_eq!(clock.now(), t + 100);
+        clock.advance_to(1_000_000_000);
+        clock.set_virtual(false);
+        assert!(clock.now() >= 1_000_000_000);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod buffer;
 pub mod rx;
+pub mod ts;
 pub mod tx;
@@ -1,12 +1,18 @@
 use super::buffer::FrameDesc;
+use super::ts::TsDesc;
 use crate::desc::*;
 use etha_model_generator::*;
-pub const RX_DESC_ENTRY_SIZE: usize = 128;
+pub const RX_DESC_ENTRY_SIZE: usize = 152;
 
 const L2_DESC_SIZE: usize = 24;
 const L3_DESC_SIZE: usize = 40;
 const L4_DESC_SIZE: usize = 12;
 pub const RX_STATUS_ENTRY_SIZE: usize = 4;
+const RSS_DESC_SIZE: usize = 8;
+const TUNNEL_DESC_SIZE: usize = 16;
+const ECPRI_DESC_SIZE: usize = 8;
+const HDR_SPLIT_DESC_SIZE: usize = 16;
+pub const RX_LRO_MAX_SEGS: usize = 0xff;
 mod bitfields {
     use super::*;
     use bitfield::bitfield;
@@ -27,6 +33,10 @@ mod bitfields {
         pub l2_etype, set_l2_etype: 127, 112;
         pub l2_header_len, set_l2_header_len: 135, 128;
         pub l2_is_vlan, set_l2_is_vlan: 136, 136;
+        pub l2_is_svlan, set_l2_is_svlan: 137, 137;
+        pub l2_vlan_stripped, set_l2_vlan_stripped: 138, 138;
+        pub l2_svlan_flags, set_l2_svlan_flags: 143, 140;
+        pub l2_svlan_vid, set_l2_svlan_vid: 155, 144;
         pub l2_payload_len, set_l2_payload_len: 183, 160;
     }
 
@@ -49,6 +59,7 @@ mod bitfields {
         pub l3_version, set_l3_version: 271, 264;
         pub l3_header_len, set_l3_header_len: 287, 272;
         pub l3_payload_len, set_l3_payload_len: 311, 288;
+        pub l3_is_frag, set_l3_is_frag: 312, 312;
     }
 
     #[desc_gen]
@@ -61,7 +72,9 @@ mod bitfields {
         pub l4_src_port, set_l4_src_port: 15, 0;
         pub l4_dst_port, set_l4_dst_port: 31, 16;
         pub l4_header_len, set_l4_header_len: 47, 32;
+        pub l4_tcp_flags, set_l4_tcp_flags: 56, 48;
         pub l4_payload_len, set_l4_payload_len: 87, 64;
+        pub l4_lro_segs, set_l4_lro_segs: 95, 88;
     }
 
     #[desc_gen]
@@ -72,6 +85,70 @@ mod bitfields {
         impl Debug;
         DescEntryT;
         pub too_large, set_too_large: 0, 0;
+        pub l3_csum_valid, set_l3_csum_valid: 1, 1;
+        pub l3_csum_err, set_l3_csum_err: 2, 2;
+        pub l4_csum_valid, set_l4_csum_valid: 3, 3;
+        pub l4_csum_err, set_l4_csum_err: 4, 4;
+        pub ts_valid, set_ts_valid: 5, 5;
+    }
+
+    #[desc_gen]
+    bitfield! {
+        #[repr(C)]
+        #[derive(Default, Copy, Clone)]
+        pub struct RxRssDesc([DescEntryT]);
+        impl Debug;
+        DescEntryT;
+        pub rss_hash, set_rss_hash: 31, 0;
+        pub rss_type, set_rss_type: 35, 32;
+    }
+
+    #[desc_gen]
+    bitfield! {
+        #[repr(C)]
+        #[derive(Default, Copy, Clone)]
+        pub struct RxTunnelDesc([DescEntryT]);
+        impl Debug;
+        DescEntryT;
+        pub tunnel_type, set_tunnel_type: 3, 0;
+        pub tunnel_inner_is_l2, set_tunnel_inner_is_l2: 4, 4;
+        pub tunnel_id, set_tunnel_id: 63, 32;
+        pub tunnel_inner_l2_offset, set_tunnel_inner_l2_offset: 79, 64;
+        pub tunnel_inner_l3_offset, set_tunnel_inner_l3_offset: 95, 80;
+        pub tunnel_inner_l4_offset, set_tunnel_inner_l4_offset: 111, 96;
+        pub tunnel_inner_protocol, set_tunnel_inner_protocol: 119, 112;
+        pub tunnel_inner_version, set_tunnel_inner_version: 123, 120;
+    }
+
+    #[desc_gen]
+    bitfield! {
+        #[repr(C)]
+        #[derive(Default, Copy, Clone)]
+        pub struct RxEcpriDesc([DescEntryT]);
+        impl Debug;
+        DescEntryT;
+        pub ecpri_valid, set_ecpri_valid: 0, 0;
+        pub ecpri_concat, set_ecpri_concat: 1, 1;
+        pub ecpri_revision, set_ecpri_revision: 7, 4;
+        pub ecpri_msg_type, set_ecpri_msg_type: 15, 8;
+        pub ecpri_payload_size, set_ecpri_payload_size: 31, 16;
+        pub ecpri_id, set_ecpri_id: 47, 32;
+        pub ecpri_seq_id, set_ecpri_seq_id: 63, 48;
+    }
+
+    //hdr_fallback is set if header split is enabled but the frame is not split
+    #[desc_gen]
+    bitfield! {
+        #[repr(C)]
+        #[derive(Default, Copy, Clone)]
+        pub struct RxHdrSplitDesc([DescEntryT]);
+        impl Debug;
+        DescEntryT;
+        pub hdr_addr_lo, set_hdr_addr_lo: 31, 0;
+        pub hdr_addr_hi, set_hdr_addr_hi: 63, 32;
+        pub hdr_len, set_hdr_len: 79, 64;
+        pub hdr_split, set_hdr_split: 80, 80;
+        pub hdr_fallback, set_hdr_fallback: 81, 81;
     }
 }
 
@@ -80,6 +157,17 @@ pub type RxResultL3Desc = bitfields::RxResultL3Desc<[DescEntryT; L3_DESC_SIZE /
 pub type RxResultL4Desc = bitfields::RxResultL4Desc<[DescEntryT; L4_DESC_SIZE / DESC_ENTRY_SIZE]>;
 pub type RxStatusDesc =
     bitfields::RxStatusDesc<[DescEntryT; RX_STATUS_ENTRY_SIZE / DESC_ENTRY_SIZE]>;
+pub type RxRssDesc = bitfields::RxRssDesc<[DescEntryT; RSS_DESC_SIZE / DESC_ENTRY_SIZE]>;
+pub type RxTunnelDesc = bitfields::RxTunnelDesc<[DescEntryT; TUNNEL_DESC_SIZE / DESC_ENTRY_SIZE]>;
+pub type RxEcpriDesc = bitfields::RxEcpriDesc<[DescEntryT; ECPRI_DESC_SIZE / DESC_ENTRY_SIZE]>;
+pub type RxHdrSplitDesc =
+    bitfields::RxHdrSplitDesc<[DescEntryT; HDR_SPLIT_DESC_SIZE / DESC_ENTRY_SIZE]>;
+
+impl RxHdrSplitDesc {
+    pub fn hdr_addr(&self) -> u64 {
+        self.hdr_addr_lo() as u64 | ((self.hdr_addr_hi() as u64) << 32)
+    }
+}
 
 #[desc_gen(padding_to = RX_DESC_ENTRY_SIZE)]
 #[repr(C)]
@@ -90,4 +178,9 @@ pub struct RxResultDesc {
     pub l3: RxResultL3Desc,
     pub l4: RxResultL4Desc,
     pub status: RxStatusDesc,
+    pub rss: RxRssDesc,
+    pub tunnel: RxTunnelDesc,
+    pub ecpri: RxEcpriDesc,
+    pub hdr: RxHdrSplitDesc,
+    pub ts: TsDesc,
 }
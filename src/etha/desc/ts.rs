@@ -0,0 +1,36 @@
+use std::convert::From;
+pub const TS_DESC_SIZE: usize = 8;
+use crate::desc::*;
+use etha_model_generator::*;
+
+mod bitfields {
+    use super::*;
+    use bitfield::bitfield;
+    //phc time in ns
+    #[desc_gen]
+    bitfield! {
+        #[repr(C)]
+        #[derive(Default, Copy, Clone)]
+        pub struct TsDesc([DescEntryT]);
+        impl Debug;
+        DescEntryT;
+        pub ts_lo, set_ts_lo: 31, 0;
+        pub ts_hi, set_ts_hi: 63, 32;
+    }
+}
+pub type TsDesc = bitfields::TsDesc<[DescEntryT; TS_DESC_SIZE / DESC_ENTRY_SIZE]>;
+
+impl TsDesc {
+    pub fn ts(&self) -> u64 {
+        self.ts_lo() as u64 | ((self.ts_hi() as u64) << 32)
+    }
+}
+
+impl From<u64> for TsDesc {
+    fn from(ts: u64) -> Self {
+        let mut d = Self::default();
+        d.set_ts_lo(ts as DescEntryT);
+        d.set_ts_hi((ts >> 32) as DescEntryT);
+        d
+    }
+}
@@ -1,10 +1,11 @@
 use super::buffer::FrameDesc;
+use super::ts::{TsDesc, TS_DESC_SIZE};
 use crate::desc::*;
 use etha_model_generator::*;
 
 pub const TX_REQ_ENTRY_SIZE: usize = 32;
 pub const TX_STATUS_ENTRY_SIZE: usize = 8;
-pub const TX_CTRL_ENTRY_SIZE: usize = 4;
+pub const TX_CTRL_ENTRY_SIZE: usize = 8;
 mod bitfields {
     use super::*;
     use bitfield::bitfield;
@@ -17,6 +18,13 @@ mod bitfields {
         impl Debug;
         DescEntryT;
         pub resp_en, set_resp_en: 0, 0;
+        pub l3_csum_en, set_l3_csum_en: 1, 1;
+        pub l4_csum_en, set_l4_csum_en: 2, 2;
+        pub tso_en, set_tso_en: 3, 3;
+        pub vlan_insert, set_vlan_insert: 4, 4;
+        pub ts_en, set_ts_en: 5, 5;
+        pub mss, set_mss: 31, 16;
+        pub vlan_tci, set_vlan_tci: 47, 32;
     }
 
     #[desc_gen]
@@ -28,9 +36,11 @@ mod bitfields {
         DescEntryT;
         pub too_large, set_too_large: 0, 0;
         pub too_small, set_too_small: 1, 1;
+        pub ts_valid, set_ts_valid: 2, 2;
+        pub segs, set_segs: 47, 32;
     }
 }
-pub const TX_RESULT_ENTRY_SIZE: usize = TX_STATUS_ENTRY_SIZE;
+pub const TX_RESULT_ENTRY_SIZE: usize = TX_STATUS_ENTRY_SIZE + TS_DESC_SIZE;
 pub type TxStatusDesc =
     bitfields::TxStatusDesc<[DescEntryT; TX_STATUS_ENTRY_SIZE / DESC_ENTRY_SIZE]>;
 pub type TxCtrlDesc = bitfields::TxCtrlDesc<[DescEntryT; TX_CTRL_ENTRY_SIZE / DESC_ENTRY_SIZE]>;
@@ -40,6 +50,7 @@ pub type TxCtrlDesc = bitfields::TxCtrlDesc<[DescEntryT; TX_CTRL_ENTRY_SIZE / DE
 #[derive(Copy, Clone, Default)]
 pub struct TxResultDesc {
     status: TxStatusDesc,
+    pub ts: TsDesc,
 }
 impl std::ops::Deref for TxResultDesc {
     type Target = TxStatusDesc;
@@ -0,0 +1,125 @@
+use super::desc::rx::RxEcpriDesc;
+use super::parser::ParserInfo;
+use super::reg_if::rx::LockedEcpriRegs;
+use super::*;
+use smoltcp::wire::{EthernetProtocol, IpProtocol};
+
+pub const ECPRI_TYPE: u16 = 0xaefe;
+const ECPRI_HEADER_LEN: usize = 4;
+const ECPRI_ID_LEN: usize = 4;
+const ECPRI_FLAG_C: u8 = 0x1;
+//message types carry 2 bytes PC_ID/RTC_ID and 2 bytes SEQ_ID
+const ECPRI_MSG_IQ_DATA: u8 = 0;
+const ECPRI_MSG_RT_CTRL: u8 = 2;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct EcpriInfo {
+    pub revision: u8,
+    pub concat: bool,
+    pub msg_type: u8,
+    pub payload_size: u16,
+    //PC_ID for iq data and bit sequence, RTC_ID for real-time control
+    pub id: u16,
+    pub seq_id: u16,
+}
+
+impl From<EcpriInfo> for RxEcpriDesc {
+    fn from(info: EcpriInfo) -> Self {
+        let mut desc = RxEcpriDesc::default();
+        desc.set_ecpri_valid(1);
+        desc.set_ecpri_concat(info.concat as u32);
+        desc.set_ecpri_revision(info.revision as u32);
+        desc.set_ecpri_msg_type(info.msg_type as u32);
+        desc.set_ecpri_payload_size(info.payload_size as u32);
+        desc.set_ecpri_id(info.id as u32);
+        desc.set_ecpri_seq_id(info.seq_id as u32);
+        desc
+    }
+}
+
+pub struct EcpriParser<'a> {
+    pub cfg: &'a LockedEcpriRegs,
+}
+
+impl<'a> EcpriParser<'a> {
+    fn start(&self, i: &ParserInfo) -> Option<usize> {
+        let ctrl = self.cfg.ecpri_ctrl();
+        match i.l2.etype {
+            EthernetProtocol::Unknown(ECPRI_TYPE) => Some(i.l2.header_len),
+            _ if ctrl.udp_en() == 1
+                && i.l3.protocol == IpProtocol::Udp
+                && i.l3.frag.is_none()
+                && i.l4.header_len != 0
+                && i.l4.dst == ctrl.udp_port() as u16 =>
+            {
+                Some(i.l2.header_len + i.l3.header_len + i.l4.header_len)
+            }
+            _ => None,
+        }
+    }
+
+    //only the first message of concatenated messages is decoded
+    fn parse(&self, buffer: &[u8]) -> Option<EcpriInfo> {
+        let hdr = buffer.get(..ECPRI_HEADER_LEN)?;
+        let mut info = EcpriInfo {
+            revision: hdr[0] >> 4,
+            concat: hdr[0] & ECPRI_FLAG_C != 0,
+            msg_type: hdr[1],
+            payload_size: u16::from_be_bytes([hdr[2], hdr[3]]),
+            ..Default::default()
+        };
+        if (ECPRI_MSG_IQ_DATA..=ECPRI_MSG_RT_CTRL).contains(&info.msg_type) {
+            let ids = buffer.get(ECPRI_HEADER_LEN..ECPRI_HEADER_LEN + ECPRI_ID_LEN)?;
+            info.id = u16::from_be_bytes([ids[0], ids[1]]);
+            info.seq_id = u16::from_be_bytes([ids[2], ids[3]]);
+        }
+        Some(info)
+    }
+}
+
+impl<'a> Pipeline for EcpriParser<'a> {
+    type Input = ParserInfo;
+    type Output = ParserInfo;
+    fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let mut info = *i;
+        info.ecpri = self
+            .start(i)
+            .and_then(|start| self.parse(buffer.get(start..)?));
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::EthaRxParser;
+    use super::super::reg_if::rx::RxRegs;
+    use super::*;
+    use crate::mac::*;
+    use smoltcp::phy::RxToken;
+    use smoltcp::time::Instant;
+    use smoltcp::wire::ETHERNET_HEADER_LEN;
+
+    #[test]
+    fn ecpri_parser_test() {
+        let regs = RxRegs::<RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        let parser = EthaRxParser::new(&regs);
+        let mut mac = PcapMacRx::new("pcaps/20_ecpri_pkts.pcap");
+        while let Some(rx) = mac.receive() {
+            let info = rx
+                .consume(Instant::now(), |buffer| {
+                    let info = parser.pipeline().execute(buffer, &())?;
+                    let ecpri = info.ecpri.unwrap();
+                    assert_eq!(ecpri.revision, 1);
+                    assert!(!ecpri.concat);
+                    assert_eq!(ecpri.msg_type, ECPRI_MSG_IQ_DATA);
+                    assert_eq!(
+                        ecpri.payload_size as usize,
+                        buffer.len() - ETHERNET_HEADER_LEN - ECPRI_HEADER_LEN
+                    );
+                    Ok(ecpri)
+                })
+                .unwrap();
+            println!("{:#x?}", info);
+        }
+    }
+}
@@ -1,24 +1,29 @@
 use super::etha_core::EthaCore;
-use super::reg_if::TopRegs;
+use super::reg_if::{switch::SWITCH_MAX_PORTS, TopRegs};
 use super::*;
 use crate::aborter::*;
-use crate::arbiter::*;
 use crate::irq::*;
 use core_affinity::{set_for_current, CoreId};
 use smoltcp::phy::Device;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-pub struct Etha<A: Arbiter, M: for<'a> Device<'a>> {
-    core: EthaCore<A, M>,
+pub struct Etha<M: for<'a> Device<'a>> {
+    core: EthaCore<M>,
     regs: Arc<TopRegs<CHS, RX_ET_FILTERS, RX_TP5_FILTERS>>,
 }
 
-impl<A: Arbiter, M: for<'a> Device<'a>> Etha<A, M> {
-    pub fn new(arbiter: A, mac: M) -> Self {
+impl<M: for<'a> Device<'a>> Etha<M> {
+    pub fn new(mac: M) -> Self {
+        Self::with_ports(vec![mac])
+    }
+
+    //one mac per switch port
+    pub fn with_ports(macs: Vec<M>) -> Self {
+        assert!((1..=SWITCH_MAX_PORTS).contains(&macs.len()));
         let regs = Arc::new(TopRegs::new());
         Etha {
-            core: EthaCore::new(arbiter, mac, &regs),
+            core: EthaCore::new(macs, &regs),
             regs,
         }
     }
@@ -34,7 +39,7 @@ impl<A: Arbiter, M: for<'a> Device<'a>> Etha<A, M> {
     }
 }
 
-impl<A: Arbiter + Send + 'static, M: for<'a> Device<'a> + Send + 'static> Etha<A, M> {
+impl<M: for<'a> Device<'a> + Send + 'static> Etha<M> {
     pub fn spawn(mut self, core_id: Option<CoreId>) -> thread::JoinHandle<()> {
         thread::spawn(move || {
             if let Some(id) = core_id {
@@ -50,32 +55,48 @@ mod tests {
     use super::tests_driver_helper::*;
     use super::*;
     use crate::mac::pcap_cmp;
+    use crate::tests_frame_helper::*;
     use std::time::Duration;
 
     extern "C" fn ch_handler(id: usize) {
         println!("get irq[{}]!", id);
     }
+    //frames are written to the port file as they leave the model
+    fn wait_pcap_frames(file: &str, frames: &[Vec<u8>]) {
+        //pcap global header and record headers
+        let size = 24 + frames.iter().map(|f| 16 + f.len() as u64).sum::<u64>();
+        while std::fs::metadata(file).map_or(0, |m| m.len()) < size {
+            std::thread::yield_now();
+        }
+    }
     #[test]
     fn abort_test() {
-        let etha = Etha::new(
-            RRArbiter::<CHS>::new(),
-            crate::mac::PcapMac::new("pcaps/20_ecpri_pkts.pcap", "pcaps/tmp/abort_test.pcap"),
-        );
+        let rx_file = "pcaps/20_ecpri_pkts.pcap";
+        let etha = Etha::new(crate::mac::PcapMac::new(rx_file, "pcaps/tmp/abort_test.pcap"));
         let abort = etha.abort();
+        let reg = etha.regs();
         let t = etha.spawn(Some(CoreId { id: 0 }));
-        thread::sleep(Duration::from_millis(20));
+        //without queues every frame is dropped, abort once the input is drained
+        let driver = SwEtha::new(&reg);
+        driver.rx_en();
+        let n_frames = crate::mac::pcap_frames(rx_file).len() as u64;
+        let rx_frames = || {
+            (0..STATS_HIST_BUCKETS)
+                .map(|i| driver.stats(StatsRegs::<CHS>::hist_offset(STATS_RX_HIST_OFFSET, i)))
+                .sum::<u64>()
+        };
+        while rx_frames() != n_frames {
+            std::thread::yield_now();
+        }
         abort.abort();
         t.join().unwrap();
     }
     #[test_log::test]
     fn pcap_simple_test() {
-        let etha = Etha::new(
-            RRArbiter::<CHS>::new(),
-            crate::mac::PcapMac::new(
-                "pcaps/20_ecpri_pkts.pcap",
-                "pcaps/tmp/pcap_simple_test.pcap",
-            ),
-        );
+        let etha = Etha::new(crate::mac::PcapMac::new(
+            "pcaps/20_ecpri_pkts.pcap",
+            "pcaps/tmp/pcap_simple_test.pcap",
+        ));
         let abort = etha.abort();
         let reg = etha.regs();
         let irqs = etha.irqs();
@@ -136,13 +157,10 @@ mod tests {
 
     #[test_log::test]
     fn pcap_default_queue_test() {
-        let etha = Etha::new(
-            RRArbiter::<CHS>::new(),
-            crate::mac::PcapMac::new(
-                "pcaps/icmp_12_pkts.pcap",
-                "pcaps/tmp/pcap_default_queue_test.pcap",
-            ),
-        );
+        let etha = Etha::new(crate::mac::PcapMac::new(
+            "pcaps/icmp_12_pkts.pcap",
+            "pcaps/tmp/pcap_default_queue_test.pcap",
+        ));
         let abort = etha.abort();
         let reg = etha.regs();
         let t = etha.spawn(Some(CoreId { id: 0 }));
@@ -263,35 +281,1174 @@ mod tests {
             0xd4, 0xf1, 0x5, 0xf, 0x2c, 0xf1, 0xb6, 0xe7, 0xc0, 0x22, 0xf1, 0xf, 0xe7, 0x40, 0x40,
             0xfb, 0x22, 0xb6, 0xca, 0x19, 0x22, 0x2c,
         ];
-        let etha = Etha::new(
-            RRArbiter::<CHS>::new(),
-            smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet),
-        );
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let _default_ch = driver.alloc_default_ch(1, 1024, 1, CongestionAction::Blocking);
+        let mut ecpri_ch = driver.alloc_et_ch(1, 2048, 1, CongestionAction::Blocking, 0xaefe);
+        driver.rx_en();
+        driver.tx_en();
+
+        ecpri_ch.tx.send(&[&frame_send], false);
+        loop {
+            if let Some(r) = ecpri_ch.rx.receive().take() {
+                let data = r.concat();
+                let frame = smoltcp::wire::EthernetFrame::new_checked(&data).unwrap();
+                println!(
+                    "{}",
+                    smoltcp::wire::PrettyPrinter::<smoltcp::wire::EthernetFrame<&[u8]>>::new(
+                        "", &frame
+                    )
+                );
+                assert_eq!(data.as_slice(), frame_send);
+                break;
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    fn ipv4_tcp_segment(seq: u32, flags: u16, payload: &[u8]) -> Vec<u8> {
+        use smoltcp::wire::*;
+        let mut frame = ipv4_tcp_frame(payload);
+        let mut ip = Ipv4Packet::new_unchecked(&mut frame[ETHERNET_HEADER_LEN..]);
+        let (src, dst) = (ip.src_addr().into(), ip.dst_addr().into());
+        let mut tcp = TcpPacket::new_unchecked(ip.payload_mut());
+        tcp.set_seq_number(TcpSeqNumber(seq as i32));
+        tcp.set_ack(flags & 0x10 != 0);
+        tcp.set_psh(flags & 0x8 != 0);
+        tcp.fill_checksum(&src, &dst);
+        frame
+    }
+
+    fn ipv4_tcp_acked(mut frame: Vec<u8>, ack: u32, window: u16) -> Vec<u8> {
+        use smoltcp::wire::*;
+        let mut ip = Ipv4Packet::new_unchecked(&mut frame[ETHERNET_HEADER_LEN..]);
+        let (src, dst) = (ip.src_addr().into(), ip.dst_addr().into());
+        let mut tcp = TcpPacket::new_unchecked(ip.payload_mut());
+        tcp.set_ack_number(TcpSeqNumber(ack as i32));
+        tcp.set_window_len(window);
+        tcp.fill_checksum(&src, &dst);
+        frame
+    }
+
+    //the headers of the first segment with the lengths of the aggregate
+    //and the ack, window and flags of the last segment
+    fn ipv4_tcp_merged(segs: &[Vec<u8>]) -> Vec<u8> {
+        use smoltcp::wire::*;
+        let hdr_len = ETHERNET_HEADER_LEN + 20 + TCP_HEADER_LEN;
+        let mut frame = segs[0][..hdr_len].to_vec();
+        for seg in segs {
+            frame.extend_from_slice(&seg[hdr_len..]);
+        }
+        let len = frame.len() - ETHERNET_HEADER_LEN;
+        let last = segs.last().unwrap();
+        let last = TcpPacket::new_unchecked(&last[ETHERNET_HEADER_LEN + 20..]);
+        let mut ip = Ipv4Packet::new_unchecked(&mut frame[ETHERNET_HEADER_LEN..]);
+        ip.set_total_len(len as u16);
+        ip.fill_checksum();
+        let mut tcp = TcpPacket::new_unchecked(ip.payload_mut());
+        tcp.set_ack_number(last.ack_number());
+        tcp.set_window_len(last.window_len());
+        tcp.set_psh(last.psh());
+        frame
+    }
+
+    #[test_log::test]
+    fn loopback_csum_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
+        driver.rx_en();
+        driver.tx_en();
+
+        //sent without checksums, they are only valid when inserted
+        let mut frame_send = ipv4_tcp_frame(&[0x5a; 64]);
+        let ip = &mut frame_send[smoltcp::wire::ETHERNET_HEADER_LEN..];
+        smoltcp::wire::Ipv4Packet::new_unchecked(ip).set_checksum(0);
+        for insert in [true, false] {
+            let mut ctrl = TxCtrlDesc::default();
+            ctrl.set_l3_csum_en(insert as u32);
+            ctrl.set_l4_csum_en(insert as u32);
+            default_ch
+                .tx
+                .send_with_ctrl(&[&frame_send], ctrl, true)
+                .unwrap();
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    let data = r.concat();
+                    assert_eq!(desc.status.l3_csum_valid(), 1);
+                    assert_eq!(desc.status.l4_csum_valid(), 1);
+                    assert_eq!(desc.status.l3_csum_err(), !insert as u32);
+                    assert_eq!(desc.status.l4_csum_err(), !insert as u32);
+                    let ip = smoltcp::wire::Ipv4Packet::new_checked(
+                        &data[smoltcp::wire::ETHERNET_HEADER_LEN..],
+                    )
+                    .unwrap();
+                    assert_eq!(ip.verify_checksum(), insert);
+                    assert_eq!(ip.payload()[20..], frame_send[54..]);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_tso_test() {
+        use smoltcp::wire::*;
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(4, 2048, 1, CongestionAction::Blocking);
+        driver.rx_en();
+        driver.tx_en();
+
+        let mss = 1000;
+        let payload = (0..2500).map(|i| i as u8).collect::<Vec<_>>();
+        let mut frame_send = ipv4_tcp_frame(&payload);
+        let mut ip = Ipv4Packet::new_unchecked(&mut frame_send[ETHERNET_HEADER_LEN..]);
+        ip.set_ident(0xfffe);
+        let mut tcp = TcpPacket::new_unchecked(ip.payload_mut());
+        tcp.set_seq_number(TcpSeqNumber(100));
+        tcp.set_fin(true);
+        tcp.set_psh(true);
+
+        let mut ctrl = TxCtrlDesc::default();
+        ctrl.set_tso_en(1);
+        ctrl.set_mss(mss as u32);
+        let resp = default_ch
+            .tx
+            .send_with_ctrl(&[&frame_send], ctrl, true)
+            .unwrap();
+        assert_eq!(resp.too_large(), 0);
+        assert_eq!(resp.segs(), 3);
+        let mut k = 0;
+        while k < 3 {
+            if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                let n_blocks = r.len();
+                let data = r.concat();
+                assert_eq!(desc.status.l3_csum_err(), 0);
+                assert_eq!(desc.status.l4_csum_valid(), 1);
+                assert_eq!(desc.status.l4_csum_err(), 0);
+                let ip = Ipv4Packet::new_checked(&data[ETHERNET_HEADER_LEN..]).unwrap();
+                let seg_payload = &payload[k * mss..std::cmp::min((k + 1) * mss, payload.len())];
+                assert_eq!(ip.total_len() as usize, 40 + seg_payload.len());
+                assert_eq!(ip.ident(), 0xfffeu16.wrapping_add(k as u16));
+                let tcp = TcpPacket::new_checked(ip.payload()).unwrap();
+                assert_eq!(tcp.seq_number(), TcpSeqNumber(100 + (k * mss) as i32));
+                assert_eq!(tcp.fin(), k == 2);
+                assert_eq!(tcp.psh(), k == 2);
+                assert_eq!(tcp.payload(), seg_payload);
+                default_ch.rx.release(n_blocks);
+                k += 1;
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_rss_test() {
+        use super::rx_filter::toeplitz_hash;
+        use smoltcp::wire::*;
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let key = (0..RSS_KEY_SIZE)
+            .map(|i| (i * 7 + 3) as u8)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        let driver = SwEtha::new(&reg);
+        let mut chs = driver.alloc_rss_chs(4, 2, 1024, 1, &key);
+        driver.rx_en();
+        driver.tx_en();
+
+        let mut hit = [false; 4];
+        for port in 0..16u16 {
+            let mut frame_send = ipv4_tcp_frame(&[0x5a; 64]);
+            let mut ip = Ipv4Packet::new_unchecked(&mut frame_send[ETHERNET_HEADER_LEN..]);
+            let mut tcp = TcpPacket::new_unchecked(ip.payload_mut());
+            tcp.set_src_port(0x4000 + port);
+            let input = [
+                &[192, 168, 1, 1, 192, 168, 1, 2][..],
+                &(0x4000 + port).to_be_bytes(),
+                &0x80u16.to_be_bytes(),
+            ]
+            .concat();
+            let hash = toeplitz_hash(&key, &input);
+            let q = hash as usize % RSS_RETA_SIZE % chs.len();
+            chs[0].tx.send(&[&frame_send], true).unwrap();
+            loop {
+                if let Some((desc, r)) = chs[q].rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert_eq!(r.concat(), frame_send);
+                    assert_eq!(desc.rss.rss_hash(), hash);
+                    assert_eq!(desc.rss.rss_type(), RssHashType::TcpIpv4 as u32);
+                    chs[q].rx.release(n_blocks);
+                    hit[q] = true;
+                    break;
+                }
+            }
+        }
+        assert!(hit.iter().all(|h| *h));
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_tunnel_test() {
+        use smoltcp::wire::*;
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
+        let mut inner_ch = driver.alloc_tp5_ch(2, 1024, 1, CongestionAction::Blocking, true, 0x80);
+        driver.tunnel_en(true, true, true);
+        driver.rx_en();
+        driver.tx_en();
+
+        let inner = ipv4_tcp_frame(&[0x5a; 64]);
+        let vxlan = udp_datagram(
+            0x1111,
+            4789,
+            &[&[0x08, 0, 0, 0, 0x12, 0x34, 0x56, 0][..], &inner].concat(),
+        );
+        let gre = [
+            &[0x20, 0, 0x08, 0x00, 0xde, 0xad, 0xbe, 0xef][..],
+            &inner[ETHERNET_HEADER_LEN..],
+        ]
+        .concat();
+        let ipip = &inner[ETHERNET_HEADER_LEN..];
+        let encap = |protocol: u8, payload: &[u8]| {
+            ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::from(protocol), payload)
+        };
+        //(frame, tunnel type, id, inner l2 offset, inner l3 offset, to inner ch)
+        let frames = [
+            (encap(17, &vxlan), 1, 0x123456, 50, 64, true),
+            (encap(47, &gre), 2, 0xdeadbeef, 42, 42, true),
+            (encap(4, ipip), 4, 0, 34, 34, true),
+            (inner.clone(), 0, 0, 0, 0, false),
+        ];
+        for (frame_send, ty, id, l2_offset, l3_offset, to_inner_ch) in frames {
+            default_ch.tx.send(&[&frame_send], true).unwrap();
+            let ch = if to_inner_ch {
+                &mut inner_ch
+            } else {
+                &mut default_ch
+            };
+            loop {
+                if let Some((desc, r)) = ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert_eq!(r.concat(), frame_send);
+                    assert_eq!(desc.tunnel.tunnel_type(), ty);
+                    if ty != 0 {
+                        assert_eq!(desc.tunnel.tunnel_id(), id);
+                        assert_eq!(desc.tunnel.tunnel_inner_is_l2(), (ty == 1) as u32);
+                        assert_eq!(desc.tunnel.tunnel_inner_l2_offset(), l2_offset);
+                        assert_eq!(desc.tunnel.tunnel_inner_l3_offset(), l3_offset);
+                        assert_eq!(desc.tunnel.tunnel_inner_l4_offset(), l3_offset + 20);
+                        assert_eq!(
+                            desc.tunnel.tunnel_inner_protocol(),
+                            u8::from(IpProtocol::Tcp) as u32
+                        );
+                        assert_eq!(desc.tunnel.tunnel_inner_version(), 4);
+                        let l4 = l3_offset as usize + 20;
+                        assert_eq!(&frame_send[l4 + 2..l4 + 4], &0x80u16.to_be_bytes());
+                    }
+                    ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    fn ecpri_msg(msg_type: u8, id: u16, seq_id: u16, payload: &[u8]) -> Vec<u8> {
+        [
+            &[0x10, msg_type][..],
+            &((4 + payload.len()) as u16).to_be_bytes(),
+            &id.to_be_bytes(),
+            &seq_id.to_be_bytes(),
+            payload,
+        ]
+        .concat()
+    }
+
+    #[test_log::test]
+    fn loopback_ecpri_filter_test() {
+        use smoltcp::wire::*;
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
+        let mut ecpri_ch = driver.alloc_ecpri_ch(2, 1024, 1, CongestionAction::Blocking, 0, 2..=3);
+        driver.ecpri_udp_en(0x1234);
+        driver.rx_en();
+        driver.tx_en();
+
+        let eth_hdr = [
+            &[0x0, 0x11, 0x22, 0x33, 0x44, 0x55][..],
+            &[0x0, 0x11, 0x22, 0x33, 0x44, 0x66],
+            &0xaefeu16.to_be_bytes(),
+        ]
+        .concat();
+        let over_eth = |msg: Vec<u8>| [&eth_hdr[..], &msg].concat();
+        let over_udp = |msg: Vec<u8>| {
+            let udp = udp_datagram(0x4321, 0x1234, &msg);
+            ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &udp)
+        };
+        //(frame, msg type, id, to ecpri ch)
+        let frames = [
+            (over_eth(ecpri_msg(0, 2, 0x0080, &[0x5a; 64])), 0, 2, true),
+            (over_eth(ecpri_msg(0, 5, 0x0180, &[0x5a; 64])), 0, 5, false),
+            (over_eth(ecpri_msg(2, 3, 0x0280, &[0x5a; 64])), 2, 3, false),
+            (over_udp(ecpri_msg(0, 3, 0x0380, &[0x5a; 64])), 0, 3, true),
+        ];
+        for (frame_send, msg_type, id, to_ecpri_ch) in frames {
+            default_ch.tx.send(&[&frame_send], true).unwrap();
+            let ch = if to_ecpri_ch {
+                &mut ecpri_ch
+            } else {
+                &mut default_ch
+            };
+            loop {
+                if let Some((desc, r)) = ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert_eq!(r.concat(), frame_send);
+                    assert_eq!(desc.ecpri.ecpri_valid(), 1);
+                    assert_eq!(desc.ecpri.ecpri_revision(), 1);
+                    assert_eq!(desc.ecpri.ecpri_msg_type(), msg_type);
+                    assert_eq!(desc.ecpri.ecpri_payload_size(), 68);
+                    assert_eq!(desc.ecpri.ecpri_id(), id);
+                    assert_eq!(desc.ecpri.ecpri_seq_id() & 0xff, 0x80);
+                    ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_flow_table_test() {
+        use smoltcp::wire::*;
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
+        let flow = (17, [10, 0, 0, 1], [10, 0, 0, 2], 0x4321, 0x1234);
+        let mut flow_ch = driver.alloc_flow_ch(2, 1024, 1, CongestionAction::Blocking, flow);
+        //same port filtered by tp5 is overridden by the flow table
+        let mut tp5_ch = driver.alloc_tp5_ch(2, 1024, 1, CongestionAction::Blocking, false, 0x1234);
+        driver.rx_en();
+        driver.tx_en();
+
+        let udp = |src_port: u16| {
+            let udp = udp_datagram(src_port, 0x1234, &[0x5a; 64]);
+            ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &udp)
+        };
+        for (frame_send, to_flow_ch) in [
+            (udp(0x4321), true),
+            (udp(0x4322), false),
+            (udp(0x4321), true),
+        ] {
+            default_ch.tx.send(&[&frame_send], true).unwrap();
+            let ch = if to_flow_ch {
+                &mut flow_ch
+            } else {
+                &mut tp5_ch
+            };
+            loop {
+                if let Some(r) = ch.rx.receive() {
+                    let n_blocks = r.len();
+                    assert_eq!(r.concat(), frame_send);
+                    ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        let (result, _) = driver.flow_table_cmd(flow, FlowCmdOp::Query);
+        assert_eq!(result, 0);
+        let base = RX_REGS_RANGE.start + reg.rx.flow_table_offset();
+        assert_eq!(reg.read(addr(base + 13)).unwrap(), 2);
+        assert_ne!(reg.read(addr(base + 14)).unwrap(), 0);
+        assert_eq!(driver.flow_table_cmd(flow, FlowCmdOp::Del).0, 0);
+        assert_eq!(driver.flow_table_cmd(flow, FlowCmdOp::Query).0, 1);
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_tx_arbiter_test() {
+        let frame = |ch: usize, seq: usize| {
+            let mut frame = vec![0u8; 256];
+            frame[12..14].copy_from_slice(&0x88b5u16.to_be_bytes());
+            frame[14] = ch as u8;
+            frame[15] = seq as u8;
+            frame
+        };
+        let frames = (0..4)
+            .map(|i| [frame(1, i), frame(2, i)])
+            .collect::<Vec<_>>();
+        //ch 1 is bulk with pri 0 and weight 1, ch 2 is fronthaul with pri 7 and weight 3
+        for (mode, order) in [
+            (TxArbMode::StrictPriority, [2, 2, 2, 2, 1, 1, 1, 1]),
+            (TxArbMode::Dwrr, [1, 2, 2, 2, 1, 2, 1, 1]),
+        ] {
+            let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+            let abort = etha.abort();
+            let reg = etha.regs();
+            let t = etha.spawn(Some(CoreId { id: 0 }));
+
+            let driver = SwEtha::new(&reg);
+            let mut default_ch = driver.alloc_default_ch(8, 1024, 1, CongestionAction::Blocking);
+            //tx only channels, non ip frames are all received by the default ch
+            let mut chs = [1, 2].map(|port| {
+                driver.alloc_tp5_ch(1, 1024, 4, CongestionAction::Blocking, false, port)
+            });
+            driver.tx_arb_ch(1, 0, 1);
+            driver.tx_arb_ch(2, 7, 3);
+            driver.tx_arb(mode);
+            driver.rx_en();
+            //all requests are pending before arbitration
+            for f in frames.iter() {
+                for (ch, f) in chs.iter_mut().zip(f.iter()) {
+                    ch.tx.send(&[f], false);
+                }
+            }
+            driver.tx_en();
+
+            let mut seqs = [0; 3];
+            for ch in order {
+                loop {
+                    if let Some(r) = default_ch.rx.receive() {
+                        let n_blocks = r.len();
+                        assert_eq!(r.concat(), frame(ch, seqs[ch]));
+                        default_ch.rx.release(n_blocks);
+                        break;
+                    }
+                }
+                seqs[ch] += 1;
+            }
+            abort.abort();
+            t.join().unwrap();
+        }
+    }
+
+    #[test_log::test]
+    fn loopback_tx_shaper_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(8, 1024, 4, CongestionAction::Blocking);
+        driver.clock_virtual(true);
+        //1 byte per us, one frame of burst
+        driver.tx_shaper(Some(0), 8, 256);
+        driver.rx_en();
+        driver.tx_en();
+
+        let frames = (0..4u8)
+            .map(|i| {
+                let mut frame = vec![i; 256];
+                frame[12..14].copy_from_slice(&0x88b5u16.to_be_bytes());
+                frame
+            })
+            .collect::<Vec<_>>();
+        let start = driver.clock();
+        for frame_send in frames.iter() {
+            default_ch.tx.send(&[frame_send], false);
+        }
+        for frame_send in frames.iter() {
+            loop {
+                if let Some(r) = default_ch.rx.receive() {
+                    let n_blocks = r.len();
+                    assert_eq!(&r.concat(), frame_send);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        //virtual time moves while frames wait for tokens, and by the wire time of
+        //the last received frame
+        assert_eq!(driver.clock() - start, 3 * 256_000 + crate::mac::wire_time(256));
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn pcap_switch_test() {
+        use crate::mac::{pcap_frames, PcapMac};
+        let tx_prefix = "pcaps/tmp/pcap_switch_test";
+        let etha = Etha::with_ports(PcapMac::ports(
+            &[Some("pcaps/icmp_12_pkts.pcap"), None, None],
+            tx_prefix,
+        ));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(4, 1024, 2, CongestionAction::Blocking);
+        driver.switch_en(true);
+        driver.rx_en();
+        driver.tx_en();
+
+        //both hosts are on port 0, only the first 2 frames are flooded
+        let rx_frames = pcap_frames("pcaps/icmp_12_pkts.pcap");
+        for frame in rx_frames[..2].iter() {
+            loop {
+                if let Some(r) = default_ch.rx.receive() {
+                    let n_blocks = r.len();
+                    assert_eq!(&r.concat(), frame);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        let (a, b) = (rx_frames[0][6..12].to_vec(), rx_frames[0][..6].to_vec());
+        while driver.fdb_cmd(b.clone().try_into().unwrap(), 0, false, FdbCmdOp::Query) != (0, 2, 0)
+        {
+            std::thread::yield_now();
+        }
+
+        let host = [0x02, 0, 0, 0, 0, 0x1];
+        let bcast = [&[0xff; 6][..], &host, &[0x88, 0xb5], &[0x5a; 50]].concat();
+        let to_a = [&a[..], &host, &[0x88, 0xb5], &[0xa5; 50]].concat();
+        default_ch.tx.send(&[&bcast], true).unwrap();
+        default_ch.tx.send(&[&to_a], true).unwrap();
+        assert_eq!(
+            driver.fdb_cmd(host, 0, false, FdbCmdOp::Query),
+            (0, 3, SWITCH_HOST_PORT as u64)
+        );
+        let expects = [
+            vec![bcast.clone(), to_a],
+            vec![rx_frames[0].clone(), rx_frames[1].clone(), bcast.clone()],
+            vec![rx_frames[0].clone(), rx_frames[1].clone(), bcast.clone()],
+        ];
+        for (port, expect) in expects.iter().enumerate() {
+            wait_pcap_frames(&PcapMac::port_file(tx_prefix, port), expect);
+        }
+        abort.abort();
+        t.join().unwrap();
+
+        for (port, expect) in expects.iter().enumerate() {
+            assert_eq!(&pcap_frames(&PcapMac::port_file(tx_prefix, port)), expect);
+        }
+    }
+
+    #[test_log::test]
+    fn loopback_stats_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(8, 1024, 4, CongestionAction::Blocking);
+        driver.rx_en();
+        driver.tx_en();
+
+        let mut frame_send = vec![0x5a; 256];
+        frame_send[12..14].copy_from_slice(&0x88b5u16.to_be_bytes());
+        for _ in 0..4 {
+            default_ch.tx.send(&[&frame_send], false);
+        }
+        for _ in 0..4 {
+            loop {
+                if let Some(r) = default_ch.rx.receive() {
+                    let n_blocks = r.len();
+                    assert_eq!(r.concat(), frame_send);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        //histogram is updated after the frame is written to the queue
+        while driver.stats(StatsRegs::<CHS>::hist_offset(STATS_RX_HIST_OFFSET, 3)) != 4 {
+            std::thread::yield_now();
+        }
+        let queue = |c| StatsRegs::<CHS>::queue_counter_offset(0, c);
+        let rx_pkts = queue(QueueCounter::RxPkts);
+        let tx_bytes = queue(QueueCounter::TxBytes);
+        assert_eq!(driver.stats(rx_pkts), 4);
+        assert_eq!(driver.stats(queue(QueueCounter::RxBytes)), 1024);
+        assert_eq!(driver.stats(queue(QueueCounter::TxPkts)), 4);
+        assert_eq!(driver.stats(tx_bytes), 1024);
+        let tx_hist = StatsRegs::<CHS>::hist_offset(STATS_TX_HIST_OFFSET, 3);
+        assert_eq!(driver.stats(tx_hist), 4);
+        assert_eq!(
+            driver.stats(StatsRegs::<CHS>::counter_offset(StatsCounter::RxParseErr)),
+            0
+        );
+
+        //snapshot then clear live counters
+        driver.stats_ctrl(0xe);
+        assert_eq!(driver.stats(rx_pkts), 4);
+        driver.stats_ctrl(0x0);
+        assert_eq!(driver.stats(rx_pkts), 0);
+        driver.stats_ctrl(0x2);
+        assert_eq!(driver.stats(tx_bytes), 1024);
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_int_coal_test() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let irqs = etha.irqs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(8, 1024, 4, CongestionAction::Blocking);
+        let base = default_ch.rx.base;
+        let ring = |offset: usize| addr(base + offset);
+        let cnt = Arc::new(AtomicUsize::new(0));
+        let handler_cnt = cnt.clone();
+        irqs.lock()
+            .unwrap()
+            .bind(0, move |_| {
+                handler_cnt.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+        reg.write(ring(RING_INTM_OFFSET), RING_INT_DONE).unwrap();
+        reg.write(ring(RING_INT_COAL_OFFSET), 4).unwrap();
+        driver.rx_en();
+        driver.tx_en();
+
+        let mut frame_send = vec![0x5a; 128];
+        frame_send[12..14].copy_from_slice(&0x88b5u16.to_be_bytes());
+        let mut loopback = |n: usize| {
+            for _ in 0..n {
+                default_ch.tx.send(&[&frame_send], false);
+                loop {
+                    if let Some(r) = default_ch.rx.receive() {
+                        let n_blocks = r.len();
+                        default_ch.rx.release(n_blocks);
+                        break;
+                    }
+                }
+            }
+        };
+        let wait_irqs = |n: usize| {
+            while cnt.load(Ordering::SeqCst) != n {
+                std::thread::yield_now();
+            }
+        };
+
+        loopback(4);
+        wait_irqs(1);
+        assert_eq!(reg.read(ring(RING_INT_STATUS_OFFSET)), Some(RING_INT_DONE));
+        reg.write(ring(RING_INT_STATUS_OFFSET), RING_INT_DONE)
+            .unwrap();
+        assert_eq!(reg.read(ring(RING_INT_STATUS_OFFSET)), Some(0));
+
+        //below the frame threshold until the timer is enabled
+        loopback(3);
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(cnt.load(Ordering::SeqCst), 1);
+        reg.write(ring(RING_INT_COAL_OFFSET), 1 << 16 | 4).unwrap();
+        wait_irqs(2);
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_int_coal_virtual_test() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let irqs = etha.irqs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(8, 1024, 4, CongestionAction::Blocking);
+        let base = default_ch.rx.base;
+        let ring = |offset: usize| addr(base + offset);
+        let cnt = Arc::new(AtomicUsize::new(0));
+        let handler_cnt = cnt.clone();
+        irqs.lock()
+            .unwrap()
+            .bind(0, move |_| {
+                handler_cnt.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+        driver.clock_virtual(true);
+        //1us timer, the frame threshold is never reached
+        reg.write(ring(RING_INTM_OFFSET), RING_INT_DONE).unwrap();
+        reg.write(ring(RING_INT_COAL_OFFSET), 1 << 16 | 64).unwrap();
+        driver.rx_en();
+        driver.tx_en();
+
+        let mut frame_send = vec![0x5a; 128];
+        frame_send[12..14].copy_from_slice(&0x88b5u16.to_be_bytes());
+        let mut loopback = |n: usize| {
+            for _ in 0..n {
+                default_ch.tx.send(&[&frame_send], false);
+                loop {
+                    if let Some(r) = default_ch.rx.receive() {
+                        let n_blocks = r.len();
+                        default_ch.rx.release(n_blocks);
+                        break;
+                    }
+                }
+            }
+        };
+
+        //the timer starts at the first frame and only runs with received frames
+        let wire = crate::mac::wire_time(frame_send.len());
+        let n = 1000usize.div_ceil(wire as usize);
+        loopback(n);
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(cnt.load(Ordering::SeqCst), 0);
+        loopback(1);
+        while cnt.load(Ordering::SeqCst) != 1 {
+            std::thread::yield_now();
+        }
+        assert_eq!(reg.read(ring(RING_INT_STATUS_OFFSET)), Some(RING_INT_DONE));
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_irq_vector_test() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let irqs = etha.irqs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(8, 1024, 4, CongestionAction::Blocking);
+        let irq = |offset: usize| addr(IRQ_REGS_RANGE.start + offset);
+        let cnts: Arc<[AtomicUsize; 2]> = Arc::new(Default::default());
+        for (i, v) in [50, 3].into_iter().enumerate() {
+            let cnts = cnts.clone();
+            irqs.lock()
+                .unwrap()
+                .bind(v, move |_| {
+                    cnts[i].fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap();
+        }
+        //done of rx ring 0 to vector 50, which is masked
+        reg.write(irq(IRQ_MAP_OFFSET), 50 << 24).unwrap();
+        reg.write(irq(IRQ_VEC_MASK_HI_OFFSET), 1 << 18).unwrap();
+        reg.write(addr(default_ch.rx.base + RING_INTM_OFFSET), RING_INT_DONE)
+            .unwrap();
+        driver.rx_en();
+        driver.tx_en();
+
+        let mut frame_send = vec![0x5a; 128];
+        frame_send[12..14].copy_from_slice(&0x88b5u16.to_be_bytes());
+        default_ch.tx.send(&[&frame_send], false);
+        loop {
+            if let Some(r) = default_ch.rx.receive() {
+                let n_blocks = r.len();
+                default_ch.rx.release(n_blocks);
+                break;
+            }
+        }
+        while reg.read(irq(IRQ_VEC_PENDING_HI_OFFSET)) != Some(1 << 18) {
+            std::thread::yield_now();
+        }
+        assert_eq!(cnts[0].load(Ordering::SeqCst), 0);
+        reg.write(irq(IRQ_VEC_MASK_HI_OFFSET), 0).unwrap();
+        while cnts[0].load(Ordering::SeqCst) != 1 {
+            std::thread::yield_now();
+        }
+
+        //link down of port 0 to vector 3
+        assert_eq!(reg.read(irq(IRQ_LINK_UP_OFFSET)), Some(0x1));
+        reg.write(irq(IRQ_MAP_OFFSET + IrqTable::<CHS>::LINK_CAUSE), 3)
+            .unwrap();
+        reg.write(irq(IRQ_LINK_MASK_OFFSET), 0x1).unwrap();
+        reg.irq.set_link(0, false).unwrap();
+        while cnts[1].load(Ordering::SeqCst) != 1 {
+            std::thread::yield_now();
+        }
+        assert_eq!(reg.read(irq(IRQ_LINK_STATUS_OFFSET)), Some(0x1));
+        assert_eq!(reg.read(irq(IRQ_LINK_UP_OFFSET)), Some(0));
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_qinq_filter_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
+        let mut vlan_ch = driver.alloc_vlan_ch(2, 1024, 1, CongestionAction::Blocking, true, 100);
+        driver.rx_en();
+        driver.tx_en();
+
+        for (s_vid, to_vlan_ch) in [(100u16, true), (101, false)] {
+            let frame = ipv4_tcp_frame(&[0x5a; 64]);
+            let frame_send = [
+                &frame[..12],
+                &0x88a8u16.to_be_bytes(),
+                &(0xa000 | s_vid).to_be_bytes(),
+                &0x8100u16.to_be_bytes(),
+                &0x3123u16.to_be_bytes(),
+                &frame[12..],
+            ]
+            .concat();
+            default_ch.tx.send(&[&frame_send], true).unwrap();
+            let ch = if to_vlan_ch {
+                &mut vlan_ch
+            } else {
+                &mut default_ch
+            };
+            loop {
+                if let Some((desc, r)) = ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert_eq!(r.concat(), frame_send);
+                    assert_eq!(desc.l2.l2_is_svlan(), 1);
+                    assert_eq!(desc.l2.l2_svlan_vid(), s_vid as u32);
+                    assert_eq!(desc.l2.l2_svlan_flags() >> 1, 5);
+                    assert_eq!(desc.l2.l2_is_vlan(), 1);
+                    assert_eq!(desc.l2.l2_vlan_vid(), 0x123);
+                    assert_eq!(desc.l2.l2_vlan_flags() >> 1, 1);
+                    assert_eq!(desc.l2.l2_etype(), 0x0800);
+                    assert_eq!(desc.l2.l2_header_len(), 22);
+                    ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_vlan_offload_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
+        driver.rx_en();
+        driver.tx_en();
+
+        let frame_send = ipv4_tcp_frame(&[0x5a; 64]);
+        let frame_tagged = [
+            &frame_send[..12],
+            &0x8100u16.to_be_bytes(),
+            &0x2064u16.to_be_bytes(),
+            &frame_send[12..],
+        ]
+        .concat();
+        for strip in [false, true] {
+            driver.rx_vlan_strip(strip);
+            let mut ctrl = TxCtrlDesc::default();
+            ctrl.set_vlan_insert(1);
+            ctrl.set_vlan_tci(0x2064);
+            default_ch
+                .tx
+                .send_with_ctrl(&[&frame_send], ctrl, true)
+                .unwrap();
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    let data = r.concat();
+                    let header_len = if strip { 14 } else { 18 };
+                    assert_eq!(&data, if strip { &frame_send } else { &frame_tagged });
+                    assert_eq!(desc.frame.total_size() as usize, data.len());
+                    assert_eq!(desc.l2.l2_is_vlan(), 1);
+                    assert_eq!(desc.l2.l2_vlan_vid(), 0x64);
+                    assert_eq!(desc.l2.l2_vlan_flags() >> 1, 1);
+                    assert_eq!(desc.l2.l2_vlan_stripped(), strip as u32);
+                    assert_eq!(desc.l2.l2_etype(), 0x0800);
+                    assert_eq!(desc.l2.l2_header_len(), header_len);
+                    assert_eq!(
+                        desc.l2.l2_payload_len() as usize,
+                        data.len() - header_len as usize
+                    );
+                    assert_eq!(desc.l3.l3_payload_len(), 20 + 64);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_hdr_split_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(4, 1024, 1, CongestionAction::Blocking);
+        default_ch.rx.enable_hdr_split(64);
+        driver.rx_en();
+        driver.tx_en();
+
+        let frame_tcp = ipv4_tcp_frame(&[0x5a; 100]);
+        let frame_raw = [&frame_tcp[..12], &[0x88, 0xb5], &[0xa5; 100][..]].concat();
+        for frame in [&frame_tcp, &frame_raw] {
+            default_ch.tx.send(&[frame], true).unwrap();
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    let data = r.concat();
+                    assert_eq!(desc.frame.total_size() as usize, frame.len());
+                    if frame == &frame_tcp {
+                        assert_eq!(desc.hdr.hdr_split(), 1);
+                        assert_eq!(desc.hdr.hdr_fallback(), 0);
+                        assert_eq!(desc.hdr.hdr_len(), 54);
+                        assert_eq!(default_ch.rx.hdr(&desc), &frame[..54]);
+                        assert_eq!(&data, &frame[54..]);
+                    } else {
+                        assert_eq!(desc.hdr.hdr_split(), 0);
+                        assert_eq!(desc.hdr.hdr_fallback(), 1);
+                        assert_eq!(&data, frame);
+                    }
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_hdr_only_full_ring_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 3, CongestionAction::Blocking);
+        default_ch.rx.enable_hdr_split(64);
+        driver.rx_en();
+        driver.tx_en();
+
+        //frames without payload leave nothing in the data buffers after the split,
+        //one more than the ring holds blocks until an entry is released
+        let frames = (0..3)
+            .map(|i| ipv4_tcp_segment(1000 + i, 0x10, &[]))
+            .collect::<Vec<_>>();
+        for frame in frames.iter() {
+            default_ch.tx.send(&[frame], true).unwrap();
+        }
+        let blocking = StatsRegs::<CHS>::queue_counter_offset(0, QueueCounter::CongestionBlocking);
+        while driver.stats(blocking) == 0 {
+            std::thread::yield_now();
+        }
+        for frame in frames.iter() {
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert!(r.concat().is_empty());
+                    assert_eq!(desc.hdr.hdr_split(), 1);
+                    assert_eq!(default_ch.rx.hdr(&desc), &frame[..]);
+                    assert_eq!(desc.frame.total_size() as usize, frame.len());
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_lro_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
+        let abort = etha.abort();
+        let reg = etha.regs();
+        let t = etha.spawn(Some(CoreId { id: 0 }));
+
+        let driver = SwEtha::new(&reg);
+        let mut default_ch = driver.alloc_default_ch(16, 256, 4, CongestionAction::Blocking);
+        default_ch.rx.enable_lro(1024, 100_000);
+        driver.rx_en();
+        driver.tx_en();
+
+        //3 in order segments are merged, the out of order one times out alone
+        let segs = [
+            ipv4_tcp_acked(ipv4_tcp_segment(1000, 0x10, &[0x1; 100]), 500, 1000),
+            ipv4_tcp_acked(ipv4_tcp_segment(1100, 0x10, &[0x2; 100]), 600, 2000),
+            ipv4_tcp_acked(ipv4_tcp_segment(1200, 0x10, &[0x3; 100]), 700, 3000),
+            ipv4_tcp_acked(ipv4_tcp_segment(2000, 0x10, &[0x4; 100]), 800, 4000),
+        ];
+        for seg in segs.iter() {
+            default_ch.tx.send(&[seg], true).unwrap();
+        }
+        let merged = ipv4_tcp_merged(&segs[..3]);
+        for (expect, n_segs) in [(&merged, 3), (&segs[3], 1)] {
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    let data = r.concat();
+                    assert_eq!(&data, expect);
+                    assert_eq!(desc.frame.total_size() as usize, expect.len());
+                    assert_eq!(desc.l4.l4_lro_segs(), n_segs);
+                    assert_eq!(desc.l3.l3_payload_len() as usize, expect.len() - 34);
+                    let ip = smoltcp::wire::Ipv4Packet::new_checked(&data[14..]).unwrap();
+                    assert!(ip.verify_checksum());
+                    assert_eq!(desc.status.l3_csum_err(), 0);
+                    //the merged tcp checksum is left to the stack
+                    assert_eq!(desc.status.l4_csum_valid(), (n_segs == 1) as u32);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+
+        //push flushes the aggregation
+        let segs = [
+            ipv4_tcp_acked(ipv4_tcp_segment(3000, 0x10, &[0x5; 100]), 900, 1000),
+            ipv4_tcp_acked(ipv4_tcp_segment(3100, 0x18, &[0x6; 100]), 1000, 500),
+        ];
+        default_ch.rx.enable_lro(1024, 1_000_000);
+        for seg in segs.iter() {
+            default_ch.tx.send(&[seg], true).unwrap();
+        }
+        loop {
+            if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                let n_blocks = r.len();
+                assert_eq!(r.concat(), ipv4_tcp_merged(&segs));
+                assert_eq!(desc.l4.l4_lro_segs(), 2);
+                assert_eq!(desc.l4.l4_tcp_flags(), 0x18);
+                default_ch.rx.release(n_blocks);
+                break;
+            }
+        }
+
+        //in order segments of one flow on two vlans are not merged
+        let vlan = |vid: u16, frame: Vec<u8>| {
+            [
+                &frame[..12],
+                &0x8100u16.to_be_bytes(),
+                &vid.to_be_bytes(),
+                &frame[12..],
+            ]
+            .concat()
+        };
+        let segs = [
+            vlan(100, ipv4_tcp_segment(4000, 0x10, &[0x7; 100])),
+            vlan(200, ipv4_tcp_segment(4100, 0x18, &[0x8; 100])),
+        ];
+        for seg in segs.iter() {
+            default_ch.tx.send(&[seg], true).unwrap();
+        }
+        for seg in segs.iter() {
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert_eq!(&r.concat(), seg);
+                    assert_eq!(desc.l4.l4_lro_segs(), 1);
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
+            }
+        }
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn loopback_ptp_ts_test() {
+        let etha = Etha::new(smoltcp::phy::Loopback::new(smoltcp::phy::Medium::Ethernet));
         let abort = etha.abort();
         let reg = etha.regs();
         let t = etha.spawn(Some(CoreId { id: 0 }));
 
         let driver = SwEtha::new(&reg);
-        let _default_ch = driver.alloc_default_ch(1, 1024, 1, CongestionAction::Blocking);
-        let mut ecpri_ch = driver.alloc_et_ch(1, 2048, 1, CongestionAction::Blocking, 0xaefe);
+        let mut default_ch = driver.alloc_default_ch(2, 1024, 1, CongestionAction::Blocking);
         driver.rx_en();
         driver.tx_en();
+        driver.clock_virtual(true);
+        driver.phc_set(0x1_0000_0000);
+        driver.phc_adjust(-0x100);
+        assert_eq!(driver.phc(), 0xffff_ff00);
+        driver.rx_ts_mode(RxTsMode::Ptp);
 
-        ecpri_ch.tx.send(&[&frame_send], false);
-        loop {
-            if let Some(r) = ecpri_ch.rx.receive().take() {
-                let data = r.concat();
-                let frame = smoltcp::wire::EthernetFrame::new_checked(&data).unwrap();
-                println!(
-                    "{}",
-                    smoltcp::wire::PrettyPrinter::<smoltcp::wire::EthernetFrame<&[u8]>>::new(
-                        "", &frame
-                    )
-                );
-                assert_eq!(data.as_slice(), frame_send);
-                break;
+        let frame_ptp = [&[0xff; 12][..], &[0x88, 0xf7], &[0x5a; 46]].concat();
+        let frame_tcp = ipv4_tcp_frame(&[0x5a; 64]);
+        let mut last = (0, 0);
+        for (i, (frame, ts)) in [(&frame_ptp, true), (&frame_tcp, false), (&frame_ptp, true)]
+            .into_iter()
+            .enumerate()
+        {
+            //frames are sent at different times, and arrive after their wire time
+            reg.clock.advance_to(reg.clock.now() + 1000 * (i as u64 + 1));
+            let send = driver.phc();
+            let mut ctrl = TxCtrlDesc::default();
+            ctrl.set_ts_en(1);
+            let resp = default_ch.tx.send_with_ctrl(&[frame], ctrl, true).unwrap();
+            assert_eq!(resp.ts_valid(), 1);
+            assert_eq!(resp.ts.ts(), send);
+            assert!(send > last.0);
+            last.0 = send;
+            loop {
+                if let Some((desc, r)) = default_ch.rx.receive_with_desc() {
+                    let n_blocks = r.len();
+                    assert_eq!(&r.concat(), frame);
+                    assert_eq!(desc.status.ts_valid(), ts as u32);
+                    if ts {
+                        let arrival = send + crate::mac::wire_time(frame.len());
+                        assert_eq!(desc.ts.ts(), arrival);
+                        assert!(arrival > last.1);
+                        last.1 = arrival;
+                    } else {
+                        assert_eq!(desc.ts.ts(), 0);
+                    }
+                    default_ch.rx.release(n_blocks);
+                    break;
+                }
             }
         }
+        let resp = default_ch.tx.send(&[&frame_ptp], true).unwrap();
+        assert_eq!(resp.ts_valid(), 0);
         abort.abort();
         t.join().unwrap();
     }
@@ -308,8 +1465,13 @@ mod tests_driver_helper {
     pub(super) use crate::etha::desc::buffer::*;
     pub(super) use crate::etha::desc::rx::*;
     pub(super) use crate::etha::desc::tx::*;
+    pub(super) use crate::etha::irq_table::IrqTable;
     pub(super) use crate::etha::reg_if::global::*;
+    pub(super) use crate::etha::reg_if::irq::*;
     pub(super) use crate::etha::reg_if::rx::*;
+    pub(super) use crate::etha::reg_if::stats::*;
+    pub(super) use crate::etha::reg_if::switch::*;
+    pub(super) use crate::etha::reg_if::tx::*;
     pub(super) use crate::etha::reg_if::*;
     pub(super) use crate::reg_if::{
         ring::{sw_ring::*, *},
@@ -326,6 +1488,7 @@ mod tests_driver_helper {
         id: usize,
         mem_size: usize,
         buffers: Vec<Vec<u8>>,
+        hdrs: Vec<u8>,
         resp_ptr: usize,
         ring: SwQueue<RegT, u64, [DescEntryT; RX_DESC_ENTRY_SIZE / DESC_ENTRY_SIZE]>,
     }
@@ -336,6 +1499,7 @@ mod tests_driver_helper {
                 id,
                 mem_size,
                 buffers: vec![vec![0; mem_size]; ring_size],
+                hdrs: vec![],
                 resp_ptr: 0,
                 ring: SwQueue::new(
                     regs,
@@ -371,7 +1535,34 @@ mod tests_driver_helper {
             println!("SwRxQueue[{}]: full: {}", self.id, self.r_full());
             assert_eq!(self.r_full(), true);
         }
+        //one header buffer for each ring entry
+        pub(super) fn enable_hdr_split(&mut self, hdr_size: usize) {
+            self.hdrs = vec![0; self.ring_size * hdr_size];
+            let base = self.hdrs.as_ptr() as u64;
+            let queue = rx_queue_base(self.id);
+            self.regs
+                .write(addr(queue + 1), base & 0xffff_ffff)
+                .unwrap();
+            self.regs.write(addr(queue + 2), base >> 32).unwrap();
+            self.regs
+                .write(addr(queue), (hdr_size << 16 | 1) as u64)
+                .unwrap();
+        }
+        pub(super) fn enable_lro(&self, max_size: usize, usecs: u64) {
+            let queue = rx_queue_base(self.id);
+            self.regs.write(addr(queue + 4), usecs).unwrap();
+            self.regs
+                .write(addr(queue + 3), (max_size << 16 | 1) as u64)
+                .unwrap();
+        }
+        pub(super) fn hdr(&self, desc: &RxResultDesc) -> &[u8] {
+            let offset = (desc.hdr.hdr_addr() - self.hdrs.as_ptr() as u64) as usize;
+            &self.hdrs[offset..offset + desc.hdr.hdr_len() as usize]
+        }
         pub(super) fn receive<'a>(&'a mut self) -> Option<Vec<&'a [u8]>> {
+            self.receive_with_desc().map(|(_, buffer)| buffer)
+        }
+        pub(super) fn receive_with_desc(&mut self) -> Option<(RxResultDesc, Vec<&[u8]>)> {
             if self.r_p_valids() > 0 {
                 let head = unsafe { *self.r_get_resp_at(self.resp_ptr).unwrap() };
                 assert!(
@@ -402,7 +1593,7 @@ mod tests_driver_helper {
                     buffer.push(&self.buffers[idx][..resp.frame.size() as usize])
                 }
                 self.resp_ptr = self.r_incr_ptr(self.resp_ptr, head.frame.n_blocks() as usize + 1);
-                Some(buffer)
+                Some((head, buffer))
             } else {
                 None
             }
@@ -468,6 +1659,14 @@ mod tests_driver_helper {
             assert_eq!(self.r_empty(), true);
         }
         pub(super) fn send(&mut self, data: &[&[u8]], blocking: bool) -> Option<TxResultDesc> {
+            self.send_with_ctrl(data, TxCtrlDesc::default(), blocking)
+        }
+        pub(super) fn send_with_ctrl(
+            &mut self,
+            data: &[&[u8]],
+            ctrl: TxCtrlDesc,
+            blocking: bool,
+        ) -> Option<TxResultDesc> {
             loop {
                 if self.r_p_valids() >= data.len() {
                     break;
@@ -480,7 +1679,7 @@ mod tests_driver_helper {
                         addr: b.as_ptr() as u64,
                         size: b.len(),
                     }),
-                    ctrl: TxCtrlDesc::default(),
+                    ctrl,
                 })
                 .collect::<Vec<_>>();
             let n_reqs = reqs.len();
@@ -556,6 +1755,9 @@ mod tests_driver_helper {
         regs: Arc<RegT>,
         ch_id: AtomicUsize,
         et_filter_id: AtomicUsize,
+        vlan_filter_id: AtomicUsize,
+        tp5_filter_id: AtomicUsize,
+        ecpri_filter_id: AtomicUsize,
         default_ch: AtomicUsize,
     }
     impl SwEtha {
@@ -564,6 +1766,9 @@ mod tests_driver_helper {
                 regs: regs.clone(),
                 ch_id: AtomicUsize::new(0),
                 et_filter_id: AtomicUsize::new(0),
+                vlan_filter_id: AtomicUsize::new(0),
+                tp5_filter_id: AtomicUsize::new(0),
+                ecpri_filter_id: AtomicUsize::new(0),
                 default_ch: AtomicUsize::new(0),
             }
         }
@@ -577,6 +1782,21 @@ mod tests_driver_helper {
             assert!(id < RX_ET_FILTERS);
             id
         }
+        fn alloc_vlan_filter(&self) -> usize {
+            let id = self.vlan_filter_id.fetch_add(1, Ordering::SeqCst);
+            assert!(id < RX_VLAN_FILTERS);
+            id
+        }
+        fn alloc_tp5_filter(&self) -> usize {
+            let id = self.tp5_filter_id.fetch_add(1, Ordering::SeqCst);
+            assert!(id < RX_TP5_FILTERS);
+            id
+        }
+        fn alloc_ecpri_filter(&self) -> usize {
+            let id = self.ecpri_filter_id.fetch_add(1, Ordering::SeqCst);
+            assert!(id < RX_ECPRI_FILTERS);
+            id
+        }
         pub(super) fn alloc_default_ch(
             &self,
             rx_size: usize,
@@ -629,15 +1849,370 @@ mod tests_driver_helper {
             ch.init_check();
             ch
         }
+        pub(super) fn alloc_vlan_ch(
+            &self,
+            rx_size: usize,
+            rx_mem_size: usize,
+            tx_size: usize,
+            congest: CongestionAction,
+            svlan: bool,
+            vid: u16,
+        ) -> SwCh {
+            let id = self.alloc_ch();
+            let filter_id = self.alloc_vlan_filter();
+            let ch = SwCh::new(
+                SwRxQueue::new(&self.regs, id, rx_size, rx_mem_size),
+                SwTxQueue::new(&self.regs, id, tx_size),
+            );
+            //match vid only
+            self.regs
+                .write(
+                    addr(RX_REGS_RANGE.start + self.regs.rx.vlan_filter_offset(filter_id)),
+                    ((vid as usize & 0xfff)
+                        | (svlan as usize) << 15
+                        | 1 << 25
+                        | set_filter_queue_id(id)
+                        | set_filter_congestion_action(congest)
+                        | set_filter_en(true)) as u64,
+                )
+                .unwrap();
+            ch.init();
+            ch.init_check();
+            ch
+        }
+        pub(super) fn alloc_tp5_ch(
+            &self,
+            rx_size: usize,
+            rx_mem_size: usize,
+            tx_size: usize,
+            congest: CongestionAction,
+            inner: bool,
+            dst_port: u16,
+        ) -> SwCh {
+            let id = self.alloc_ch();
+            let filter_id = self.alloc_tp5_filter();
+            let ch = SwCh::new(
+                SwRxQueue::new(&self.regs, id, rx_size, rx_mem_size),
+                SwTxQueue::new(&self.regs, id, tx_size),
+            );
+            let base = RX_REGS_RANGE.start + self.regs.rx.tp5_filter_offset(filter_id);
+            self.regs
+                .write(addr(base + 8), ((dst_port as usize) << 16) as u64)
+                .unwrap();
+            //match ipv4 dst port only
+            self.regs
+                .write(
+                    addr(base + 9),
+                    ((inner as usize) << 12
+                        | 0xf << 24
+                        | set_filter_queue_id(id)
+                        | set_filter_congestion_action(congest)
+                        | set_filter_en(true)) as u64,
+                )
+                .unwrap();
+            ch.init();
+            ch.init_check();
+            ch
+        }
+        pub(super) fn alloc_ecpri_ch(
+            &self,
+            rx_size: usize,
+            rx_mem_size: usize,
+            tx_size: usize,
+            congest: CongestionAction,
+            msg_type: u8,
+            ids: std::ops::RangeInclusive<u16>,
+        ) -> SwCh {
+            let id = self.alloc_ch();
+            let filter_id = self.alloc_ecpri_filter();
+            let ch = SwCh::new(
+                SwRxQueue::new(&self.regs, id, rx_size, rx_mem_size),
+                SwTxQueue::new(&self.regs, id, tx_size),
+            );
+            let base = RX_REGS_RANGE.start + self.regs.rx.ecpri_filter_offset(filter_id);
+            self.regs
+                .write(
+                    addr(base + 1),
+                    (*ids.start() as u64) | (*ids.end() as u64) << 16,
+                )
+                .unwrap();
+            self.regs
+                .write(
+                    addr(base),
+                    (msg_type as usize
+                        | set_filter_queue_id(id)
+                        | set_filter_congestion_action(congest)
+                        | set_filter_en(true)) as u64,
+                )
+                .unwrap();
+            ch.init();
+            ch.init_check();
+            ch
+        }
+        //flow is (protocol, src, dst, src port, dst port)
+        pub(super) fn alloc_flow_ch(
+            &self,
+            rx_size: usize,
+            rx_mem_size: usize,
+            tx_size: usize,
+            congest: CongestionAction,
+            flow: (u8, [u8; 4], [u8; 4], u16, u16),
+        ) -> SwCh {
+            let id = self.alloc_ch();
+            let ch = SwCh::new(
+                SwRxQueue::new(&self.regs, id, rx_size, rx_mem_size),
+                SwTxQueue::new(&self.regs, id, tx_size),
+            );
+            let base = RX_REGS_RANGE.start + self.regs.rx.flow_table_offset();
+            self.regs
+                .write(
+                    addr(base + 10),
+                    (set_filter_queue_id(id) | set_filter_congestion_action(congest)) as u64,
+                )
+                .unwrap();
+            assert_eq!(self.flow_table_cmd(flow, FlowCmdOp::Add).0, 0);
+            ch.init();
+            ch.init_check();
+            ch
+        }
+        pub(super) fn flow_table_cmd(
+            &self,
+            flow: (u8, [u8; 4], [u8; 4], u16, u16),
+            op: FlowCmdOp,
+        ) -> (u64, u64) {
+            let base = RX_REGS_RANGE.start + self.regs.rx.flow_table_offset();
+            let (protocol, src, dst, src_port, dst_port) = flow;
+            self.regs
+                .write(addr(base), u32::from_le_bytes(src) as u64)
+                .unwrap();
+            self.regs
+                .write(addr(base + 4), u32::from_le_bytes(dst) as u64)
+                .unwrap();
+            self.regs
+                .write(addr(base + 8), src_port as u64 | (dst_port as u64) << 16)
+                .unwrap();
+            self.regs.write(addr(base + 9), protocol as u64).unwrap();
+            self.regs
+                .write(addr(base + FLOW_CMD_OFFSET), op as u64)
+                .unwrap();
+            let status = self.regs.read(addr(base + 12)).unwrap();
+            (status & 0xf, status >> 16)
+        }
+        pub(super) fn ecpri_udp_en(&self, port: u16) {
+            self.regs
+                .write(
+                    addr(RX_REGS_RANGE.start + self.regs.rx.ecpri_offset()),
+                    set_filter_en(true) as u64 | port as u64,
+                )
+                .unwrap();
+        }
+        pub(super) fn tunnel_en(&self, vxlan: bool, gre: bool, ipip: bool) {
+            self.regs
+                .write(
+                    addr(RX_REGS_RANGE.start + self.regs.rx.tunnel_offset()),
+                    (vxlan as u64) | (gre as u64) << 1 | (ipip as u64) << 2,
+                )
+                .unwrap();
+        }
+        pub(super) fn alloc_rss_chs(
+            &self,
+            n: usize,
+            rx_size: usize,
+            rx_mem_size: usize,
+            tx_size: usize,
+            key: &[u8; RSS_KEY_SIZE],
+        ) -> Vec<SwCh> {
+            let ids = (0..n).map(|_| self.alloc_ch()).collect::<Vec<_>>();
+            let chs = ids
+                .iter()
+                .map(|id| {
+                    let ch = SwCh::new(
+                        SwRxQueue::new(&self.regs, *id, rx_size, rx_mem_size),
+                        SwTxQueue::new(&self.regs, *id, tx_size),
+                    );
+                    ch.init();
+                    ch.init_check();
+                    ch
+                })
+                .collect::<Vec<_>>();
+            let base = RX_REGS_RANGE.start + self.regs.rx.rss_offset();
+            for (i, k) in key.chunks(4).enumerate() {
+                self.regs
+                    .write(
+                        addr(base + RSS_KEY_OFFSET + i),
+                        u32::from_le_bytes(k.try_into().unwrap()) as u64,
+                    )
+                    .unwrap();
+            }
+            for i in 0..RSS_RETA_REGS {
+                let entries = (0..RSS_RETA_ENTRIES_PER_REG)
+                    .map(|j| ids[(i * RSS_RETA_ENTRIES_PER_REG + j) % n] << (8 * j))
+                    .fold(0, |acc, e| acc | e);
+                self.regs
+                    .write(addr(base + RSS_RETA_OFFSET + i), entries as u64)
+                    .unwrap();
+            }
+            //enable all hash types
+            self.regs
+                .write(addr(base), (set_filter_en(true) | 0x3f) as u64)
+                .unwrap();
+            chs
+        }
         pub(super) fn rx_en(&self) {
             self.regs
                 .write(addr(GLOBAL_REGS_RANGE.start + GLOBAL_RX_EN_OFFSET), 0x1)
                 .unwrap();
         }
+        pub(super) fn rx_vlan_strip(&self, en: bool) {
+            self.regs
+                .write(
+                    addr(GLOBAL_REGS_RANGE.start + GLOBAL_RX_VLAN_STRIP_OFFSET),
+                    en as u64,
+                )
+                .unwrap();
+        }
         pub(super) fn tx_en(&self) {
             self.regs
                 .write(addr(GLOBAL_REGS_RANGE.start + GLOBAL_TX_EN_OFFSET), 0x1)
                 .unwrap();
         }
+        //rate in Mbps, burst in bytes, port shaper if id is none
+        pub(super) fn tx_shaper(&self, id: Option<usize>, rate: usize, burst: usize) {
+            let base = TX_REGS_RANGE.start
+                + id.map_or(
+                    TxRegs::<CHS>::port_shaper_offset(),
+                    TxRegs::<CHS>::shaper_offset,
+                );
+            self.regs.write(addr(base + 1), burst as u64).unwrap();
+            self.regs
+                .write(addr(base), (set_filter_en(true) | rate) as u64)
+                .unwrap();
+        }
+        pub(super) fn clock_virtual(&self, en: bool) {
+            self.regs
+                .write(
+                    addr(GLOBAL_REGS_RANGE.start + GLOBAL_CLOCK_CTRL_OFFSET),
+                    en as u64,
+                )
+                .unwrap();
+        }
+        pub(super) fn clock(&self) -> u64 {
+            let lo = self
+                .regs
+                .read(addr(GLOBAL_REGS_RANGE.start + GLOBAL_CLOCK_LO_OFFSET))
+                .unwrap();
+            let hi = self
+                .regs
+                .read(addr(GLOBAL_REGS_RANGE.start + GLOBAL_CLOCK_HI_OFFSET))
+                .unwrap();
+            hi << 32 | lo
+        }
+        pub(super) fn rx_ts_mode(&self, mode: RxTsMode) {
+            self.regs
+                .write(
+                    addr(GLOBAL_REGS_RANGE.start + GLOBAL_PTP_CTRL_OFFSET),
+                    mode as u64,
+                )
+                .unwrap();
+        }
+        pub(super) fn phc_set(&self, time: u64) {
+            let base = GLOBAL_REGS_RANGE.start;
+            self.regs
+                .write(addr(base + GLOBAL_PTP_SET_LO_OFFSET), time & 0xffff_ffff)
+                .unwrap();
+            self.regs
+                .write(addr(base + GLOBAL_PTP_SET_HI_OFFSET), time >> 32)
+                .unwrap();
+        }
+        pub(super) fn phc_adjust(&self, delta: i32) {
+            self.regs
+                .write(
+                    addr(GLOBAL_REGS_RANGE.start + GLOBAL_PTP_ADJ_OFFSET),
+                    delta as u32 as u64,
+                )
+                .unwrap();
+        }
+        pub(super) fn phc(&self) -> u64 {
+            let base = GLOBAL_REGS_RANGE.start;
+            let lo = self
+                .regs
+                .read(addr(base + GLOBAL_PTP_TIME_LO_OFFSET))
+                .unwrap();
+            let hi = self
+                .regs
+                .read(addr(base + GLOBAL_PTP_TIME_HI_OFFSET))
+                .unwrap();
+            hi << 32 | lo
+        }
+        pub(super) fn stats_ctrl(&self, ctrl: u64) {
+            self.regs
+                .write(addr(STATS_REGS_RANGE.start + STATS_CTRL_OFFSET), ctrl)
+                .unwrap();
+        }
+        //reading lo latches hi
+        pub(super) fn stats(&self, offset: usize) -> u64 {
+            let base = STATS_REGS_RANGE.start + offset;
+            let lo = self.regs.read(addr(base)).unwrap();
+            let hi = self.regs.read(addr(base + 1)).unwrap();
+            hi << 32 | lo
+        }
+        pub(super) fn switch_en(&self, learn: bool) {
+            self.regs
+                .write(addr(SWITCH_REGS_RANGE.start), 0x1 | (learn as u64) << 1)
+                .unwrap();
+        }
+        //returns result, entry count and port
+        pub(super) fn fdb_cmd(
+            &self,
+            mac: [u8; 6],
+            port: usize,
+            is_static: bool,
+            op: FdbCmdOp,
+        ) -> (u64, u64, u64) {
+            let base = SWITCH_REGS_RANGE.start;
+            self.regs
+                .write(
+                    addr(base + 2),
+                    u32::from_be_bytes(mac[2..].try_into().unwrap()) as u64,
+                )
+                .unwrap();
+            self.regs
+                .write(addr(base + 3), u16::from_be_bytes([mac[0], mac[1]]) as u64)
+                .unwrap();
+            self.regs
+                .write(addr(base + 4), (is_static as u64) << 8 | port as u64)
+                .unwrap();
+            self.regs
+                .write(addr(base + FDB_CMD_OFFSET), op as u64)
+                .unwrap();
+            let status = self.regs.read(addr(base + FDB_CMD_OFFSET + 1)).unwrap();
+            let entry = self.regs.read(addr(base + 4)).unwrap();
+            (status & 0xf, status >> 16, entry & 0x7)
+        }
+        pub(super) fn tx_arb(&self, mode: TxArbMode) {
+            self.regs
+                .write(
+                    addr(GLOBAL_REGS_RANGE.start + GLOBAL_TX_ARB_OFFSET),
+                    mode as u64,
+                )
+                .unwrap();
+        }
+        pub(super) fn tx_arb_ch(&self, id: usize, pri: usize, weight: usize) {
+            let pri_addr =
+                addr(GLOBAL_REGS_RANGE.start + GLOBAL_TX_ARB_PRI_OFFSET + id / TX_ARB_PRI_PER_REG);
+            let shift = id % TX_ARB_PRI_PER_REG * 4;
+            let v = self.regs.read(pri_addr).unwrap() & !(0xf << shift);
+            self.regs
+                .write(pri_addr, v | (pri as u64) << shift)
+                .unwrap();
+            let weight_addr = addr(
+                GLOBAL_REGS_RANGE.start + GLOBAL_TX_ARB_WEIGHT_OFFSET + id / TX_ARB_WEIGHT_PER_REG,
+            );
+            let shift = id % TX_ARB_WEIGHT_PER_REG * 8;
+            let v = self.regs.read(weight_addr).unwrap() & !(0xff << shift);
+            self.regs
+                .write(weight_addr, v | (weight as u64) << shift)
+                .unwrap();
+        }
     }
 }
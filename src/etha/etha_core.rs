@@ -1,42 +1,69 @@
-use super::reg_if::{global::LockedEthaGlobalRegs, TopRegs};
+use super::irq_table::IrqTable;
+use super::reg_if::{
+    global::LockedEthaGlobalRegs,
+    irq::IRQ_VECTORS,
+    stats::{StatsCounter, StatsRegs},
+    switch::SWITCH_HOST_PORT,
+    TopRegs,
+};
 use super::rx_datapath::EthaRxDataPath;
+use super::switch::Fdb;
 use super::tx_datapath::EthaTxDataPath;
 use super::tx_sequencer::TxLoadInfo;
 use super::*;
 use crate::aborter::*;
-use crate::arbiter::*;
+use crate::clock::ModelClock;
 use crate::irq::*;
 use smoltcp::phy::{Device, RxToken, TxToken};
 use smoltcp::time::Instant;
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
-pub struct EthaCore<A: Arbiter, M: for<'a> Device<'a>> {
-    tx: EthaTxDataPath<A>,
-    tx_frame: Option<Vec<u8>>,
+pub struct EthaCore<M: for<'a> Device<'a>> {
+    tx: EthaTxDataPath,
+    tx_buffer: Vec<u8>,
+    //frames waiting for each mac port
+    tx_frames: Vec<VecDeque<Vec<u8>>>,
     rx: EthaRxDataPath,
     rx_frame: Option<Vec<u8>>,
-    mac: M,
+    rx_port: usize,
+    macs: Vec<M>,
     regs: Arc<LockedEthaGlobalRegs>,
+    stats: Arc<StatsRegs<CHS>>,
+    fdb: Arc<Fdb>,
+    clock: Arc<ModelClock>,
     abort: Arc<Aborter>,
     irqs: Arc<Mutex<IrqVec>>,
 }
 
-impl<A: Arbiter, M: for<'a> Device<'a>> EthaCore<A, M> {
-    pub fn new(
-        arbiter: A,
-        mac: M,
-        regs: &Arc<TopRegs<CHS, RX_ET_FILTERS, RX_TP5_FILTERS>>,
-    ) -> Self {
-        let irqs = Arc::new(Mutex::new(IrqVec::new("EthaIrqs")));
+impl<M: for<'a> Device<'a>> EthaCore<M> {
+    pub fn new(macs: Vec<M>, regs: &Arc<TopRegs<CHS, RX_ET_FILTERS, RX_TP5_FILTERS>>) -> Self {
+        regs.fdb.set_ports(macs.len());
+        let irqs = Arc::new(Mutex::new(IrqVec::with_vectors("EthaIrqs", IRQ_VECTORS)));
         let rx = EthaRxDataPath::new(regs, &irqs);
-        let tx = EthaTxDataPath::new(arbiter, regs, &irqs);
+        let tx = EthaTxDataPath::new(regs, &irqs);
+        //cause ids are the indexes of cause maps
+        {
+            let mut irqs = irqs.lock().unwrap();
+            assert_eq!(irqs.alloc("EthaErrIrq"), IrqTable::<CHS>::ERR_CAUSE);
+            assert_eq!(irqs.alloc("EthaLinkIrq"), IrqTable::<CHS>::LINK_CAUSE);
+        }
+        //all ports are up after reset
+        for port in 0..macs.len() {
+            regs.irq.set_link(port, true).unwrap();
+        }
         EthaCore {
             tx,
-            tx_frame: None,
+            tx_buffer: vec![0u8; TX_BUFFER_LEN],
+            tx_frames: macs.iter().map(|_| VecDeque::new()).collect(),
             rx,
             rx_frame: None,
-            mac,
+            rx_port: 0,
+            macs,
             regs: regs.global.clone(),
+            stats: regs.stats.clone(),
+            fdb: regs.fdb.clone(),
+            clock: regs.clock.clone(),
             abort: Arc::new(Aborter::new()),
             irqs,
         }
@@ -54,26 +81,47 @@ impl<A: Arbiter, M: for<'a> Device<'a>> EthaCore<A, M> {
         })
     }
 
+    fn switch(fdb: &Fdb, frames: &mut [VecDeque<Vec<u8>>], ingress: usize, frame: &[u8]) -> bool {
+        let mut to_host = false;
+        for port in fdb.forward(ingress, frame) {
+            if port == SWITCH_HOST_PORT {
+                to_host = true;
+            } else {
+                frames[port].push_back(frame.to_vec());
+            }
+        }
+        to_host
+    }
+
+    //new frames are only loaded once all ports are drained
     fn tx<P: Pipeline<Input = (), Output = TxLoadInfo>>(
         pipe: &mut P,
-        mac: &mut M,
-        frame: Option<Vec<u8>>,
-    ) -> Option<Vec<u8>> {
-        frame
-            .or_else(|| {
-                let mut f = vec![0u8; crate::mac::MAC_MAX_LEN];
-                let r = pipe.execute(&mut f, &());
-                match r {
-                    Ok(info) => {
-                        f.truncate(info.len);
-                        Some(f)
+        fdb: &Fdb,
+        buffer: &mut [u8],
+        frames: &mut [VecDeque<Vec<u8>>],
+    ) {
+        if frames.iter().all(|f| f.is_empty()) {
+            match pipe.execute(buffer, &()) {
+                Ok(info) => {
+                    for f in buffer[..info.len].chunks(info.seg_len) {
+                        Self::switch(fdb, frames, SWITCH_HOST_PORT, f);
                     }
-                    Err(Error::ParseError(e)) => panic!("{:?}", e),
-                    _ => None,
                 }
-            })
-            .map(|f| Self::tx_update_frame(mac, &f).map(|_| f))
-            .flatten()
+                Err(Error::ParseError(e)) => panic!("{:?}", e),
+                _ => {}
+            }
+        }
+    }
+
+    fn tx_ports(macs: &mut [M], frames: &mut [VecDeque<Vec<u8>>]) {
+        for (mac, frames) in macs.iter_mut().zip(frames.iter_mut()) {
+            if frames
+                .front()
+                .is_some_and(|f| Self::tx_update_frame(mac, f).is_none())
+            {
+                frames.pop_front();
+            }
+        }
     }
 
     fn rx_update_frame(mac: &mut M) -> Option<Vec<u8>> {
@@ -91,38 +139,79 @@ impl<A: Arbiter, M: for<'a> Device<'a>> EthaCore<A, M> {
         })
     }
 
+    //ports are polled in round robin, frames to other ports are queued for tx,
+    //virtual time moves by the wire time of each received frame
+    fn rx_switch(
+        macs: &mut [M],
+        fdb: &Fdb,
+        clock: &ModelClock,
+        frames: &mut [VecDeque<Vec<u8>>],
+        port: &mut usize,
+    ) -> Option<Vec<u8>> {
+        for _ in 0..macs.len() {
+            let ingress = *port;
+            *port = (*port + 1) % macs.len();
+            if let Some(f) = Self::rx_update_frame(&mut macs[ingress]) {
+                tracing::debug!(target : "core", "rx received from port {}!", ingress);
+                clock.advance_to(clock.now() + crate::mac::wire_time(f.len()));
+                if Self::switch(fdb, frames, ingress, &f) {
+                    return Some(f);
+                }
+            }
+        }
+        None
+    }
+
     fn rx<P: Pipeline<Input = (), Output = ()>>(
         pipe: &mut P,
-        mac: &mut M,
-        frame: Option<Vec<u8>>,
+        stats: &StatsRegs<CHS>,
+        mut f: Vec<u8>,
     ) -> Option<Vec<u8>> {
-        frame
-            .or_else(|| Self::rx_update_frame(mac))
-            .map(|mut f| {
-                tracing::debug!(target : "core", "rx received!");
-                let r = pipe.execute(&mut f, &());
-                tracing::debug!(target : "core", "rx pipe {} bytes, result {:?}", f.len(), r);
-                match r {
-                    Ok(_) | Err(Error::Dropped) => Self::rx_update_frame(mac),
-                    Err(Error::Blocking) => Some(f),
-                    Err(e) => panic!("{:?}", e),
-                }
-            })
-            .flatten()
+        let r = pipe.execute(&mut f, &());
+        tracing::debug!(target : "core", "rx pipe {} bytes, result {:?}", f.len(), r);
+        match r {
+            Ok(_) | Err(Error::Dropped) => {}
+            Err(Error::Blocking) => return Some(f),
+            //malformed frames are dropped
+            Err(Error::ParseError(_)) => stats.inc(StatsCounter::RxParseErr),
+        }
+        stats.rx_hist(f.len());
+        None
     }
 
     pub fn run(&mut self) {
         let mut tx_pipe = self.tx.pipeline();
         let mut rx_pipe = self.rx.pipeline();
+        let mut rx_irqs = self.rx.irqs();
         loop {
             if self.abort.aborted() {
                 break;
             }
             if self.regs.tx_en().en() == 1 {
-                self.tx_frame = Self::tx(&mut tx_pipe, &mut self.mac, self.tx_frame.take());
+                Self::tx(
+                    &mut tx_pipe,
+                    &self.fdb,
+                    &mut self.tx_buffer,
+                    &mut self.tx_frames,
+                );
             }
+            Self::tx_ports(&mut self.macs, &mut self.tx_frames);
+            //a frame blocked by the host holds all ports
             if self.regs.rx_en().en() == 1 {
-                self.rx_frame = Self::rx(&mut rx_pipe, &mut self.mac, self.rx_frame.take());
+                self.rx_frame = self
+                    .rx_frame
+                    .take()
+                    .or_else(|| {
+                        Self::rx_switch(
+                            &mut self.macs,
+                            &self.fdb,
+                            &self.clock,
+                            &mut self.tx_frames,
+                            &mut self.rx_port,
+                        )
+                    })
+                    .and_then(|f| Self::rx(&mut rx_pipe, &self.stats, f));
+                rx_irqs.execute(&mut [], &()).unwrap();
             }
         }
     }
@@ -1,7 +1,7 @@
 use super::STATICS_TAR;
-use super::{CHS, Etha, RX_ET_FILTERS, RX_TP5_FILTERS, reg_if::TopRegs};
+use super::reg_if::{GLOBAL_REGS_RANGE, TopRegs, global::GLOBAL_CLOCK_CTRL_OFFSET};
+use super::{CHS, Etha, RX_ET_FILTERS, RX_TP5_FILTERS};
 use crate::aborter::*;
-use crate::arbiter::*;
 use crate::irq::*;
 use crate::logger;
 use crate::mac::{PcapMac, pcap_cmp};
@@ -18,7 +18,7 @@ struct CHandle {
     model_thread: thread::JoinHandle<()>,
 }
 impl CHandle {
-    fn new<M: for<'a> Device<'a> + Send + 'static>(etha: Etha<MyArbiter, M>, core_id: i32) -> Self {
+    fn new<M: for<'a> Device<'a> + Send + 'static>(etha: Etha<M>, core_id: i32) -> Self {
         let core_id = if core_id < 0 {
             None
         } else {
@@ -46,8 +46,6 @@ impl CHandle {
 
 static C_HANDLE: RwLock<Option<CHandle>> = RwLock::new(None);
 
-type MyArbiter = RRArbiter<CHS>;
-
 #[unsafe(no_mangle)]
 unsafe extern "C" fn etha_simulate_pcap(
     rx_file: *const std::os::raw::c_char,
@@ -57,14 +55,18 @@ unsafe extern "C" fn etha_simulate_pcap(
     unsafe {
         let rx_file = std::ffi::CStr::from_ptr(rx_file).to_str().unwrap();
         let tx_file = std::ffi::CStr::from_ptr(tx_file).to_str().unwrap();
-        let etha = Etha::new(MyArbiter::new(), PcapMac::new(rx_file, tx_file));
+        let etha = Etha::new(PcapMac::new(rx_file, tx_file));
+        //pcap runs are reproducible in virtual time
+        etha.regs()
+            .write((GLOBAL_REGS_RANGE.start + GLOBAL_CLOCK_CTRL_OFFSET) as u64, 1)
+            .unwrap();
         *C_HANDLE.write().unwrap() = Some(CHandle::new(etha, core_id));
     }
 }
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn etha_simulate_loopback(core_id: i32) {
-    let etha = Etha::new(MyArbiter::new(), Loopback::new(Medium::Ethernet));
+    let etha = Etha::new(Loopback::new(Medium::Ethernet));
     *C_HANDLE.write().unwrap() = Some(CHandle::new(etha, core_id));
 }
 
@@ -95,26 +97,20 @@ unsafe extern "C" fn etha_simulate_raw_socket(
     core_id: i32,
 ) {
     let socket_file = unsafe { std::ffi::CStr::from_ptr(socket_file).to_str().unwrap() };
-    let etha = Etha::new(
-        MyArbiter::new(),
-        DeviceWrapper(
-            RawSocket::new(socket_file, Medium::Ethernet)
-                .expect(&format!("socket file {} open failed!", socket_file)),
-        ),
-    );
+    let etha = Etha::new(DeviceWrapper(
+        RawSocket::new(socket_file, Medium::Ethernet)
+            .expect(&format!("socket file {} open failed!", socket_file)),
+    ));
     *C_HANDLE.write().unwrap() = Some(CHandle::new(etha, core_id));
 }
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn etha_simulate_tap(tap_file: *const std::os::raw::c_char, core_id: i32) {
     let tap_file = unsafe { std::ffi::CStr::from_ptr(tap_file).to_str().unwrap() };
-    let etha = Etha::new(
-        MyArbiter::new(),
-        DeviceWrapper(
-            TunTapInterface::new(tap_file, Medium::Ethernet)
-                .expect(&format!("tap file {} open failed!", tap_file)),
-        ),
-    );
+    let etha = Etha::new(DeviceWrapper(
+        TunTapInterface::new(tap_file, Medium::Ethernet)
+            .expect(&format!("tap file {} open failed!", tap_file)),
+    ));
     *C_HANDLE.write().unwrap() = Some(CHandle::new(etha, core_id));
 }
 
@@ -144,6 +140,21 @@ unsafe extern "C" fn etha_register_irq_handler(id: u32, f: extern "C" fn(u32)) {
         ));
 }
 
+//link changes of mac ports are driven by the simulation, invalid ports are ignored
+#[unsafe(no_mangle)]
+unsafe extern "C" fn etha_set_link(port: u32, up: bool) {
+    let h = C_HANDLE.read().unwrap();
+    if h.as_ref()
+        .expect("etha model does not exist!")
+        .regs
+        .irq
+        .set_link(port as usize, up)
+        .is_none()
+    {
+        tracing::warn!("etha_set_link: invalid port {}!", port);
+    }
+}
+
 #[unsafe(no_mangle)]
 unsafe extern "C" fn etha_reg_write(addr: u32, value: u32) {
     let h = C_HANDLE.read().unwrap();
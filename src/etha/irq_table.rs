@@ -0,0 +1,233 @@
+use super::reg_if::irq::*;
+use super::reg_if::stats::{StatsCounter, StatsRegs};
+use super::reg_if::switch::SWITCH_MAX_PORTS;
+use crate::irq::IrqVec;
+use crate::reg_if::RegBus;
+use etha_model_generator::GenHeader;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const IRQ_MAP_VEC_MASK: u64 = (1 << IRQ_MAP_VEC_BITS) - 1;
+
+//the error counter latched by each field of IrqErrCause, in field order
+const ERR_CAUSES: [StatsCounter; 5] = [
+    StatsCounter::RxParseErr,
+    StatsCounter::RxNoQueue,
+    StatsCounter::TxTooLarge,
+    StatsCounter::TxTooSmall,
+    StatsCounter::RxAddrFilter,
+];
+
+//causes are the rx rings, the tx rings, errors and link changes,
+//each sub cause is routed to a vector by its cause map
+pub struct IrqTable<const CHS: usize> {
+    pub regs: LockedIrqRegs,
+    maps: Vec<AtomicU64>,
+    pending: AtomicU64,
+    stats: Arc<StatsRegs<CHS>>,
+}
+
+impl<const CHS: usize> IrqTable<CHS> {
+    pub const ERR_CAUSE: usize = 2 * CHS;
+    pub const LINK_CAUSE: usize = 2 * CHS + 1;
+    pub const CAUSES: usize = 2 * CHS + 2;
+
+    pub fn new(stats: &Arc<StatsRegs<CHS>>) -> Self {
+        IrqTable {
+            regs: LockedIrqRegs::new(32),
+            //causes are routed to the vector of the same id after reset
+            maps: (0..Self::CAUSES)
+                .map(|c| AtomicU64::new(Self::map_all(c)))
+                .collect(),
+            pending: AtomicU64::new(0),
+            stats: stats.clone(),
+        }
+    }
+
+    fn map_all(vector: usize) -> u64 {
+        (0..IRQ_SUB_CAUSES).fold(0, |m, i| m | (vector as u64) << (i * IRQ_MAP_VEC_BITS))
+    }
+
+    pub fn vector(&self, cause: usize, sub: usize) -> usize {
+        let map = self.maps[cause].load(Ordering::Relaxed);
+        ((map >> (sub * IRQ_MAP_VEC_BITS)) & IRQ_MAP_VEC_MASK) as usize
+    }
+
+    pub fn raise(&self, cause: usize, subs: u64) {
+        for sub in (0..IRQ_SUB_CAUSES).filter(|i| subs & (1 << i) != 0) {
+            self.pending
+                .fetch_or(1 << self.vector(cause, sub), Ordering::Relaxed);
+        }
+    }
+
+    //err cause bits of the counters raised since the last poll
+    fn err_causes(&self) -> u64 {
+        let raised = self.stats.take_raised();
+        IrqErrCause::fields()
+            .zip(ERR_CAUSES)
+            .filter(|(_, c)| raised & (1 << *c as usize) != 0)
+            .fold(0, |m, (f, _)| m | 1 << f.lsb)
+    }
+
+    fn mask(&self) -> u64 {
+        self.regs.vec_mask_hi().bits() << 32 | self.regs.vec_mask_lo().bits()
+    }
+
+    //latches new errors then sends pending vectors which are not masked
+    pub fn poll(&self, irqs: &IrqVec) {
+        let errs = self.err_causes() & self.regs.err_mask().get();
+        let latched = self.regs.err_status().get();
+        self.regs.err_status_mut().set(latched | errs);
+        self.raise(Self::ERR_CAUSE, errs & !latched);
+
+        let ready = self.pending.load(Ordering::Relaxed) & !self.mask();
+        for v in (0..irqs.vectors()).filter(|v| ready & (1 << v) != 0) {
+            self.pending.fetch_and(!(1 << v), Ordering::Relaxed);
+            irqs.send(v);
+        }
+    }
+
+    //ports out of range are rejected
+    pub fn set_link(&self, port: usize, up: bool) -> Option<()> {
+        if port >= SWITCH_MAX_PORTS {
+            return None;
+        }
+        let old = self.regs.link_up().ports();
+        let new = if up {
+            old | 1 << port
+        } else {
+            old & !(1 << port)
+        };
+        self.regs.link_up_mut().set_ports(new);
+        let changed = (old ^ new) & self.regs.link_mask().ports();
+        let latched = self.regs.link_status().ports();
+        self.regs.link_status_mut().set_ports(latched | changed);
+        self.raise(Self::LINK_CAUSE, changed & !latched);
+        Some(())
+    }
+}
+
+impl<const CHS: usize> RegBus for IrqTable<CHS> {
+    fn write(&self, addr: u64, data: u64) -> Option<()> {
+        let addr = addr as usize;
+        match addr {
+            IRQ_MAP_OFFSET.. => {
+                let map = self.maps.get(addr - IRQ_MAP_OFFSET)?;
+                map.store(data, Ordering::Relaxed);
+                Some(())
+            }
+            _ => self.regs.bus_write(addr as u64, data),
+        }
+    }
+
+    fn read(&self, addr: u64) -> Option<u64> {
+        let addr = addr as usize;
+        let pending = self.pending.load(Ordering::Relaxed);
+        match addr {
+            IRQ_VEC_PENDING_OFFSET => Some(pending & 0xffff_ffff),
+            IRQ_VEC_PENDING_HI_OFFSET => Some(pending >> 32),
+            IRQ_MAP_OFFSET.. => Some(
+                self.maps
+                    .get(addr - IRQ_MAP_OFFSET)?
+                    .load(Ordering::Relaxed),
+            ),
+            _ => self.regs.read(addr as u64),
+        }
+    }
+}
+
+impl<const CHS: usize> GenHeader for IrqTable<CHS> {
+    fn render_name() -> &'static str {
+        "IrqTable"
+    }
+    fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
+        IrqRegs::gen_c_header(header)?;
+        IrqVecBits::gen_c_header(header)?;
+        IrqErrCause::gen_c_header(header)?;
+        IrqLinkCause::gen_c_header(header)?;
+        IrqCauseMap::gen_c_header(header)?;
+
+        writeln!(header, "#define IRQ_VECTORS {}", IRQ_VECTORS)?;
+        writeln!(header, "#define IRQ_MAP_OFFSET {:#x}", IRQ_MAP_OFFSET)?;
+        writeln!(header, "#define IRQ_MAP_VEC_BITS {}", IRQ_MAP_VEC_BITS)?;
+        writeln!(header, "#define IRQ_CAUSE_ERR {}", Self::ERR_CAUSE)?;
+        writeln!(header, "#define IRQ_CAUSE_LINK {}", Self::LINK_CAUSE)?;
+        writeln!(header, "#define IRQ_CAUSES {}", Self::CAUSES)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn err_causes_test() {
+        let fields = IrqErrCause::fields().collect::<Vec<_>>();
+        assert_eq!(fields.len(), ERR_CAUSES.len());
+        for (f, c) in fields.iter().zip(ERR_CAUSES) {
+            assert_eq!(f.name.replace('_', ""), format!("{:?}", c).to_lowercase());
+        }
+    }
+
+    #[test]
+    fn irq_table_test() {
+        let stats = Arc::new(StatsRegs::<4>::new());
+        let table = IrqTable::new(&stats);
+        let mut irqs = IrqVec::with_vectors("TestIrqs", IRQ_VECTORS);
+        let sent = Arc::new(Mutex::new(vec![]));
+        for v in [1, 5, 8, 40] {
+            let sent = sent.clone();
+            irqs.bind(v, move |id| sent.lock().unwrap().push(id))
+                .unwrap();
+        }
+        let take = || std::mem::take(&mut *sent.lock().unwrap());
+
+        //ring 1 full and done
+        table.raise(1, 0x11);
+        table.poll(&irqs);
+        assert_eq!(take(), [1]);
+        //done of ring 1 to vector 40, full to vector 5
+        table
+            .write((IRQ_MAP_OFFSET + 1) as u64, 40 << 24 | 5)
+            .unwrap();
+        table.raise(1, 0x11);
+        table.regs.vec_mask_hi_mut().set_bits(1 << 8);
+        table.poll(&irqs);
+        assert_eq!(take(), [5]);
+        assert_eq!(table.read(IRQ_VEC_PENDING_HI_OFFSET as u64), Some(1 << 8));
+        table.regs.vec_mask_hi_mut().set_bits(0);
+        table.poll(&irqs);
+        assert_eq!(take(), [40]);
+        assert_eq!(table.read(IRQ_VEC_PENDING_HI_OFFSET as u64), Some(0));
+
+        //errors are latched until cleared
+        stats.inc(StatsCounter::RxNoQueue);
+        table.poll(&irqs);
+        assert!(take().is_empty());
+        table.regs.err_mask_mut().set_rx_no_queue(1);
+        stats.inc(StatsCounter::RxNoQueue);
+        table.poll(&irqs);
+        assert_eq!(take(), [IrqTable::<4>::ERR_CAUSE]);
+        stats.inc(StatsCounter::RxNoQueue);
+        table.poll(&irqs);
+        assert!(take().is_empty());
+        assert_eq!(table.read(IRQ_ERR_STATUS_OFFSET as u64), Some(0x2));
+        table.write(IRQ_ERR_STATUS_OFFSET as u64, 0x2).unwrap();
+        assert_eq!(table.read(IRQ_ERR_STATUS_OFFSET as u64), Some(0));
+
+        //link changes
+        table
+            .write((IRQ_MAP_OFFSET + IrqTable::<4>::LINK_CAUSE) as u64, 5 << 6)
+            .unwrap();
+        table.regs.link_mask_mut().set_ports(0x2);
+        table.set_link(0, true).unwrap();
+        table.set_link(1, true).unwrap();
+        assert_eq!(table.set_link(SWITCH_MAX_PORTS, true), None);
+        table.poll(&irqs);
+        assert_eq!(take(), [5]);
+        assert_eq!(table.regs.link_up().ports(), 0x3);
+        assert_eq!(table.read(IRQ_LINK_STATUS_OFFSET as u64), Some(0x2));
+    }
+}
@@ -3,15 +3,28 @@ use super::*;
 use smoltcp::wire::EthernetAddress;
 use smoltcp::wire::EthernetFrame;
 use smoltcp::wire::EthernetProtocol;
-use smoltcp::wire::ETHERNET_HEADER_LEN;
 use std::convert::Into;
-const VLAN_SIZE: usize = 4;
-const VLAN_TYPE: u16 = 0x8100;
+pub const VLAN_SIZE: usize = 4;
+pub const VLAN_TYPE: u16 = 0x8100;
+const SVLAN_TYPE: u16 = 0x88a8;
+const MAX_VLAN_TAGS: usize = 2;
 #[derive(Debug, Copy, Clone)]
 pub struct VlanInfo {
     pub flags: u8,
     pub vid: u16,
 }
+
+impl VlanInfo {
+    fn from_tci(tci: u16) -> Self {
+        VlanInfo {
+            flags: (tci >> 12) as u8,
+            vid: tci & 0xfff,
+        }
+    }
+    pub fn pcp(&self) -> u8 {
+        self.flags >> 1
+    }
+}
 #[derive(Debug, Copy, Clone)]
 pub struct L2Info {
     pub src: EthernetAddress,
@@ -19,6 +32,8 @@ pub struct L2Info {
     pub etype: EthernetProtocol,
     pub header_len: usize,
     pub vlan: Option<VlanInfo>,
+    pub svlan: Option<VlanInfo>,
+    pub vlan_stripped: bool,
 }
 
 impl Into<RxResultL2Desc> for L2Info {
@@ -43,6 +58,12 @@ impl Into<RxResultL2Desc> for L2Info {
             desc.set_l2_vlan_flags(info.flags as u32);
             desc.set_l2_vlan_vid(info.vid as u32);
         }
+        if let Some(info) = self.svlan {
+            desc.set_l2_is_svlan(1);
+            desc.set_l2_svlan_flags(info.flags as u32);
+            desc.set_l2_svlan_vid(info.vid as u32);
+        }
+        desc.set_l2_vlan_stripped(self.vlan_stripped as u32);
         desc
     }
 }
@@ -52,35 +73,37 @@ pub struct L2Parser;
 impl L2Parser {
     fn parse(&self, buffer: &[u8]) -> Result<L2Info> {
         let frame = EthernetFrame::new_checked(buffer)?;
-        match frame.ethertype() {
-            EthernetProtocol::Unknown(p) if p == VLAN_TYPE => self.parse_vlan(buffer),
-            _ => Ok(L2Info {
-                src: frame.src_addr(),
-                dst: frame.dst_addr(),
-                etype: frame.ethertype(),
-                vlan: None,
-                header_len: EthernetFrame::<&[u8]>::header_len(),
-            }),
+        let mut tags = Vec::with_capacity(MAX_VLAN_TAGS);
+        let mut header_len = EthernetFrame::<&[u8]>::header_len();
+        let mut etype = u16::from(frame.ethertype());
+        while tags.len() < MAX_VLAN_TAGS && (etype == VLAN_TYPE || etype == SVLAN_TYPE) {
+            if buffer.len() < header_len + VLAN_SIZE {
+                return Err(smoltcp::Error::Truncated.into());
+            }
+            let tci = u16::from_be_bytes(buffer[header_len..header_len + 2].try_into().unwrap());
+            tags.push((etype, VlanInfo::from_tci(tci)));
+            etype = u16::from_be_bytes(
+                buffer[header_len + 2..header_len + VLAN_SIZE]
+                    .try_into()
+                    .unwrap(),
+            );
+            header_len += VLAN_SIZE;
         }
-    }
-
-    fn parse_vlan(&self, buffer: &[u8]) -> Result<L2Info> {
-        let buffer_novlan = [
-            &buffer[..ETHERNET_HEADER_LEN - 2],
-            &buffer[ETHERNET_HEADER_LEN + 2..],
-        ]
-        .concat();
-        let frame = EthernetFrame::new_checked(&buffer_novlan)?;
+        //with two tags the outer one is always the S-tag
+        let (svlan, vlan) = match tags[..] {
+            [(_, outer), (_, inner)] => (Some(outer), Some(inner)),
+            [(SVLAN_TYPE, tag)] => (Some(tag), None),
+            [(_, tag)] => (None, Some(tag)),
+            _ => (None, None),
+        };
         Ok(L2Info {
             src: frame.src_addr(),
             dst: frame.dst_addr(),
-            etype: frame.ethertype(),
-            vlan: Some(VlanInfo {
-                flags: buffer[ETHERNET_HEADER_LEN] >> 4,
-                vid: (buffer[ETHERNET_HEADER_LEN] as u16 & 0xf) << 8
-                    | buffer[ETHERNET_HEADER_LEN + 1] as u16,
-            }),
-            header_len: EthernetFrame::<&[u8]>::header_len() + VLAN_SIZE,
+            etype: EthernetProtocol::from(etype),
+            vlan,
+            svlan,
+            vlan_stripped: false,
+            header_len,
         })
     }
 }
@@ -99,6 +122,7 @@ mod tests {
     use crate::mac::*;
     use smoltcp::phy::RxToken;
     use smoltcp::time::Instant;
+    use smoltcp::wire::ETHERNET_HEADER_LEN;
 
     #[test]
     fn l2_parser_vlan_test() {
@@ -111,7 +135,8 @@ mod tests {
                         let check_frame = EthernetFrame::new_checked(buffer)?;
                         match check_frame.ethertype() {
                             EthernetProtocol::Unknown(p) if p == VLAN_TYPE => {
-                                assert!(info.vlan.is_some())
+                                assert!(info.vlan.is_some());
+                                assert_eq!(info.header_len, ETHERNET_HEADER_LEN + VLAN_SIZE);
                             }
                             _ => assert!(info.vlan.is_none()),
                         }
@@ -124,4 +149,28 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn l2_parser_qinq_test() {
+        let mut frame = vec![0u8; ETHERNET_HEADER_LEN + 2 * VLAN_SIZE + 20];
+        frame[12..14].copy_from_slice(&SVLAN_TYPE.to_be_bytes());
+        frame[14..16].copy_from_slice(&0xa064u16.to_be_bytes());
+        frame[16..18].copy_from_slice(&VLAN_TYPE.to_be_bytes());
+        frame[18..20].copy_from_slice(&0x3123u16.to_be_bytes());
+        frame[20..22].copy_from_slice(&0x0800u16.to_be_bytes());
+        let info = L2Parser.execute(&mut frame, &()).unwrap();
+        assert_eq!(info.etype, EthernetProtocol::Ipv4);
+        assert_eq!(info.header_len, ETHERNET_HEADER_LEN + 2 * VLAN_SIZE);
+        let svlan = info.svlan.unwrap();
+        assert_eq!((svlan.pcp(), svlan.vid), (5, 0x64));
+        let vlan = info.vlan.unwrap();
+        assert_eq!((vlan.pcp(), vlan.vid), (1, 0x123));
+
+        frame[16..18].copy_from_slice(&0x0800u16.to_be_bytes());
+        let info = L2Parser.execute(&mut frame, &()).unwrap();
+        assert_eq!(info.etype, EthernetProtocol::Ipv4);
+        assert_eq!(info.header_len, ETHERNET_HEADER_LEN + VLAN_SIZE);
+        assert!(info.vlan.is_none());
+        assert_eq!(info.svlan.unwrap().vid, 0x64);
+    }
 }
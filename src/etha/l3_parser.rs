@@ -8,12 +8,17 @@ use smoltcp::wire::Ipv4Address;
 use smoltcp::wire::Ipv4Packet;
 use smoltcp::wire::Ipv6Packet;
 use std::convert::Into;
+const IPV6_FRAG_HEADER_LEN: usize = 8;
 #[derive(Debug, Copy, Clone)]
 pub struct L3Info {
     pub src: IpAddress,
     pub dst: IpAddress,
     pub protocol: IpProtocol,
     pub header_len: usize,
+    pub payload_len: usize,
+    pub csum: Option<bool>,
+    //fragment offset in bytes, for fragmented packets only
+    pub frag: Option<usize>,
 }
 
 impl Default for L3Info {
@@ -23,6 +28,9 @@ impl Default for L3Info {
             dst: IpAddress::Ipv4(Ipv4Address::from_bytes(&[0; 4])),
             protocol: IpProtocol::Unknown(0xff),
             header_len: 0,
+            payload_len: 0,
+            csum: None,
+            frag: None,
         }
     }
 }
@@ -74,6 +82,7 @@ impl Into<RxResultL3Desc> for L3Info {
         }
         desc.set_l3_protocol(u8::from(self.protocol) as u32);
         desc.set_l3_header_len(self.header_len as u32);
+        desc.set_l3_is_frag(self.frag.is_some() as u32);
         desc
     }
 }
@@ -88,6 +97,13 @@ impl Ipv4Parser {
             dst: IpAddress::Ipv4(packet.dst_addr()),
             protocol: packet.protocol(),
             header_len: packet.header_len() as usize,
+            payload_len: packet.total_len() as usize - packet.header_len() as usize,
+            csum: Some(packet.verify_checksum()),
+            frag: if packet.more_frags() || packet.frag_offset() != 0 {
+                Some(packet.frag_offset() as usize)
+            } else {
+                None
+            },
         })
     }
 }
@@ -97,11 +113,44 @@ struct Ipv6Parser;
 impl Ipv6Parser {
     fn parse(&self, buffer: &[u8]) -> Result<L3Info> {
         let packet = Ipv6Packet::new_checked(buffer)?;
+        let mut protocol = packet.next_header();
+        let mut header_len = packet.header_len();
+        let mut frag = None;
+        //walk the extension header chain up to the upper-layer protocol
+        loop {
+            let ext = &buffer[header_len..];
+            let ext_len = match protocol {
+                IpProtocol::HopByHop | IpProtocol::Ipv6Route | IpProtocol::Ipv6Opts => {
+                    if ext.len() < 2 {
+                        return Err(smoltcp::Error::Truncated.into());
+                    }
+                    (ext[1] as usize + 1) * 8
+                }
+                IpProtocol::Ipv6Frag => {
+                    if ext.len() < IPV6_FRAG_HEADER_LEN {
+                        return Err(smoltcp::Error::Truncated.into());
+                    }
+                    frag = Some((u16::from_be_bytes([ext[2], ext[3]]) & 0xfff8) as usize);
+                    IPV6_FRAG_HEADER_LEN
+                }
+                _ => break,
+            };
+            if ext.len() < ext_len
+                || header_len + ext_len > packet.header_len() + packet.payload_len() as usize
+            {
+                return Err(smoltcp::Error::Truncated.into());
+            }
+            protocol = IpProtocol::from(ext[0]);
+            header_len += ext_len;
+        }
         Ok(L3Info {
             src: IpAddress::Ipv6(packet.src_addr()),
             dst: IpAddress::Ipv6(packet.dst_addr()),
-            protocol: packet.next_header(),
-            header_len: packet.header_len(),
+            protocol,
+            header_len,
+            payload_len: packet.header_len() + packet.payload_len() as usize - header_len,
+            csum: None,
+            frag,
         })
     }
 }
@@ -185,4 +234,33 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn l3_parser_ipv6_ext_test() {
+        //hbh+tcp, hbh+routing+dst opts+udp, 2 tcp fragments, plain udp, ipv4 fragment
+        let expect = [
+            (IpProtocol::Tcp, 48, 52, None),
+            (IpProtocol::Udp, 80, 40, None),
+            (IpProtocol::Tcp, 48, 24, Some(0)),
+            (IpProtocol::Tcp, 48, 28, Some(24)),
+            (IpProtocol::Udp, 40, 40, None),
+            (IpProtocol::Udp, 20, 16, Some(0)),
+        ];
+        let mut mac = PcapMacRx::new("pcaps/ipv6_ext_hdrs.pcap");
+        for (protocol, header_len, payload_len, frag) in expect {
+            let (_, l3_info) = mac
+                .receive()
+                .unwrap()
+                .consume(Instant::now(), |buffer| {
+                    Ok(L2Parser.comb(L3Parser).execute(buffer, &())?)
+                })
+                .unwrap();
+            println!("{:#x?}", l3_info);
+            assert_eq!(l3_info.protocol, protocol);
+            assert_eq!(l3_info.header_len, header_len);
+            assert_eq!(l3_info.payload_len, payload_len);
+            assert_eq!(l3_info.frag, frag);
+        }
+        assert!(mac.receive().is_none());
+    }
 }
@@ -1,17 +1,24 @@
 use super::desc::rx::RxResultL4Desc;
-use super::l3_parser::L3Parser;
+use super::l3_parser::{L3Info, L3Parser};
 use super::*;
+use smoltcp::wire::IpAddress;
 use smoltcp::wire::IpProtocol;
 use smoltcp::wire::TcpPacket;
 use smoltcp::wire::UdpPacket;
-use smoltcp::wire::TCP_HEADER_LEN;
 use smoltcp::wire::UDP_HEADER_LEN;
 use std::convert::Into;
+//ns, cwr, ece, urg, ack, psh, rst, syn, fin
+const TCP_FLAGS_MASK: u16 = 0x1ff;
+pub const TCP_FLAG_PSH: u16 = 0x8;
+pub const TCP_FLAG_ACK: u16 = 0x10;
 #[derive(Debug, Copy, Clone, Default)]
 pub struct L4Info {
     pub src: u16,
     pub dst: u16,
     pub header_len: usize,
+    pub csum: Option<bool>,
+    pub tcp_flags: u16,
+    pub tcp_seq: u32,
 }
 
 impl Into<RxResultL4Desc> for L4Info {
@@ -20,6 +27,7 @@ impl Into<RxResultL4Desc> for L4Info {
         desc.set_l4_src_port(self.src as u32);
         desc.set_l4_dst_port(self.dst as u32);
         desc.set_l4_header_len(self.header_len as u32);
+        desc.set_l4_tcp_flags(self.tcp_flags as u32);
         desc
     }
 }
@@ -27,12 +35,19 @@ impl Into<RxResultL4Desc> for L4Info {
 struct TcpParser;
 
 impl TcpParser {
-    fn parse(&self, buffer: &[u8]) -> Result<L4Info> {
+    fn parse(&self, buffer: &[u8], l3: &L3Info) -> Result<L4Info> {
         let packet = TcpPacket::new_checked(buffer)?;
         Ok(L4Info {
             src: packet.src_port(),
             dst: packet.dst_port(),
-            header_len: TCP_HEADER_LEN,
+            header_len: packet.header_len() as usize,
+            //checksum covers the whole reassembled payload
+            csum: match l3.frag {
+                Some(_) => None,
+                None => Some(packet.verify_checksum(&l3.src, &l3.dst)),
+            },
+            tcp_flags: u16::from_be_bytes([buffer[12], buffer[13]]) & TCP_FLAGS_MASK,
+            tcp_seq: packet.seq_number().0 as u32,
         })
     }
 }
@@ -40,12 +55,26 @@ impl TcpParser {
 struct UdpParser;
 
 impl UdpParser {
-    fn parse(&self, buffer: &[u8]) -> Result<L4Info> {
-        let packet = UdpPacket::new_checked(buffer)?;
+    fn parse(&self, buffer: &[u8], l3: &L3Info) -> Result<L4Info> {
+        //length field of a fragment covers the whole reassembled datagram
+        let packet = if l3.frag.is_some() && buffer.len() >= UDP_HEADER_LEN {
+            UdpPacket::new_unchecked(buffer)
+        } else {
+            UdpPacket::new_checked(buffer)?
+        };
+        //zero checksum means no checksum over ipv4
+        let csum = match l3.src {
+            _ if l3.frag.is_some() => None,
+            IpAddress::Ipv4(_) if packet.checksum() == 0 => None,
+            _ => Some(packet.verify_checksum(&l3.src, &l3.dst)),
+        };
         Ok(L4Info {
             src: packet.src_port(),
             dst: packet.dst_port(),
             header_len: UDP_HEADER_LEN,
+            csum,
+            tcp_flags: 0,
+            tcp_seq: 0,
         })
     }
 }
@@ -53,7 +82,7 @@ impl UdpParser {
 struct UnknownParser;
 
 impl UnknownParser {
-    fn parse(&self, _buffer: &[u8]) -> Result<L4Info> {
+    fn parse(&self, _buffer: &[u8], _l3: &L3Info) -> Result<L4Info> {
         Ok(L4Info::default())
     }
 }
@@ -73,11 +102,11 @@ impl L4ParserInner {
         }
     }
 
-    fn parse(&self, buffer: &[u8]) -> Result<L4Info> {
+    fn parse(&self, buffer: &[u8], l3: &L3Info) -> Result<L4Info> {
         match self {
-            L4ParserInner::Tcp(parser) => parser.parse(buffer),
-            L4ParserInner::Udp(parser) => parser.parse(buffer),
-            L4ParserInner::Unknown(parser) => parser.parse(buffer),
+            L4ParserInner::Tcp(parser) => parser.parse(buffer, l3),
+            L4ParserInner::Udp(parser) => parser.parse(buffer, l3),
+            L4ParserInner::Unknown(parser) => parser.parse(buffer, l3),
         }
     }
 }
@@ -89,8 +118,14 @@ impl Pipeline for L4Parser {
     type Output = (Self::Input, L4Info);
     fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
         let (l2, l3) = i;
+        let start = l2.header_len + l3.header_len;
+        let end = std::cmp::min(start + l3.payload_len, buffer.len());
+        //only the first fragment carries the l4 header
+        if l3.frag.is_some_and(|offset| offset != 0) {
+            return Ok((*i, L4Info::default()));
+        }
         L4ParserInner::new(&l3.protocol)
-            .parse(&buffer[l2.header_len + l3.header_len..])
+            .parse(&buffer[start..end], l3)
             .map(|r| (*i, r))
     }
 }
@@ -133,4 +168,32 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn l4_parser_ipv6_ext_test() {
+        let expect = [
+            (0x1234, 80, Some(true)),
+            (0x1234, 53, Some(true)),
+            (0x1234, 80, None),
+            (0, 0, None),
+            (0x1234, 53, Some(true)),
+            (0x1234, 53, None),
+        ];
+        let mut mac = PcapMacRx::new("pcaps/ipv6_ext_hdrs.pcap");
+        for (src, dst, csum) in expect {
+            let l4_info = mac
+                .receive()
+                .unwrap()
+                .consume(Instant::now(), |buffer| {
+                    let (_, l4_info) = L2Parser
+                        .comb(L3Parser)
+                        .comb(L4Parser)
+                        .execute(buffer, &())?;
+                    Ok(l4_info)
+                })
+                .unwrap();
+            println!("{:#x?}", l4_info);
+            assert_eq!((l4_info.src, l4_info.dst, l4_info.csum), (src, dst, csum));
+        }
+    }
 }
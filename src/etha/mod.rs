@@ -1,27 +1,39 @@
 pub mod desc;
+mod ecpri_parser;
 mod etha;
 mod etha_core;
 #[cfg(not(test))]
 mod ffi;
+mod irq_table;
 mod l2_parser;
 mod l3_parser;
 mod l4_parser;
 mod parser;
 mod pipeline;
+mod ptp;
 pub mod reg_if;
 mod rx_ch;
 mod rx_datapath;
 mod rx_dispatcher;
 mod rx_filter;
+mod rx_mac_filter;
+mod switch;
+mod tunnel_parser;
 mod tx_ch;
 mod tx_datapath;
 mod tx_sequencer;
+mod tx_shaper;
+mod tx_tso;
 pub use etha::*;
 use pipeline::*;
 
 pub const CHS: usize = 16;
 pub const RX_TP5_FILTERS: usize = CHS;
 pub const RX_ET_FILTERS: usize = 4;
+pub const RX_VLAN_FILTERS: usize = 8;
+pub const RX_ECPRI_FILTERS: usize = 8;
 
 const MIN_FRAME_LEN: usize = smoltcp::wire::ETHERNET_HEADER_LEN;
+const TSO_MAX_LEN: usize = 0x10000;
+const TX_BUFFER_LEN: usize = 4 * TSO_MAX_LEN;
 pub const STATICS_TAR: &str = "etha";
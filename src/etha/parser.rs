@@ -1,20 +1,44 @@
+use super::ecpri_parser::*;
 use super::l2_parser::*;
 use super::l3_parser::*;
 use super::l4_parser::*;
+use super::reg_if::rx::{LockedEcpriRegs, LockedTunnelRegs, RxRegs};
+use super::rx_filter::RssInfo;
+use super::tunnel_parser::*;
 use super::Result;
 use super::*;
+use std::sync::Arc;
 
 #[derive(Debug, Copy, Clone)]
 pub struct ParserInfo {
     pub l2: L2Info,
     pub l3: L3Info,
     pub l4: L4Info,
+    pub rss: RssInfo,
+    pub tunnel: Option<TunnelInfo>,
+    pub ecpri: Option<EcpriInfo>,
+    //phc time at arrival
+    pub ts: Option<u64>,
 }
 
-pub struct EthaRxParser;
+pub struct EthaRxParser {
+    tunnel: Arc<LockedTunnelRegs>,
+    ecpri: Arc<LockedEcpriRegs>,
+}
 impl EthaRxParser {
+    pub fn new(regs: &RxRegs<RX_ET_FILTERS, RX_TP5_FILTERS>) -> Self {
+        EthaRxParser {
+            tunnel: regs.tunnel.clone(),
+            ecpri: regs.ecpri.clone(),
+        }
+    }
     pub fn pipeline<'a>(&'a self) -> impl Pipeline<Input = (), Output = ParserInfo> + 'a {
-        L2Parser.comb(L3Parser).comb(L4Parser).comb(EthaRxParserFmt)
+        L2Parser
+            .comb(L3Parser)
+            .comb(L4Parser)
+            .comb(EthaRxParserFmt)
+            .comb(EcpriParser { cfg: &self.ecpri })
+            .comb(TunnelParser { cfg: &self.tunnel })
     }
 }
 
@@ -25,6 +49,14 @@ impl Pipeline for EthaRxParserFmt {
     type Output = ParserInfo;
     fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
         let ((l2, l3), l4) = *i;
-        Ok(ParserInfo { l2, l3, l4 })
+        Ok(ParserInfo {
+            l2,
+            l3,
+            l4,
+            rss: RssInfo::default(),
+            tunnel: None,
+            ecpri: None,
+            ts: None,
+        })
     }
 }
@@ -1,3 +1,5 @@
+use super::CHS;
+use super::irq_table::IrqTable;
 use crate::irq::*;
 use std::sync::{Arc, Mutex};
 #[derive(Debug)]
@@ -71,24 +73,26 @@ where
     }
 }
 
-pub struct EthaIrqs<'a, R: WithIrq> {
+pub struct EthaIrqs<'a, R: WithIrqCauses> {
     chs: &'a [R],
+    table: &'a IrqTable<CHS>,
     irqs: &'a Arc<Mutex<IrqVec>>,
 }
-impl<'a, R: WithIrq> EthaIrqs<'a, R> {
-    pub fn new(chs: &'a [R], irqs: &'a Arc<Mutex<IrqVec>>) -> Self {
-        EthaIrqs { chs, irqs }
+impl<'a, R: WithIrqCauses> EthaIrqs<'a, R> {
+    pub fn new(chs: &'a [R], table: &'a IrqTable<CHS>, irqs: &'a Arc<Mutex<IrqVec>>) -> Self {
+        EthaIrqs { chs, table, irqs }
     }
 }
 
-impl<'a, R: WithIrq> Pipeline for EthaIrqs<'a, R> {
+impl<'a, R: WithIrqCauses> Pipeline for EthaIrqs<'a, R> {
     type Input = ();
     type Output = ();
     fn execute(&mut self, _: &mut [u8], _: &Self::Input) -> Result<Self::Output> {
         let irqs = self.irqs.lock().unwrap();
-        for irq in self.chs.iter().filter_map(|ch| ch.poll_irq()) {
-            irqs.send(irq)
+        for (cause, subs) in self.chs.iter().map(|ch| ch.poll_causes()) {
+            self.table.raise(cause, subs);
         }
+        self.table.poll(&irqs);
         Ok(())
     }
 }
@@ -0,0 +1,88 @@
+use super::parser::ParserInfo;
+use crate::clock::ModelClock;
+use smoltcp::wire::IpProtocol;
+use std::sync::{Arc, Mutex};
+
+pub const PTP_ETHERTYPE: u16 = 0x88f7;
+pub const PTP_EVENT_PORT: u16 = 319;
+pub const PTP_GENERAL_PORT: u16 = 320;
+const NS_PER_SEC: i128 = 1_000_000_000;
+
+//phc time at model time base
+#[derive(Copy, Clone, Default)]
+struct PhcState {
+    base: u64,
+    time: u64,
+    ppb: i64,
+}
+
+//the phc follows the model clock, so timestamps are deterministic in virtual time
+pub struct Phc {
+    clock: Arc<ModelClock>,
+    state: Mutex<PhcState>,
+}
+
+impl Phc {
+    pub fn new(clock: &Arc<ModelClock>) -> Self {
+        Phc {
+            clock: clock.clone(),
+            state: Mutex::new(PhcState::default()),
+        }
+    }
+    fn at(state: &PhcState, now: u64) -> u64 {
+        let elapsed = now.saturating_sub(state.base) as i128;
+        (state.time as i128 + elapsed + elapsed * state.ppb as i128 / NS_PER_SEC) as u64
+    }
+    pub fn time(&self) -> u64 {
+        Self::at(&self.state.lock().unwrap(), self.clock.now())
+    }
+    //adjustments only apply from now on
+    fn rebase<F: FnOnce(&mut PhcState)>(&self, f: F) {
+        let mut state = self.state.lock().unwrap();
+        let now = self.clock.now();
+        state.time = Self::at(&state, now);
+        state.base = now;
+        f(&mut state)
+    }
+    pub fn set_time(&self, time: u64) {
+        self.rebase(|s| s.time = time)
+    }
+    pub fn adjust(&self, delta: i64) {
+        self.rebase(|s| s.time = s.time.wrapping_add_signed(delta))
+    }
+    pub fn adjust_freq(&self, ppb: i64) {
+        self.rebase(|s| s.ppb = ppb)
+    }
+}
+
+//ptp over ethernet or over udp
+pub fn is_ptp(info: &ParserInfo) -> bool {
+    u16::from(info.l2.etype) == PTP_ETHERTYPE
+        || (info.l3.protocol == IpProtocol::Udp
+            && matches!(info.l4.dst, PTP_EVENT_PORT | PTP_GENERAL_PORT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phc_test() {
+        let clock = Arc::new(ModelClock::new());
+        clock.set_virtual(true);
+        let phc = Phc::new(&clock);
+        let start = clock.now();
+        phc.set_time(1_000_000_000);
+        clock.advance_to(start + 1000);
+        assert_eq!(phc.time(), 1_000_001_000);
+        phc.adjust(-500);
+        assert_eq!(phc.time(), 1_000_000_500);
+        //100 ppm faster
+        phc.adjust_freq(100_000);
+        clock.advance_to(start + 1_000_000 + 1000);
+        assert_eq!(phc.time(), 1_000_000_500 + 1_000_100);
+        phc.adjust_freq(0);
+        clock.advance_to(start + 2_000_000 + 1000);
+        assert_eq!(phc.time(), 1_000_000_500 + 2_000_100);
+    }
+}
@@ -1,6 +1,26 @@
 use etha_model_generator::*;
 pub const GLOBAL_RX_EN_OFFSET: usize = 0x0;
 pub const GLOBAL_TX_EN_OFFSET: usize = 0x1;
+pub const GLOBAL_RX_VLAN_STRIP_OFFSET: usize = 0x2;
+pub const GLOBAL_TX_ARB_OFFSET: usize = 0x3;
+pub const GLOBAL_TX_ARB_PRI_OFFSET: usize = 0x4;
+pub const GLOBAL_TX_ARB_WEIGHT_OFFSET: usize = 0x6;
+pub const GLOBAL_CLOCK_CTRL_OFFSET: usize = 0xa;
+pub const GLOBAL_CLOCK_LO_OFFSET: usize = 0xb;
+pub const GLOBAL_CLOCK_HI_OFFSET: usize = 0xc;
+pub const GLOBAL_RX_MODE_OFFSET: usize = 0xd;
+pub const GLOBAL_MC_HASH_OFFSET: usize = 0xe;
+pub const GLOBAL_STATION_OFFSET: usize = 0x10;
+pub const GLOBAL_PTP_CTRL_OFFSET: usize = 0x18;
+pub const GLOBAL_PTP_TIME_LO_OFFSET: usize = 0x19;
+pub const GLOBAL_PTP_TIME_HI_OFFSET: usize = 0x1a;
+pub const GLOBAL_PTP_SET_LO_OFFSET: usize = 0x1b;
+pub const GLOBAL_PTP_SET_HI_OFFSET: usize = 0x1c;
+pub const GLOBAL_PTP_ADJ_OFFSET: usize = 0x1d;
+pub const GLOBAL_PTP_FREQ_OFFSET: usize = 0x1e;
+pub const RX_STATION_ADDRS: usize = 4;
+pub const TX_ARB_PRI_PER_REG: usize = 8;
+pub const TX_ARB_WEIGHT_PER_REG: usize = 4;
 define_reg! {
     EthaEn {
         fields {
@@ -9,9 +29,259 @@ define_reg! {
     }
 }
 
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TxArbMode {
+    RoundRobin = 0,
+    StrictPriority = 1,
+    Dwrr = 2,
+    Hybrid = 3,
+}
+
+impl std::convert::From<u64> for TxArbMode {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => TxArbMode::StrictPriority,
+            2 => TxArbMode::Dwrr,
+            3 => TxArbMode::Hybrid,
+            _ => TxArbMode::RoundRobin,
+        }
+    }
+}
+
+define_reg! {
+    TxArbCtrl {
+        fields {
+            mode(RW){rr:0, sp:1, dwrr:2, hybrid:3}: 1, 0;
+        }
+    }
+}
+
+//higher value is higher priority
+define_reg! {
+    TxArbPri {
+        fields {
+            pri0(RW): 2, 0;
+            pri1(RW): 6, 4;
+            pri2(RW): 10, 8;
+            pri3(RW): 14, 12;
+            pri4(RW): 18, 16;
+            pri5(RW): 22, 20;
+            pri6(RW): 26, 24;
+            pri7(RW): 30, 28;
+        }
+    }
+}
+
+//dwrr quantum in units of 256 bytes, 0 is treated as 1
+define_reg! {
+    TxArbWeight {
+        fields {
+            weight0(RW): 7, 0;
+            weight1(RW): 15, 8;
+            weight2(RW): 23, 16;
+            weight3(RW): 31, 24;
+        }
+    }
+}
+
+define_reg! {
+    ClockCtrl {
+        fields {
+            virtual_en(RW): 0, 0;
+        }
+    }
+}
+
+//reading lo latches hi
+define_reg! {
+    ClockCounter {
+        fields {
+            cnt(RW): 31, 0;
+        }
+    }
+}
+
+//all frames are accepted if en is clear
+define_reg! {
+    RxMode {
+        fields {
+            en(RW): 0, 0;
+            bcast_en(RW): 1, 1;
+            promisc(RW): 2, 2;
+            allmulti(RW): 3, 3;
+        }
+    }
+}
+
+//indexed by the top 6 bits of ether_crc(dst)
+define_reg! {
+    McHash {
+        fields {
+            bits(RW): 31, 0;
+        }
+    }
+}
+
+//mac address bytes 0-1 are in hi and 2-5 in lo, in network order
+define_reg! {
+    StationAddrLo {
+        fields {
+            mac(RW): 31, 0;
+        }
+    }
+}
+
+define_reg! {
+    StationAddrHi {
+        fields {
+            mac(RW): 15, 0;
+            en(RW): 31, 31;
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RxTsMode {
+    None = 0,
+    Ptp = 1,
+    All = 2,
+}
+
+impl std::convert::From<u64> for RxTsMode {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => RxTsMode::Ptp,
+            2 => RxTsMode::All,
+            _ => RxTsMode::None,
+        }
+    }
+}
+
+//rx frames to be timestamped
+define_reg! {
+    PtpCtrl {
+        fields {
+            rx_ts(RW){none:0, ptp:1, all:2}: 1, 0;
+        }
+    }
+}
+
+//phc step in ns, signed
+define_reg! {
+    PtpAdj {
+        fields {
+            delta(RW): 31, 0;
+        }
+    }
+}
+
+//phc frequency adjustment in ppb, signed
+define_reg! {
+    PtpFreq {
+        fields {
+            ppb(RW): 31, 0;
+        }
+    }
+}
+
+//reading time_lo latches time_hi, writing set_hi loads the phc with set_hi:set_lo,
+//writing adj steps the phc
 reg_map! {
     pub EthaGlobalRegs(1024) {
         rx_en(RW): EthaEn, 0;
         tx_en(RW): EthaEn, 1;
+        rx_vlan_strip(RW): EthaEn, 2;
+        tx_arb(RW): TxArbCtrl, 3;
+        tx_arb_pri0(RW): TxArbPri, 4;
+        tx_arb_pri1(RW): TxArbPri, 5;
+        tx_arb_weight0(RW): TxArbWeight, 6;
+        tx_arb_weight1(RW): TxArbWeight, 7;
+        tx_arb_weight2(RW): TxArbWeight, 8;
+        tx_arb_weight3(RW): TxArbWeight, 9;
+        clock_ctrl(RW): ClockCtrl, 0xa;
+        clock_lo(RO): ClockCounter, 0xb;
+        clock_hi(RO): ClockCounter, 0xc;
+        rx_mode(RW): RxMode, 0xd;
+        mc_hash_lo(RW): McHash, 0xe;
+        mc_hash_hi(RW): McHash, 0xf;
+        station_lo0(RW): StationAddrLo, 0x10;
+        station_hi0(RW): StationAddrHi, 0x11;
+        station_lo1(RW): StationAddrLo, 0x12;
+        station_hi1(RW): StationAddrHi, 0x13;
+        station_lo2(RW): StationAddrLo, 0x14;
+        station_hi2(RW): StationAddrHi, 0x15;
+        station_lo3(RW): StationAddrLo, 0x16;
+        station_hi3(RW): StationAddrHi, 0x17;
+        ptp_ctrl(RW): PtpCtrl, 0x18;
+        ptp_time_lo(RO): ClockCounter, 0x19;
+        ptp_time_hi(RO): ClockCounter, 0x1a;
+        ptp_set_lo(RW): ClockCounter, 0x1b;
+        ptp_set_hi(RW): ClockCounter, 0x1c;
+        ptp_adj(RW): PtpAdj, 0x1d;
+        ptp_freq(RW): PtpFreq, 0x1e;
+    }
+}
+
+impl LockedEthaGlobalRegs {
+    pub fn tx_arb_mode(&self) -> TxArbMode {
+        TxArbMode::from(self.tx_arb().mode())
+    }
+    pub fn rx_ts_mode(&self) -> RxTsMode {
+        RxTsMode::from(self.ptp_ctrl().rx_ts())
+    }
+    pub fn tx_priority(&self, ch: usize) -> usize {
+        let pri = match ch / TX_ARB_PRI_PER_REG {
+            0 => self.tx_arb_pri0(),
+            _ => self.tx_arb_pri1(),
+        };
+        (match ch % TX_ARB_PRI_PER_REG {
+            0 => pri.pri0(),
+            1 => pri.pri1(),
+            2 => pri.pri2(),
+            3 => pri.pri3(),
+            4 => pri.pri4(),
+            5 => pri.pri5(),
+            6 => pri.pri6(),
+            _ => pri.pri7(),
+        }) as usize
+    }
+    pub fn station(&self, i: usize) -> Option<[u8; 6]> {
+        let (lo, hi) = match i {
+            0 => (self.station_lo0(), self.station_hi0()),
+            1 => (self.station_lo1(), self.station_hi1()),
+            2 => (self.station_lo2(), self.station_hi2()),
+            _ => (self.station_lo3(), self.station_hi3()),
+        };
+        if hi.en() == 0 {
+            return None;
+        }
+        let (hi, lo) = (
+            (hi.mac() as u16).to_be_bytes(),
+            (lo.mac() as u32).to_be_bytes(),
+        );
+        Some([hi[0], hi[1], lo[0], lo[1], lo[2], lo[3]])
+    }
+    pub fn mc_hash(&self, bit: usize) -> bool {
+        let bits = match bit / 32 {
+            0 => self.mc_hash_lo().bits(),
+            _ => self.mc_hash_hi().bits(),
+        };
+        (bits >> (bit % 32)) & 1 == 1
+    }
+    pub fn tx_weight(&self, ch: usize) -> usize {
+        let weight = match ch / TX_ARB_WEIGHT_PER_REG {
+            0 => self.tx_arb_weight0(),
+            1 => self.tx_arb_weight1(),
+            2 => self.tx_arb_weight2(),
+            _ => self.tx_arb_weight3(),
+        };
+        (match ch % TX_ARB_WEIGHT_PER_REG {
+            0 => weight.weight0(),
+            1 => weight.weight1(),
+            2 => weight.weight2(),
+            _ => weight.weight3(),
+        }) as usize
     }
 }
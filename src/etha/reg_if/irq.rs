@@ -0,0 +1,74 @@
+use etha_model_generator::*;
+
+pub const IRQ_VECTORS: usize = 64;
+pub const IRQ_MAP_VEC_BITS: usize = 6;
+pub const IRQ_SUB_CAUSES: usize = 5;
+
+//one bit per vector, vectors 0..31 in lo and 32..63 in hi
+define_reg! {
+    IrqVecBits {
+        fields {
+            bits(RW): 31, 0;
+        }
+    }
+}
+
+//latched by the error counters of ERR_CAUSES in irq_table
+define_reg! {
+    IrqErrCause {
+        fields {
+            rx_parse_err(RW): 0, 0;
+            rx_no_queue(RW): 1, 1;
+            tx_too_large(RW): 2, 2;
+            tx_too_small(RW): 3, 3;
+            rx_addr_filter(RW): 4, 4;
+        }
+    }
+}
+
+define_reg! {
+    IrqLinkCause {
+        fields {
+            ports(RW): 3, 0;
+        }
+    }
+}
+
+//vector of each sub cause, sub causes of rings follow the bits of int_status
+define_reg! {
+    IrqCauseMap {
+        fields {
+            vec0(RW): 5, 0;
+            vec1(RW): 11, 6;
+            vec2(RW): 17, 12;
+            vec3(RW): 23, 18;
+            vec4(RW): 29, 24;
+        }
+    }
+}
+
+pub const IRQ_VEC_MASK_OFFSET: usize = 0;
+pub const IRQ_VEC_MASK_HI_OFFSET: usize = 1;
+pub const IRQ_VEC_PENDING_OFFSET: usize = 2;
+pub const IRQ_VEC_PENDING_HI_OFFSET: usize = 3;
+pub const IRQ_ERR_MASK_OFFSET: usize = 4;
+pub const IRQ_ERR_STATUS_OFFSET: usize = 5;
+pub const IRQ_LINK_MASK_OFFSET: usize = 6;
+pub const IRQ_LINK_STATUS_OFFSET: usize = 7;
+pub const IRQ_LINK_UP_OFFSET: usize = 8;
+pub const IRQ_MAP_OFFSET: usize = 0x10;
+
+//masked vectors stay pending until unmasked
+reg_map! {
+    pub IrqRegs(9) {
+        vec_mask_lo(RW): IrqVecBits, 0;
+        vec_mask_hi(RW): IrqVecBits, 1;
+        vec_pending_lo(RO): IrqVecBits, 2;
+        vec_pending_hi(RO): IrqVecBits, 3;
+        err_mask(RW): IrqErrCause, 4;
+        err_status(RW1C): IrqErrCause, 5;
+        link_mask(RW): IrqLinkCause, 6;
+        link_status(RW1C): IrqLinkCause, 7;
+        link_up(RO): IrqLinkCause, 8;
+    }
+}
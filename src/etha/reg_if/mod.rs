@@ -1,7 +1,16 @@
 pub mod global;
+pub mod irq;
 pub mod rx;
+pub mod stats;
+pub mod switch;
+pub mod tx;
+use super::irq_table::IrqTable;
+use super::ptp::Phc;
+use super::switch::Fdb;
+use crate::clock::ModelClock;
 use crate::reg_if::{RegBus, ring::*};
 use etha_model_generator::*;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 pub const RX_REGS_RANGE: std::ops::Range<usize> = 0..1024;
@@ -9,7 +18,17 @@ pub const TX_REGS_RANGE: std::ops::Range<usize> = RX_REGS_RANGE.end..RX_REGS_RAN
 pub const QUEUE_REGS_RANGE: std::ops::Range<usize> = TX_REGS_RANGE.end..TX_REGS_RANGE.end + 2048;
 pub const GLOBAL_REGS_RANGE: std::ops::Range<usize> =
     QUEUE_REGS_RANGE.end..QUEUE_REGS_RANGE.end + 1024;
+pub const STATS_REGS_RANGE: std::ops::Range<usize> =
+    GLOBAL_REGS_RANGE.end..GLOBAL_REGS_RANGE.end + 1024;
+pub const SWITCH_REGS_RANGE: std::ops::Range<usize> =
+    STATS_REGS_RANGE.end..STATS_REGS_RANGE.end + switch::SWITCH_REGS_SIZE;
+pub const IRQ_REGS_RANGE: std::ops::Range<usize> =
+    SWITCH_REGS_RANGE.end..SWITCH_REGS_RANGE.end + 0x40;
+//rx queue configs are behind the other blocks, the rings keep their layout
+pub const RX_QUEUE_REGS_RANGE: std::ops::Range<usize> =
+    IRQ_REGS_RANGE.end..IRQ_REGS_RANGE.end + 512;
 pub const QUEUE_REG_SIZE: usize = RING_REGS_SIZE * 2;
+pub const RX_QUEUE_CFG_SIZE: usize = 8;
 pub const fn queue_base(i: usize) -> usize {
     QUEUE_REGS_RANGE.start + i * QUEUE_REG_SIZE
 }
@@ -19,10 +38,14 @@ pub const fn rx_ring_base(i: usize) -> usize {
 pub const fn tx_ring_base(i: usize) -> usize {
     queue_base(i) + RING_REGS_SIZE
 }
+pub const fn rx_queue_base(i: usize) -> usize {
+    RX_QUEUE_REGS_RANGE.start + i * RX_QUEUE_CFG_SIZE
+}
 
 pub struct ChRegs {
     pub rx: Arc<LockedRingRegs>,
     pub tx: Arc<LockedRingRegs>,
+    pub rx_queue: Arc<rx::LockedRxQueueRegs>,
 }
 
 impl ChRegs {
@@ -30,24 +53,26 @@ impl ChRegs {
         ChRegs {
             rx: Arc::new(LockedRingRegs::new(32)),
             tx: Arc::new(LockedRingRegs::new(32)),
+            rx_queue: Arc::new(rx::LockedRxQueueRegs::new(32)),
         }
     }
+    fn ring_write(ring: &LockedRingRegs, offset: u64, data: u64) -> Option<()> {
+        let r = ring.bus_write(offset, data);
+        ring.r_update_status();
+        r
+    }
+    fn ring_read(ring: &LockedRingRegs, offset: u64) -> Option<u64> {
+        ring.r_update_status();
+        ring.read(offset)
+    }
 }
 
 impl RegBus for ChRegs {
     fn write(&self, addr: u64, data: u64) -> Option<()> {
         let offset = addr % (RING_REGS_SIZE as u64);
         match (addr as usize) / RING_REGS_SIZE {
-            0 => {
-                let r = self.rx.write(offset, data);
-                self.rx.r_update_status();
-                r
-            }
-            1 => {
-                let r = self.tx.write(offset, data);
-                self.tx.r_update_status();
-                r
-            }
+            0 => Self::ring_write(&self.rx, offset, data),
+            1 => Self::ring_write(&self.tx, offset, data),
             _ => None,
         }
     }
@@ -55,14 +80,8 @@ impl RegBus for ChRegs {
     fn read(&self, addr: u64) -> Option<u64> {
         let offset = addr % (RING_REGS_SIZE as u64);
         match (addr as usize) / RING_REGS_SIZE {
-            0 => {
-                self.rx.r_update_status();
-                self.rx.read(offset)
-            }
-            1 => {
-                self.tx.r_update_status();
-                self.tx.read(offset)
-            }
+            0 => Self::ring_read(&self.rx, offset),
+            1 => Self::ring_read(&self.tx, offset),
             _ => None,
         }
     }
@@ -90,25 +109,83 @@ impl GenHeader for ChRegs {
             header,
             "#define TX_RING_OFFSET(base, name, i) ((base) + (QUEUE_REGS_SIZE * i) + TX_RINGS_OFFSET + RING_REGS_##name##_OFFSET)",
         )?;
+        rx::RxQueueRegs::gen_c_header(header)?;
+        rx::RxHdrSplit::gen_c_header(header)?;
+        rx::RxLro::gen_c_header(header)?;
+        rx::RxLroTimeout::gen_c_header(header)?;
+        writeln!(header, "#define RX_QUEUE_CFG_SIZE {:#x}", RX_QUEUE_CFG_SIZE)?;
+        writeln!(
+            header,
+            "#define RX_QUEUE_OFFSET(base, name, i) ((base) + (RX_QUEUE_CFG_SIZE * i) + RX_QUEUE_REGS_##name##_OFFSET)",
+        )?;
         Ok(())
     }
 }
 
 pub struct TopRegs<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> {
     pub rx: rx::RxRegs<ET_FILTER_N, TP5_FILTER_N>,
+    pub tx: Arc<tx::TxRegs<CHS>>,
     pub chs: [ChRegs; CHS],
     pub global: Arc<global::LockedEthaGlobalRegs>,
+    pub stats: Arc<stats::StatsRegs<CHS>>,
+    pub fdb: Arc<Fdb>,
+    pub irq: Arc<IrqTable<CHS>>,
+    pub phc: Arc<Phc>,
+    pub clock: Arc<ModelClock>,
+    //set when tx arbiter priorities or weights are written
+    pub tx_arb_dirty: Arc<AtomicBool>,
 }
 
 impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize>
     TopRegs<CHS, ET_FILTER_N, TP5_FILTER_N>
 {
     pub fn new() -> Self {
+        let clock = Arc::new(ModelClock::new());
+        let stats = Arc::new(stats::StatsRegs::new());
         TopRegs {
             rx: rx::RxRegs::new(),
+            tx: Arc::new(tx::TxRegs::new()),
             chs: array_init::array_init(|_| ChRegs::new()),
             global: Arc::new(global::LockedEthaGlobalRegs::new(32)),
+            irq: Arc::new(IrqTable::new(&stats)),
+            stats,
+            fdb: Arc::new(Fdb::new(&clock)),
+            phc: Arc::new(Phc::new(&clock)),
+            clock,
+            tx_arb_dirty: Arc::new(AtomicBool::new(true)),
+        }
+    }
+    fn global_write(&self, offset: usize, data: u64) -> Option<()> {
+        self.global.write(offset as u64, data)?;
+        match offset {
+            global::GLOBAL_CLOCK_CTRL_OFFSET => self
+                .clock
+                .set_virtual(self.global.clock_ctrl().virtual_en() == 1),
+            global::GLOBAL_PTP_SET_HI_OFFSET => self
+                .phc
+                .set_time(self.global.ptp_set_hi().cnt() << 32 | self.global.ptp_set_lo().cnt()),
+            global::GLOBAL_PTP_ADJ_OFFSET => self.phc.adjust(data as i32 as i64),
+            global::GLOBAL_PTP_FREQ_OFFSET => self.phc.adjust_freq(data as i32 as i64),
+            //priority and weight registers are in front of clock ctrl
+            global::GLOBAL_TX_ARB_PRI_OFFSET..global::GLOBAL_CLOCK_CTRL_OFFSET => {
+                self.tx_arb_dirty.store(true, Ordering::SeqCst)
+            }
+            _ => {}
         }
+        Some(())
+    }
+    fn global_read(&self, offset: usize) -> Option<u64> {
+        if offset == global::GLOBAL_CLOCK_LO_OFFSET {
+            let now = self.clock.now();
+            self.global.clock_lo_mut().set_cnt(now & 0xffff_ffff);
+            self.global.clock_hi_mut().set_cnt(now >> 32);
+        }
+        if offset == global::GLOBAL_PTP_TIME_LO_OFFSET {
+            let now = self.phc.time();
+            self.global.ptp_time_lo_mut().set_cnt(now & 0xffff_ffff);
+            self.global.ptp_time_hi_mut().set_cnt(now >> 32);
+        }
+        self.global.read(offset as u64)
     }
 }
 
@@ -119,6 +196,8 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegB
         let offset = addr as usize;
         if RX_REGS_RANGE.contains(&offset) {
             self.rx.write(addr, data)
+        } else if TX_REGS_RANGE.contains(&offset) {
+            self.tx.write((offset - TX_REGS_RANGE.start) as u64, data)
         } else if QUEUE_REGS_RANGE.contains(&offset) {
             let offset = offset - QUEUE_REGS_RANGE.start;
             let idx = offset / QUEUE_REG_SIZE;
@@ -128,8 +207,25 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegB
                 None
             }
         } else if GLOBAL_REGS_RANGE.contains(&offset) {
-            let offset = offset - GLOBAL_REGS_RANGE.start;
-            self.global.write(offset as u64, data)
+            self.global_write(offset - GLOBAL_REGS_RANGE.start, data)
+        } else if STATS_REGS_RANGE.contains(&offset) {
+            self.stats
+                .write((offset - STATS_REGS_RANGE.start) as u64, data)
+        } else if SWITCH_REGS_RANGE.contains(&offset) {
+            self.fdb
+                .write((offset - SWITCH_REGS_RANGE.start) as u64, data)
+        } else if IRQ_REGS_RANGE.contains(&offset) {
+            self.irq.write((offset - IRQ_REGS_RANGE.start) as u64, data)
+        } else if RX_QUEUE_REGS_RANGE.contains(&offset) {
+            let offset = offset - RX_QUEUE_REGS_RANGE.start;
+            let idx = offset / RX_QUEUE_CFG_SIZE;
+            if idx < self.chs.len() {
+                self.chs[idx]
+                    .rx_queue
+                    .write((offset % RX_QUEUE_CFG_SIZE) as u64, data)
+            } else {
+                None
+            }
         } else {
             None
         }
@@ -139,6 +235,8 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegB
         let offset = addr as usize;
         if RX_REGS_RANGE.contains(&offset) {
             self.rx.read(addr)
+        } else if TX_REGS_RANGE.contains(&offset) {
+            self.tx.read((offset - TX_REGS_RANGE.start) as u64)
         } else if QUEUE_REGS_RANGE.contains(&offset) {
             let offset = offset - QUEUE_REGS_RANGE.start;
             let idx = offset / QUEUE_REG_SIZE;
@@ -148,8 +246,23 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegB
                 None
             }
         } else if GLOBAL_REGS_RANGE.contains(&offset) {
-            let offset = offset - GLOBAL_REGS_RANGE.start;
-            self.global.read(offset as u64)
+            self.global_read(offset - GLOBAL_REGS_RANGE.start)
+        } else if STATS_REGS_RANGE.contains(&offset) {
+            self.stats.read((offset - STATS_REGS_RANGE.start) as u64)
+        } else if SWITCH_REGS_RANGE.contains(&offset) {
+            self.fdb.read((offset - SWITCH_REGS_RANGE.start) as u64)
+        } else if IRQ_REGS_RANGE.contains(&offset) {
+            self.irq.read((offset - IRQ_REGS_RANGE.start) as u64)
+        } else if RX_QUEUE_REGS_RANGE.contains(&offset) {
+            let offset = offset - RX_QUEUE_REGS_RANGE.start;
+            let idx = offset / RX_QUEUE_CFG_SIZE;
+            if idx < self.chs.len() {
+                self.chs[idx]
+                    .rx_queue
+                    .read((offset % RX_QUEUE_CFG_SIZE) as u64)
+            } else {
+                None
+            }
         } else {
             None
         }
@@ -178,10 +291,51 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenH
         )?;
         global::EthaGlobalRegs::gen_c_header(header)?;
         global::EthaEn::gen_c_header(header)?;
+        global::TxArbCtrl::gen_c_header(header)?;
+        global::TxArbPri::gen_c_header(header)?;
+        global::TxArbWeight::gen_c_header(header)?;
+        global::ClockCtrl::gen_c_header(header)?;
+        global::ClockCounter::gen_c_header(header)?;
+        global::RxMode::gen_c_header(header)?;
+        global::McHash::gen_c_header(header)?;
+        global::StationAddrLo::gen_c_header(header)?;
+        global::StationAddrHi::gen_c_header(header)?;
+        global::PtpCtrl::gen_c_header(header)?;
+        global::PtpAdj::gen_c_header(header)?;
+        global::PtpFreq::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define RX_STATION_ADDRS {}",
+            global::RX_STATION_ADDRS
+        )?;
+        writeln!(
+            header,
+            "#define STATS_REGS_OFFSET {:#x}",
+            STATS_REGS_RANGE.start
+        )?;
+        stats::StatsRegs::<CHS>::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define SWITCH_REGS_OFFSET {:#x}",
+            SWITCH_REGS_RANGE.start
+        )?;
+        Fdb::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define IRQ_REGS_OFFSET {:#x}",
+            IRQ_REGS_RANGE.start
+        )?;
+        IrqTable::<CHS>::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define RX_QUEUE_REGS_OFFSET {:#x}",
+            RX_QUEUE_REGS_RANGE.start
+        )?;
 
         ChRegs::gen_c_header(header)?;
 
         rx::RxRegs::<ET_FILTER_N, TP5_FILTER_N>::gen_c_header(header)?;
+        tx::TxRegs::<CHS>::gen_c_header(header)?;
 
         writeln!(
             header,
@@ -191,7 +345,37 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenH
             header,
             "#define ET_FILTER(i) ET_FILTER_OFFSET(RX_REGS_OFFSET, i)",
         )?;
+        writeln!(
+            header,
+            "#define VLAN_FILTER(i) VLAN_FILTER_OFFSET(RX_REGS_OFFSET, i)",
+        )?;
+        writeln!(
+            header,
+            "#define ECPRI_FILTER(name, i) ECPRI_FILTER_OFFSET(RX_REGS_OFFSET, name, i)",
+        )?;
         writeln!(header, "#define DEFAULT_Q DEFAULT_Q_OFFSET(RX_REGS_OFFSET)",)?;
+        writeln!(
+            header,
+            "#define RSS_CFG(name) RSS_CFG_OFFSET(RSS_OFFSET(RX_REGS_OFFSET), name)",
+        )?;
+        writeln!(
+            header,
+            "#define RSS_RETA_REG(i) RSS_RETA_REG_OFFSET(RSS_OFFSET(RX_REGS_OFFSET), i)",
+        )?;
+        writeln!(header, "#define TUNNEL_CTRL TUNNEL_OFFSET(RX_REGS_OFFSET)",)?;
+        writeln!(header, "#define ECPRI_CTRL ECPRI_OFFSET(RX_REGS_OFFSET)",)?;
+        writeln!(
+            header,
+            "#define FLOW_TABLE(name) FLOW_TABLE_OFFSET(RX_REGS_OFFSET, name)",
+        )?;
+        writeln!(
+            header,
+            "#define TX_SHAPER(name, i) TX_SHAPER_OFFSET(TX_REGS_OFFSET, name, i)",
+        )?;
+        writeln!(
+            header,
+            "#define TX_PORT_SHAPER(name) TX_PORT_SHAPER_OFFSET(TX_REGS_OFFSET, name)",
+        )?;
         writeln!(
             header,
             "#define RX_RING(name, i) RX_RING_OFFSET(QUEUE_REGS_OFFSET, name, i)",
@@ -200,6 +384,10 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenH
             header,
             "#define TX_RING(name, i) TX_RING_OFFSET(QUEUE_REGS_OFFSET, name, i)",
         )?;
+        writeln!(
+            header,
+            "#define RX_QUEUE(name, i) RX_QUEUE_OFFSET(RX_QUEUE_REGS_OFFSET, name, i)",
+        )?;
         writeln!(
             header,
             "#define RX_EN (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_RX_EN_OFFSET)",
@@ -208,6 +396,100 @@ impl<const CHS: usize, const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenH
             header,
             "#define TX_EN (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_EN_OFFSET)",
         )?;
+        writeln!(
+            header,
+            "#define RX_VLAN_STRIP (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_RX_VLAN_STRIP_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define TX_ARB (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_ARB_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define TX_ARB_PRI(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_ARB_PRI0_OFFSET + (i) / {})",
+            global::TX_ARB_PRI_PER_REG
+        )?;
+        writeln!(
+            header,
+            "#define TX_ARB_WEIGHT(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_TX_ARB_WEIGHT0_OFFSET + (i) / {})",
+            global::TX_ARB_WEIGHT_PER_REG
+        )?;
+        writeln!(
+            header,
+            "#define CLOCK_CTRL (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_CLOCK_CTRL_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_CTRL (STATS_REGS_OFFSET + STATS_CTRL_REGS_STATS_CTRL_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_LO(name) (STATS_REGS_OFFSET + STATS_##name##_LO_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_HI(name) (STATS_REGS_OFFSET + STATS_##name##_HI_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_RX_HIST_LO(i) (STATS_REGS_OFFSET + STATS_RX_HIST_OFFSET + (i) * STATS_COUNTER_SIZE)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_RX_HIST_HI(i) (STATS_RX_HIST_LO(i) + 1)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_TX_HIST_LO(i) (STATS_REGS_OFFSET + STATS_TX_HIST_OFFSET + (i) * STATS_COUNTER_SIZE)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_TX_HIST_HI(i) (STATS_TX_HIST_LO(i) + 1)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_QUEUE_LO(name, i) (STATS_REGS_OFFSET + STATS_QUEUE_OFFSET + (i) * STATS_QUEUE_SIZE + STATS_QUEUE_##name##_LO_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define STATS_QUEUE_HI(name, i) (STATS_REGS_OFFSET + STATS_QUEUE_OFFSET + (i) * STATS_QUEUE_SIZE + STATS_QUEUE_##name##_HI_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define CLOCK_LO (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_CLOCK_LO_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define CLOCK_HI (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_CLOCK_HI_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define SWITCH(name) (SWITCH_REGS_OFFSET + SWITCH_REGS_##name##_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define IRQ(name) (IRQ_REGS_OFFSET + IRQ_REGS_##name##_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define IRQ_MAP(cause) (IRQ_REGS_OFFSET + IRQ_MAP_OFFSET + (cause))",
+        )?;
+        writeln!(
+            header,
+            "#define RX_MODE (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_RX_MODE_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define MC_HASH(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_MC_HASH_LO_OFFSET + (i))",
+        )?;
+        writeln!(
+            header,
+            "#define STATION_LO(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_STATION_LO0_OFFSET + (i) * 2)",
+        )?;
+        writeln!(
+            header,
+            "#define STATION_HI(i) (GLOBAL_REGS_OFFSET + ETHA_GLOBAL_REGS_STATION_HI0_OFFSET + (i) * 2)",
+        )?;
         Ok(())
     }
 }
@@ -1,6 +1,9 @@
+use super::super::{RX_ECPRI_FILTERS, RX_ET_FILTERS, RX_TP5_FILTERS, RX_VLAN_FILTERS};
+use super::RX_REGS_RANGE;
+use crate::reg_if::ring::{LockedRingBase, RingBase};
 use crate::reg_if::RegBus;
 use etha_model_generator::*;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 pub const fn set_filter_queue_id(id: usize) -> usize {
     (id & 0xff) << 16
 }
@@ -50,6 +53,19 @@ impl std::convert::From<u8> for CongestionAction {
     }
 }
 
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum RssHashType {
+    #[default]
+    None = 0,
+    Ipv4 = 1,
+    TcpIpv4 = 2,
+    UdpIpv4 = 3,
+    Ipv6 = 4,
+    TcpIpv6 = 5,
+    UdpIpv6 = 6,
+}
+
 pub const fn set_filter_etype(etype: u16) -> usize {
     etype as usize
 }
@@ -96,12 +112,35 @@ define_reg! {
     }
 }
 
+//prefix length 0 means the whole address
+define_reg! {
+    TuplesFilterPrefix {
+        fields {
+            src_prefix(RW): 7, 0;
+            dst_prefix(RW): 15, 8;
+        }
+    }
+}
+
+define_reg! {
+    TuplesFilterTcpFlags {
+        fields {
+            flags(RW): 8, 0;
+            flags_mask(RW): 24, 16;
+        }
+    }
+}
+
 define_reg! {
     TuplesFilterCtrl {
         fields {
             protocol(RW): 7, 0;
             pri(RW): 10, 8;
             ipv6(RW): 11, 11;
+            inner(RW): 12, 12;
+            src_port_range(RW): 13, 13;
+            dst_port_range(RW): 14, 14;
+            tcp_flags_en(RW): 15, 15;
             queue_id(RW): 23, 16;
             src_mask(RW):24, 24;
             dst_mask(RW):25, 25;
@@ -124,6 +163,62 @@ impl LockedTuplesFilterCtrl {
     }
 }
 
+define_reg! {
+    VlanFilter {
+        fields {
+            vid(RW): 11, 0;
+            pcp(RW): 14, 12;
+            svlan(RW): 15, 15;
+            queue_id(RW): 23, 16;
+            vid_mask(RW): 24, 24;
+            pcp_mask(RW): 25, 25;
+            congestion_action(RW){blocking:0, drop:1, default:2}:30, 29;
+            en(RW): 31, 31;
+        }
+    }
+}
+
+impl LockedVlanFilter {
+    pub fn get_congestion_action(&self) -> CongestionAction {
+        match self.congestion_action() {
+            1 => CongestionAction::Drop,
+            2 => CongestionAction::Default,
+            _ => CongestionAction::Blocking,
+        }
+    }
+}
+
+define_reg! {
+    EcpriFilterCtrl {
+        fields {
+            msg_type(RW): 7, 0;
+            msg_type_mask(RW): 8, 8;
+            queue_id(RW): 23, 16;
+            congestion_action(RW){blocking:0, drop:1, default:2}:30, 29;
+            en(RW): 31, 31;
+        }
+    }
+}
+
+impl LockedEcpriFilterCtrl {
+    pub fn get_congestion_action(&self) -> CongestionAction {
+        match self.congestion_action() {
+            1 => CongestionAction::Drop,
+            2 => CongestionAction::Default,
+            _ => CongestionAction::Blocking,
+        }
+    }
+}
+
+define_reg! {
+    EcpriFilterRange {
+        fields {
+            id_min(RW): 15, 0;
+            id_max(RW): 31, 16;
+        }
+    }
+}
+
 define_reg! {
     DefaultQueue {
         fields {
@@ -143,6 +238,108 @@ impl LockedDefaultQueue {
     }
 }
 
+define_reg! {
+    RssCtrl {
+        fields {
+            ipv4(RW): 0, 0;
+            tcp_ipv4(RW): 1, 1;
+            udp_ipv4(RW): 2, 2;
+            ipv6(RW): 3, 3;
+            tcp_ipv6(RW): 4, 4;
+            udp_ipv6(RW): 5, 5;
+            congestion_action(RW){blocking:0, drop:1, default:2}:30, 29;
+            en(RW): 31, 31;
+        }
+    }
+}
+
+impl LockedRssCtrl {
+    pub fn get_congestion_action(&self) -> CongestionAction {
+        match self.congestion_action() {
+            1 => CongestionAction::Drop,
+            2 => CongestionAction::Default,
+            _ => CongestionAction::Blocking,
+        }
+    }
+}
+
+define_reg! {
+    RssKey {
+        fields {
+            key(RW): 31, 0;
+        }
+    }
+}
+
+define_reg! {
+    RssReta {
+        fields {
+            queue_id0(RW): 7, 0;
+            queue_id1(RW): 15, 8;
+            queue_id2(RW): 23, 16;
+            queue_id3(RW): 31, 24;
+        }
+    }
+}
+
+impl LockedRssReta {
+    pub fn get_queue_id(&self, i: usize) -> usize {
+        (match i % RSS_RETA_ENTRIES_PER_REG {
+            0 => self.queue_id0(),
+            1 => self.queue_id1(),
+            2 => self.queue_id2(),
+            _ => self.queue_id3(),
+        }) as usize
+    }
+}
+
+//header buffers are hdr_size bytes each, one for each entry of the rx ring
+define_reg! {
+    RxHdrSplit {
+        fields {
+            en(RW): 0, 0;
+            hdr_size(RW): 31, 16;
+        }
+    }
+}
+
+//in-order tcp segments of a flow are merged up to max_size bytes,
+//an open aggregation is flushed usecs after its first segment
+define_reg! {
+    RxLro {
+        fields {
+            en(RW): 0, 0;
+            max_size(RW): 31, 16;
+        }
+    }
+}
+
+define_reg! {
+    RxLroTimeout {
+        fields {
+            usecs(RW): 31, 0;
+        }
+    }
+}
+
+reg_map! {
+    pub RxQueueRegs(5) {
+        hdr_split(RW): RxHdrSplit, 0;
+        hdr_base_lo(RW): RingBase, 1;
+        hdr_base_hi(RW): RingBase, 2;
+        lro(RW): RxLro, 3;
+        lro_timeout(RW): RxLroTimeout, 4;
+    }
+}
+
+impl LockedRxQueueRegs {
+    pub fn hdr_base(&self) -> u64 {
+        self.hdr_base_lo().addr() | self.hdr_base_hi().addr() << 32
+    }
+}
+
+pub const RX_QUEUE_REGS_SIZE: usize = 5;
+
 reg_map! {
     pub EtherTypeFilterRegs(1) {
         et_filter(RW): EtherTypeFilter, 0;
@@ -163,6 +360,316 @@ reg_map! {
         tp5_v6_dst_3(RW): TuplesFilterAddress, 7;
         tp5_port(RW): TuplesFilterPort, 8;
         tp5_ctrl(RW): TuplesFilterCtrl, 9;
+        tp5_port_max(RW): TuplesFilterPort, 10;
+        tp5_prefix(RW): TuplesFilterPrefix, 11;
+        tp5_tcp_flags(RW): TuplesFilterTcpFlags, 12;
+    }
+}
+
+reg_map! {
+    pub VlanFilterRegs(1) {
+        vlan_filter(RW): VlanFilter, 0;
+    }
+}
+
+define_reg! {
+    FlowKeyCtrl {
+        fields {
+            protocol(RW): 7, 0;
+            ipv6(RW): 8, 8;
+        }
+    }
+}
+
+define_reg! {
+    FlowAction {
+        fields {
+            queue_id(RW): 23, 16;
+            congestion_action(RW){blocking:0, drop:1, default:2}:30, 29;
+        }
+    }
+}
+
+impl LockedFlowAction {
+    pub fn get_congestion_action(&self) -> CongestionAction {
+        match self.congestion_action() {
+            1 => CongestionAction::Drop,
+            2 => CongestionAction::Default,
+            _ => CongestionAction::Blocking,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FlowCmdOp {
+    Nop = 0,
+    Add = 1,
+    Del = 2,
+    Query = 3,
+}
+
+impl std::convert::From<u64> for FlowCmdOp {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => FlowCmdOp::Add,
+            2 => FlowCmdOp::Del,
+            3 => FlowCmdOp::Query,
+            _ => FlowCmdOp::Nop,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FlowCmdResult {
+    Ok = 0,
+    NotFound = 1,
+    Exists = 2,
+    Full = 3,
+}
+
+define_reg! {
+    FlowCmd {
+        fields {
+            op(RW){nop:0, add:1, del:2, query:3}: 1, 0;
+        }
+    }
+}
+
+define_reg! {
+    FlowStatus {
+        fields {
+            result(RW){ok:0, not_found:1, exists:2, full:3}: 3, 0;
+            index(RW): 31, 16;
+        }
+    }
+}
+
+define_reg! {
+    FlowCounter {
+        fields {
+            cnt(RW): 31, 0;
+        }
+    }
+}
+
+pub const FLOW_TABLE_REGS_SIZE: usize = 0x10;
+pub const FLOW_CMD_OFFSET: usize = 11;
+
+reg_map! {
+    pub FlowTableRegs(0x10) {
+        flow_src0(RW): TuplesFilterAddress, 0;
+        flow_src1(RW): TuplesFilterAddress, 1;
+        flow_src2(RW): TuplesFilterAddress, 2;
+        flow_src3(RW): TuplesFilterAddress, 3;
+        flow_dst0(RW): TuplesFilterAddress, 4;
+        flow_dst1(RW): TuplesFilterAddress, 5;
+        flow_dst2(RW): TuplesFilterAddress, 6;
+        flow_dst3(RW): TuplesFilterAddress, 7;
+        flow_port(RW): TuplesFilterPort, 8;
+        flow_key(RW): FlowKeyCtrl, 9;
+        flow_action(RW): FlowAction, 10;
+        flow_cmd(RW): FlowCmd, 11;
+        flow_status(RO): FlowStatus, 12;
+        flow_hits(RO): FlowCounter, 13;
+        flow_ts_lo(RO): FlowCounter, 14;
+        flow_ts_hi(RO): FlowCounter, 15;
+    }
+}
+
+pub const FLOW_TABLE_SIZE: usize = 4096;
+pub const FLOW_TABLE_WAYS: usize = 4;
+const FLOW_TABLE_BUCKETS: usize = FLOW_TABLE_SIZE / FLOW_TABLE_WAYS;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FlowKey {
+    //ipv4 addresses take the first 4 bytes
+    pub src: [u8; 16],
+    pub dst: [u8; 16],
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub protocol: u8,
+    pub ipv6: bool,
+}
+
+impl FlowKey {
+    fn from_regs(regs: &LockedFlowTableRegs) -> Self {
+        let src = [
+            regs.flow_src0().get(),
+            regs.flow_src1().get(),
+            regs.flow_src2().get(),
+            regs.flow_src3().get(),
+        ];
+        let dst = [
+            regs.flow_dst0().get(),
+            regs.flow_dst1().get(),
+            regs.flow_dst2().get(),
+            regs.flow_dst3().get(),
+        ];
+        let ipv6 = regs.flow_key().ipv6() == 1;
+        let len = if ipv6 { 4 } else { 1 };
+        let mut key = FlowKey {
+            src_port: regs.flow_port().src() as u16,
+            dst_port: regs.flow_port().dst() as u16,
+            protocol: regs.flow_key().protocol() as u8,
+            ipv6,
+            ..Default::default()
+        };
+        for i in 0..len {
+            key.src[i * 4..i * 4 + 4].copy_from_slice(&(src[i] as u32).to_le_bytes());
+            key.dst[i * 4..i * 4 + 4].copy_from_slice(&(dst[i] as u32).to_le_bytes());
+        }
+        key
+    }
+
+    //fnv-1a
+    fn hash(&self) -> u32 {
+        [
+            &self.src[..],
+            &self.dst,
+            &self.src_port.to_be_bytes(),
+            &self.dst_port.to_be_bytes(),
+            &[self.protocol, self.ipv6 as u8],
+        ]
+        .concat()
+        .iter()
+        .fold(0x811c9dc5u32, |h, b| {
+            (h ^ *b as u32).wrapping_mul(0x01000193)
+        })
+    }
+
+    pub fn bucket(&self) -> std::ops::Range<usize> {
+        let start = self.hash() as usize % FLOW_TABLE_BUCKETS * FLOW_TABLE_WAYS;
+        start..start + FLOW_TABLE_WAYS
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FlowEntry {
+    key: FlowKey,
+    queue_id: usize,
+    congestion_action: CongestionAction,
+    hits: u32,
+    last_hit: u64,
+}
+
+pub struct FlowTable {
+    pub regs: LockedFlowTableRegs,
+    entries: Mutex<Vec<Option<FlowEntry>>>,
+}
+
+impl FlowTable {
+    pub fn new() -> Self {
+        FlowTable {
+            regs: LockedFlowTableRegs::new(32),
+            entries: Mutex::new(vec![None; FLOW_TABLE_SIZE]),
+        }
+    }
+
+    fn find(entries: &[Option<FlowEntry>], key: &FlowKey) -> Option<usize> {
+        key.bucket()
+            .find(|i| entries[*i].is_some_and(|e| e.key == *key))
+    }
+
+    fn execute(&self) {
+        let key = FlowKey::from_regs(&self.regs);
+        let mut entries = self.entries.lock().unwrap();
+        let found = Self::find(&entries, &key);
+        let (result, index) = match (FlowCmdOp::from(self.regs.flow_cmd().op()), found) {
+            //nop clears the status of the last command
+            (FlowCmdOp::Nop, _) => (FlowCmdResult::Ok, 0),
+            (FlowCmdOp::Add, Some(i)) => (FlowCmdResult::Exists, i),
+            (FlowCmdOp::Add, None) => match key.bucket().find(|i| entries[*i].is_none()) {
+                Some(i) => {
+                    entries[i] = Some(FlowEntry {
+                        key,
+                        queue_id: self.regs.flow_action().queue_id() as usize,
+                        congestion_action: self.regs.flow_action().get_congestion_action(),
+                        hits: 0,
+                        last_hit: 0,
+                    });
+                    (FlowCmdResult::Ok, i)
+                }
+                None => (FlowCmdResult::Full, 0),
+            },
+            (FlowCmdOp::Del, Some(i)) => {
+                entries[i] = None;
+                (FlowCmdResult::Ok, i)
+            }
+            (FlowCmdOp::Query, Some(i)) => {
+                let e = entries[i].unwrap();
+                self.regs.flow_action_mut().set_queue_id(e.queue_id as u64);
+                self.regs
+                    .flow_action_mut()
+                    .set_congestion_action(e.congestion_action as u64);
+                self.regs.flow_hits_mut().set(e.hits as u64);
+                self.regs.flow_ts_lo_mut().set(e.last_hit & 0xffff_ffff);
+                self.regs.flow_ts_hi_mut().set(e.last_hit >> 32);
+                (FlowCmdResult::Ok, i)
+            }
+            (_, None) => (FlowCmdResult::NotFound, 0),
+        };
+        self.regs.flow_status_mut().set_result(result as u64);
+        self.regs.flow_status_mut().set_index(index as u64);
+    }
+
+    //now is the model time of the hit in ns
+    pub fn lookup(&self, key: &FlowKey, now: u64) -> Option<(usize, CongestionAction)> {
+        let mut entries = self.entries.lock().unwrap();
+        let i = Self::find(&entries, key)?;
+        let e = entries[i].as_mut().unwrap();
+        e.hits = e.hits.wrapping_add(1);
+        e.last_hit = now;
+        Some((e.queue_id, e.congestion_action))
+    }
+}
+
+impl Default for FlowTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegBus for FlowTable {
+    fn write(&self, addr: u64, data: u64) -> Option<()> {
+        self.regs.write(addr, data)?;
+        //commands are done once the command register is written
+        if addr == FLOW_CMD_OFFSET as u64 {
+            self.execute();
+        }
+        Some(())
+    }
+
+    fn read(&self, addr: u64) -> Option<u64> {
+        self.regs.read(addr)
+    }
+}
+
+impl GenHeader for FlowTable {
+    fn render_name() -> &'static str {
+        "FlowTable"
+    }
+    fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
+        FlowTableRegs::gen_c_header(header)?;
+        FlowKeyCtrl::gen_c_header(header)?;
+        FlowAction::gen_c_header(header)?;
+        FlowCmd::gen_c_header(header)?;
+        FlowStatus::gen_c_header(header)?;
+        FlowCounter::gen_c_header(header)?;
+
+        writeln!(header, "#define FLOW_TABLE_SIZE {}", FLOW_TABLE_SIZE)?;
+        writeln!(header, "#define FLOW_TABLE_WAYS {}", FLOW_TABLE_WAYS)?;
+        Ok(())
+    }
+}
+
+pub const ECPRI_FILTER_REGS_SIZE: usize = 2;
+
+reg_map! {
+    pub EcpriFilterRegs(2) {
+        ecpri_filter_ctrl(RW): EcpriFilterCtrl, 0;
+        ecpri_filter_range(RW): EcpriFilterRange, 1;
     }
 }
 
@@ -172,12 +679,201 @@ reg_map! {
     }
 }
 
-pub const ET_FILTER_APERTURE: usize = 32;
+define_reg! {
+    TunnelCtrl {
+        fields {
+            vxlan_en(RW): 0, 0;
+            gre_en(RW): 1, 1;
+            ipip_en(RW): 2, 2;
+            vxlan_port(RW): 31, 16;
+        }
+    }
+}
+
+reg_map! {
+    pub TunnelRegs(1) {
+        tunnel_ctrl(RW): TunnelCtrl, 0;
+    }
+}
+
+define_reg! {
+    EcpriCtrl {
+        fields {
+            udp_port(RW): 15, 0;
+            udp_en(RW): 31, 31;
+        }
+    }
+}
+
+reg_map! {
+    pub EcpriRegs(1) {
+        ecpri_ctrl(RW): EcpriCtrl, 0;
+    }
+}
+
+pub const RSS_KEY_SIZE: usize = 40;
+pub const RSS_RETA_SIZE: usize = 128;
+pub const RSS_RETA_ENTRIES_PER_REG: usize = 4;
+pub const RSS_RETA_REGS: usize = RSS_RETA_SIZE / RSS_RETA_ENTRIES_PER_REG;
+pub const RSS_CFG_REGS_SIZE: usize = 0x10;
+pub const RSS_KEY_OFFSET: usize = 1;
+pub const RSS_RETA_OFFSET: usize = RSS_CFG_REGS_SIZE;
+
+reg_map! {
+    pub RssCfgRegs(0x10) {
+        rss_ctrl(RW): RssCtrl, 0;
+        rss_key0(RW): RssKey, 1;
+        rss_key1(RW): RssKey, 2;
+        rss_key2(RW): RssKey, 3;
+        rss_key3(RW): RssKey, 4;
+        rss_key4(RW): RssKey, 5;
+        rss_key5(RW): RssKey, 6;
+        rss_key6(RW): RssKey, 7;
+        rss_key7(RW): RssKey, 8;
+        rss_key8(RW): RssKey, 9;
+        rss_key9(RW): RssKey, 10;
+    }
+}
+
+impl LockedRssCfgRegs {
+    //key byte 4*i is stored in the lowest byte of rss_key{i}
+    pub fn key(&self) -> [u8; RSS_KEY_SIZE] {
+        let words = [
+            self.rss_key0().get(),
+            self.rss_key1().get(),
+            self.rss_key2().get(),
+            self.rss_key3().get(),
+            self.rss_key4().get(),
+            self.rss_key5().get(),
+            self.rss_key6().get(),
+            self.rss_key7().get(),
+            self.rss_key8().get(),
+            self.rss_key9().get(),
+        ];
+        let mut key = [0u8; RSS_KEY_SIZE];
+        for (k, w) in key.chunks_mut(4).zip(words.iter()) {
+            k.copy_from_slice(&(*w as u32).to_le_bytes());
+        }
+        key
+    }
+}
+
+reg_map! {
+    pub RssRetaRegs(1) {
+        rss_reta(RW): RssReta, 0;
+    }
+}
+
+pub struct RssRegs {
+    pub cfg: LockedRssCfgRegs,
+    pub reta: [LockedRssRetaRegs; RSS_RETA_REGS],
+}
+
+impl RssRegs {
+    pub fn new() -> Self {
+        RssRegs {
+            cfg: LockedRssCfgRegs::new(32),
+            reta: array_init::array_init(|_| LockedRssRetaRegs::new(32)),
+        }
+    }
+    pub fn queue_id(&self, hash: u32) -> usize {
+        let idx = hash as usize % RSS_RETA_SIZE;
+        self.reta[idx / RSS_RETA_ENTRIES_PER_REG]
+            .rss_reta()
+            .get_queue_id(idx)
+    }
+    const CFG_RANGE: std::ops::Range<u64> = 0..(RSS_CFG_REGS_SIZE as u64);
+    const RETA_RANGE: std::ops::Range<u64> =
+        (RSS_RETA_OFFSET as u64)..((RSS_RETA_OFFSET + RSS_RETA_REGS) as u64);
+    const SIZE: u64 = Self::RETA_RANGE.end;
+}
+
+impl Default for RssRegs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegBus for RssRegs {
+    fn write(&self, addr: u64, data: u64) -> Option<()> {
+        if Self::CFG_RANGE.contains(&addr) {
+            self.cfg.write(addr, data)
+        } else if Self::RETA_RANGE.contains(&addr) {
+            self.reta[(addr - Self::RETA_RANGE.start) as usize].write(0, data)
+        } else {
+            None
+        }
+    }
+
+    fn read(&self, addr: u64) -> Option<u64> {
+        if Self::CFG_RANGE.contains(&addr) {
+            self.cfg.read(addr)
+        } else if Self::RETA_RANGE.contains(&addr) {
+            self.reta[(addr - Self::RETA_RANGE.start) as usize].read(0)
+        } else {
+            None
+        }
+    }
+}
+
+impl GenHeader for RssRegs {
+    fn render_name() -> &'static str {
+        "RssRegs"
+    }
+    fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
+        RssCfgRegs::gen_c_header(header)?;
+        RssCtrl::gen_c_header(header)?;
+        RssKey::gen_c_header(header)?;
+        RssRetaRegs::gen_c_header(header)?;
+        RssReta::gen_c_header(header)?;
+
+        writeln!(header, "#define RSS_KEY_SIZE {}", RSS_KEY_SIZE)?;
+        writeln!(header, "#define RSS_RETA_SIZE {}", RSS_RETA_SIZE)?;
+        writeln!(
+            header,
+            "#define RSS_RETA_OFFSET {:#x}",
+            Self::RETA_RANGE.start
+        )?;
+        writeln!(
+            header,
+            "#define RSS_CFG_OFFSET(base, name) ((base) + RSS_CFG_REGS_##name##_OFFSET)",
+        )?;
+        writeln!(
+            header,
+            "#define RSS_RETA_REG_OFFSET(base, i) ((base) + RSS_RETA_OFFSET + (i) / {})",
+            RSS_RETA_ENTRIES_PER_REG
+        )?;
+        Ok(())
+    }
+}
+
 pub const TP5_FILTER_APERTURE: usize = TP5_FILETER_REGS_SIZE * 32;
+const RX_BLOCK_ALIGN: u64 = 0x10;
+const fn rx_block_start(end: u64) -> usize {
+    ((end + RX_BLOCK_ALIGN - 1) & !(RX_BLOCK_ALIGN - 1)) as usize
+}
+//vlan filters, ecpri filters and flow table follow the ecpri regs in this order,
+//each block starts at the next 16 regs boundary
+type RxFilterRegs = FilterRegs<RX_ET_FILTERS, RX_TP5_FILTERS>;
+type RxTopRegs = RxRegs<RX_ET_FILTERS, RX_TP5_FILTERS>;
+pub const VLAN_FILTER_START: usize = rx_block_start(RxTopRegs::ECPRI_RANGE.end);
+pub const ECPRI_FILTER_START: usize = rx_block_start(RxFilterRegs::VLAN_FILETER_RANGE.end);
+pub const FLOW_TABLE_START: usize = rx_block_start(RxFilterRegs::ECPRI_FILETER_RANGE.end);
+//rx blocks do not overlap and fit in the rx regs
+const _: () = {
+    assert!(RxFilterRegs::TP5_FILETER_RANGE.end <= RxFilterRegs::ET_FILETER_RANGE.start);
+    assert!(RxFilterRegs::ET_FILETER_RANGE.end <= RxTopRegs::DEFAULT_Q_RANGE.start);
+    assert!(RxTopRegs::ECPRI_RANGE.end <= RxFilterRegs::VLAN_FILETER_RANGE.start);
+    assert!(RxFilterRegs::VLAN_FILETER_RANGE.end <= RxFilterRegs::ECPRI_FILETER_RANGE.start);
+    assert!(RxFilterRegs::ECPRI_FILETER_RANGE.end <= RxTopRegs::FLOW_TABLE_RANGE.start);
+    assert!(RxTopRegs::FLOW_TABLE_RANGE.end <= RX_REGS_RANGE.end as u64);
+};
 
 pub struct FilterRegs<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> {
     pub et_filters: [LockedEtherTypeFilterRegs; ET_FILTER_N],
     pub tp5_filters: [LockedTuplesFilterRegs; TP5_FILTER_N],
+    pub vlan_filters: [LockedVlanFilterRegs; RX_VLAN_FILTERS],
+    pub ecpri_filters: [LockedEcpriFilterRegs; RX_ECPRI_FILTERS],
 }
 
 impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> FilterRegs<ET_FILTER_N, TP5_FILTER_N> {
@@ -185,12 +881,18 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> FilterRegs<ET_FILTER_N
         FilterRegs {
             et_filters: array_init::array_init(|_| LockedEtherTypeFilterRegs::new(32)),
             tp5_filters: array_init::array_init(|_| LockedTuplesFilterRegs::new(32)),
+            vlan_filters: array_init::array_init(|_| LockedVlanFilterRegs::new(32)),
+            ecpri_filters: array_init::array_init(|_| LockedEcpriFilterRegs::new(32)),
         }
     }
     const TP5_FILETER_RANGE: std::ops::Range<u64> =
         0..((TP5_FILTER_N * TP5_FILETER_REGS_SIZE) as u64);
     const ET_FILETER_RANGE: std::ops::Range<u64> =
         (TP5_FILTER_APERTURE as u64)..((TP5_FILTER_APERTURE + ET_FILTER_N) as u64);
+    const VLAN_FILETER_RANGE: std::ops::Range<u64> =
+        (VLAN_FILTER_START as u64)..((VLAN_FILTER_START + RX_VLAN_FILTERS) as u64);
+    const ECPRI_FILETER_RANGE: std::ops::Range<u64> = (ECPRI_FILTER_START as u64)
+        ..((ECPRI_FILTER_START + RX_ECPRI_FILTERS * ECPRI_FILTER_REGS_SIZE) as u64);
 }
 
 impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegBus
@@ -200,9 +902,16 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegBus
         if Self::ET_FILETER_RANGE.contains(&addr) {
             let offset = (addr - Self::ET_FILETER_RANGE.start) as usize;
             self.et_filters[offset].write(0, data)
+        } else if Self::VLAN_FILETER_RANGE.contains(&addr) {
+            let offset = (addr - Self::VLAN_FILETER_RANGE.start) as usize;
+            self.vlan_filters[offset].write(0, data)
+        } else if Self::ECPRI_FILETER_RANGE.contains(&addr) {
+            let offset = (addr - Self::ECPRI_FILETER_RANGE.start) as usize;
+            self.ecpri_filters[offset / ECPRI_FILTER_REGS_SIZE]
+                .write((offset % ECPRI_FILTER_REGS_SIZE) as u64, data)
         } else if Self::TP5_FILETER_RANGE.contains(&addr) {
             let offset = (addr - Self::TP5_FILETER_RANGE.start) as usize;
-            self.et_filters[offset / TP5_FILETER_REGS_SIZE]
+            self.tp5_filters[offset / TP5_FILETER_REGS_SIZE]
                 .write((offset % TP5_FILETER_REGS_SIZE) as u64, data)
         } else {
             None
@@ -213,9 +922,16 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegBus
         if Self::ET_FILETER_RANGE.contains(&addr) {
             let offset = (addr - Self::ET_FILETER_RANGE.start) as usize;
             self.et_filters[offset].read(0)
+        } else if Self::VLAN_FILETER_RANGE.contains(&addr) {
+            let offset = (addr - Self::VLAN_FILETER_RANGE.start) as usize;
+            self.vlan_filters[offset].read(0)
+        } else if Self::ECPRI_FILETER_RANGE.contains(&addr) {
+            let offset = (addr - Self::ECPRI_FILETER_RANGE.start) as usize;
+            self.ecpri_filters[offset / ECPRI_FILTER_REGS_SIZE]
+                .read((offset % ECPRI_FILTER_REGS_SIZE) as u64)
         } else if Self::TP5_FILETER_RANGE.contains(&addr) {
             let offset = (addr - Self::TP5_FILETER_RANGE.start) as usize;
-            self.et_filters[offset / TP5_FILETER_REGS_SIZE]
+            self.tp5_filters[offset / TP5_FILETER_REGS_SIZE]
                 .read((offset % TP5_FILETER_REGS_SIZE) as u64)
         } else {
             None
@@ -235,6 +951,13 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenHeader
         TuplesFilterRegs::gen_c_header(header)?;
         TuplesFilterPort::gen_c_header(header)?;
         TuplesFilterCtrl::gen_c_header(header)?;
+        TuplesFilterPrefix::gen_c_header(header)?;
+        TuplesFilterTcpFlags::gen_c_header(header)?;
+        VlanFilterRegs::gen_c_header(header)?;
+        VlanFilter::gen_c_header(header)?;
+        EcpriFilterRegs::gen_c_header(header)?;
+        EcpriFilterCtrl::gen_c_header(header)?;
+        EcpriFilterRange::gen_c_header(header)?;
 
         writeln!(header, "#define TP5_FILTERS_NUM {}", TP5_FILTER_N)?;
 
@@ -251,6 +974,22 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenHeader
             "#define ET_FILTERS_OFFSET {:#x}",
             Self::ET_FILETER_RANGE.start
         )?;
+
+        writeln!(header, "#define VLAN_FILTERS_NUM {}", RX_VLAN_FILTERS)?;
+
+        writeln!(
+            header,
+            "#define VLAN_FILTERS_OFFSET {:#x}",
+            Self::VLAN_FILETER_RANGE.start
+        )?;
+
+        writeln!(header, "#define ECPRI_FILTERS_NUM {}", RX_ECPRI_FILTERS)?;
+
+        writeln!(
+            header,
+            "#define ECPRI_FILTERS_OFFSET {:#x}",
+            Self::ECPRI_FILETER_RANGE.start
+        )?;
         writeln!(
             header,
             "#define TP5_FILTER_OFFSET(base, name, i) ((base) + TP5_FILTERS_OFFSET + (TUPLES_FILETER_REGS_SIZE * i) + TUPLES_FILTER_REGS_##name##_OFFSET)",
@@ -259,6 +998,14 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenHeader
             header,
             "#define ET_FILTER_OFFSET(base, i) ((base) + ET_FILTERS_OFFSET + (ETHER_TYPE_FILTER_REGS_SIZE * i))",
         )?;
+        writeln!(
+            header,
+            "#define VLAN_FILTER_OFFSET(base, i) ((base) + VLAN_FILTERS_OFFSET + (VLAN_FILTER_REGS_SIZE * i))",
+        )?;
+        writeln!(
+            header,
+            "#define ECPRI_FILTER_OFFSET(base, name, i) ((base) + ECPRI_FILTERS_OFFSET + (ECPRI_FILTER_REGS_SIZE * i) + ECPRI_FILTER_REGS_##name##_OFFSET)",
+        )?;
         Ok(())
     }
 }
@@ -266,6 +1013,10 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenHeader
 pub struct RxRegs<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> {
     pub filters: Arc<FilterRegs<ET_FILTER_N, TP5_FILTER_N>>,
     pub default_q: Arc<LockedDefaultFilterRegs>,
+    pub rss: Arc<RssRegs>,
+    pub tunnel: Arc<LockedTunnelRegs>,
+    pub ecpri: Arc<LockedEcpriRegs>,
+    pub flow_table: Arc<FlowTable>,
 }
 
 impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RxRegs<ET_FILTER_N, TP5_FILTER_N> {
@@ -273,19 +1024,60 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RxRegs<ET_FILTER_N, TP
         RxRegs {
             filters: Arc::new(FilterRegs::new()),
             default_q: Arc::new(LockedDefaultFilterRegs::new(32)),
+            rss: Arc::new(RssRegs::new()),
+            tunnel: Arc::new(LockedTunnelRegs::new(32)),
+            ecpri: Arc::new(LockedEcpriRegs::new(32)),
+            flow_table: Arc::new(FlowTable::new()),
         }
     }
     const DEFAULT_Q_RANGE: std::ops::Range<u64> =
         FilterRegs::<ET_FILTER_N, TP5_FILTER_N>::ET_FILETER_RANGE.end
             ..(FilterRegs::<ET_FILTER_N, TP5_FILTER_N>::ET_FILETER_RANGE.end + 1);
+    const RSS_RANGE: std::ops::Range<u64> =
+        Self::DEFAULT_Q_RANGE.end..(Self::DEFAULT_Q_RANGE.end + RssRegs::SIZE);
+    const TUNNEL_RANGE: std::ops::Range<u64> = Self::RSS_RANGE.end..(Self::RSS_RANGE.end + 1);
+    const ECPRI_RANGE: std::ops::Range<u64> = Self::TUNNEL_RANGE.end..(Self::TUNNEL_RANGE.end + 1);
+    //flow table is placed behind ecpri filters
+    const FLOW_TABLE_RANGE: std::ops::Range<u64> =
+        (FLOW_TABLE_START as u64)..((FLOW_TABLE_START + FLOW_TABLE_REGS_SIZE) as u64);
 
     pub const fn default_filter_offset(&self) -> usize {
         Self::DEFAULT_Q_RANGE.start as usize
     }
 
+    pub const fn tp5_filter_offset(&self, i: usize) -> usize {
+        FilterRegs::<ET_FILTER_N, TP5_FILTER_N>::TP5_FILETER_RANGE.start as usize
+            + i * TP5_FILETER_REGS_SIZE
+    }
+
     pub const fn et_filter_offset(&self, i: usize) -> usize {
         FilterRegs::<ET_FILTER_N, TP5_FILTER_N>::ET_FILETER_RANGE.start as usize + i
     }
+
+    pub const fn vlan_filter_offset(&self, i: usize) -> usize {
+        FilterRegs::<ET_FILTER_N, TP5_FILTER_N>::VLAN_FILETER_RANGE.start as usize + i
+    }
+
+    pub const fn rss_offset(&self) -> usize {
+        Self::RSS_RANGE.start as usize
+    }
+
+    pub const fn tunnel_offset(&self) -> usize {
+        Self::TUNNEL_RANGE.start as usize
+    }
+
+    pub const fn ecpri_offset(&self) -> usize {
+        Self::ECPRI_RANGE.start as usize
+    }
+
+    pub const fn flow_table_offset(&self) -> usize {
+        Self::FLOW_TABLE_RANGE.start as usize
+    }
+
+    pub const fn ecpri_filter_offset(&self, i: usize) -> usize {
+        FilterRegs::<ET_FILTER_N, TP5_FILTER_N>::ECPRI_FILETER_RANGE.start as usize
+            + i * ECPRI_FILTER_REGS_SIZE
+    }
 }
 
 impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegBus
@@ -294,6 +1086,15 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegBus
     fn write(&self, addr: u64, data: u64) -> Option<()> {
         if Self::DEFAULT_Q_RANGE.contains(&addr) {
             self.default_q.write(0, data)
+        } else if Self::RSS_RANGE.contains(&addr) {
+            self.rss.write(addr - Self::RSS_RANGE.start, data)
+        } else if Self::TUNNEL_RANGE.contains(&addr) {
+            self.tunnel.write(0, data)
+        } else if Self::ECPRI_RANGE.contains(&addr) {
+            self.ecpri.write(0, data)
+        } else if Self::FLOW_TABLE_RANGE.contains(&addr) {
+            self.flow_table
+                .write(addr - Self::FLOW_TABLE_RANGE.start, data)
         } else {
             self.filters.write(addr, data)
         }
@@ -302,6 +1103,14 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> RegBus
     fn read(&self, addr: u64) -> Option<u64> {
         if Self::DEFAULT_Q_RANGE.contains(&addr) {
             self.default_q.read(0)
+        } else if Self::RSS_RANGE.contains(&addr) {
+            self.rss.read(addr - Self::RSS_RANGE.start)
+        } else if Self::TUNNEL_RANGE.contains(&addr) {
+            self.tunnel.read(0)
+        } else if Self::ECPRI_RANGE.contains(&addr) {
+            self.ecpri.read(0)
+        } else if Self::FLOW_TABLE_RANGE.contains(&addr) {
+            self.flow_table.read(addr - Self::FLOW_TABLE_RANGE.start)
         } else {
             self.filters.read(addr)
         }
@@ -324,6 +1133,32 @@ impl<const ET_FILTER_N: usize, const TP5_FILTER_N: usize> GenHeader
             "#define DEFAULT_Q_OFFSET(base) ((base) + {:#x})",
             Self::DEFAULT_Q_RANGE.start
         )?;
+        RssRegs::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define RSS_OFFSET(base) ((base) + {:#x})",
+            Self::RSS_RANGE.start
+        )?;
+        TunnelRegs::gen_c_header(header)?;
+        TunnelCtrl::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define TUNNEL_OFFSET(base) ((base) + {:#x})",
+            Self::TUNNEL_RANGE.start
+        )?;
+        EcpriRegs::gen_c_header(header)?;
+        EcpriCtrl::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define ECPRI_OFFSET(base) ((base) + {:#x})",
+            Self::ECPRI_RANGE.start
+        )?;
+        FlowTable::gen_c_header(header)?;
+        writeln!(
+            header,
+            "#define FLOW_TABLE_OFFSET(base, name) ((base) + {:#x} + FLOW_TABLE_REGS_##name##_OFFSET)",
+            Self::FLOW_TABLE_RANGE.start
+        )?;
         Ok(())
     }
 }
@@ -0,0 +1,327 @@
+use crate::reg_if::RegBus;
+use etha_model_generator::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+define_reg! {
+    StatsCtrl {
+        fields {
+            clear_on_read(RW): 0, 0;
+            snapshot_en(RW): 1, 1;
+            snapshot(RW): 2, 2;
+            clear(RW): 3, 3;
+        }
+    }
+}
+
+//snapshot and clear are self cleared
+reg_map! {
+    pub StatsCtrlRegs(1) {
+        stats_ctrl(RW): StatsCtrl, 0;
+    }
+}
+
+#[repr(usize)]
+#[derive(Copy, Clone, Debug)]
+pub enum StatsCounter {
+    RxParseErr = 0,
+    RxNoQueue = 1,
+    TxTooLarge = 2,
+    TxTooSmall = 3,
+    RxAddrFilter = 4,
+}
+
+//congestion counters are split by CongestionAction
+#[repr(usize)]
+#[derive(Copy, Clone, Debug)]
+pub enum QueueCounter {
+    RxPkts = 0,
+    RxBytes = 1,
+    TxPkts = 2,
+    TxBytes = 3,
+    CongestionBlocking = 4,
+    CongestionDrop = 5,
+    CongestionDefault = 6,
+}
+
+const COUNTERS: [(&str, StatsCounter); 5] = [
+    ("RX_PARSE_ERR", StatsCounter::RxParseErr),
+    ("RX_NO_QUEUE", StatsCounter::RxNoQueue),
+    ("TX_TOO_LARGE", StatsCounter::TxTooLarge),
+    ("TX_TOO_SMALL", StatsCounter::TxTooSmall),
+    ("RX_ADDR_FILTER", StatsCounter::RxAddrFilter),
+];
+
+const QUEUE_COUNTERS: [(&str, QueueCounter); 7] = [
+    ("RX_PKTS", QueueCounter::RxPkts),
+    ("RX_BYTES", QueueCounter::RxBytes),
+    ("TX_PKTS", QueueCounter::TxPkts),
+    ("TX_BYTES", QueueCounter::TxBytes),
+    ("CONGESTION_BLOCKING", QueueCounter::CongestionBlocking),
+    ("CONGESTION_DROP", QueueCounter::CongestionDrop),
+    ("CONGESTION_DEFAULT", QueueCounter::CongestionDefault),
+];
+
+//upper bounds of frame size buckets, the last bucket is 1519+
+pub const STATS_HIST_BOUNDS: [usize; 6] = [64, 127, 255, 511, 1023, 1518];
+pub const STATS_HIST_BUCKETS: usize = STATS_HIST_BOUNDS.len() + 1;
+
+//every counter is a lo/hi pair, reading lo latches hi
+pub const STATS_COUNTER_SIZE: usize = 2;
+pub const STATS_CTRL_OFFSET: usize = 0;
+pub const STATS_COUNTER_OFFSET: usize = 0x20;
+pub const STATS_RX_HIST_OFFSET: usize = 0x40;
+pub const STATS_TX_HIST_OFFSET: usize = 0x50;
+pub const STATS_QUEUE_OFFSET: usize = 0x80;
+pub const STATS_QUEUE_SIZE: usize = 8 * STATS_COUNTER_SIZE;
+
+fn hist_bucket(len: usize) -> usize {
+    STATS_HIST_BOUNDS
+        .iter()
+        .position(|b| len <= *b)
+        .unwrap_or(STATS_HIST_BOUNDS.len())
+}
+
+//counters are indexed by their lo offsets
+pub struct StatsRegs<const CHS: usize> {
+    pub ctrl: LockedStatsCtrlRegs,
+    live: Vec<AtomicU64>,
+    snapshot: Vec<AtomicU64>,
+    //value taken by the last lo read, hi is read from it
+    latched: Vec<AtomicU64>,
+    //value to clear once hi is read if clear_on_read is set
+    to_clear: Vec<AtomicU64>,
+    //counters increased since last taken, indexed by StatsCounter
+    raised: AtomicU64,
+}
+
+impl<const CHS: usize> StatsRegs<CHS> {
+    const SIZE: usize = STATS_QUEUE_OFFSET + CHS * STATS_QUEUE_SIZE;
+    const COUNTERS: usize = Self::SIZE / STATS_COUNTER_SIZE;
+    pub fn new() -> Self {
+        let counters = || (0..Self::COUNTERS).map(|_| AtomicU64::new(0)).collect();
+        StatsRegs {
+            ctrl: LockedStatsCtrlRegs::new(32),
+            live: counters(),
+            snapshot: counters(),
+            latched: counters(),
+            to_clear: counters(),
+            raised: AtomicU64::new(0),
+        }
+    }
+    pub const fn counter_offset(c: StatsCounter) -> usize {
+        STATS_COUNTER_OFFSET + c as usize * STATS_COUNTER_SIZE
+    }
+    pub const fn queue_counter_offset(id: usize, c: QueueCounter) -> usize {
+        STATS_QUEUE_OFFSET + id * STATS_QUEUE_SIZE + c as usize * STATS_COUNTER_SIZE
+    }
+    pub const fn hist_offset(base: usize, bucket: usize) -> usize {
+        base + bucket * STATS_COUNTER_SIZE
+    }
+    fn counter(&self, offset: usize) -> &AtomicU64 {
+        &self.live[offset / STATS_COUNTER_SIZE]
+    }
+    pub fn inc(&self, c: StatsCounter) {
+        self.counter(Self::counter_offset(c)).fetch_add(1, Ordering::Relaxed);
+        self.raised.fetch_or(1 << c as usize, Ordering::Relaxed);
+    }
+    pub fn take_raised(&self) -> u64 {
+        self.raised.swap(0, Ordering::Relaxed)
+    }
+    pub fn add_queue(&self, id: usize, c: QueueCounter, v: u64) {
+        self.counter(Self::queue_counter_offset(id, c))
+            .fetch_add(v, Ordering::Relaxed);
+    }
+    pub fn rx_frame(&self, id: usize, len: usize) {
+        self.add_queue(id, QueueCounter::RxPkts, 1);
+        self.add_queue(id, QueueCounter::RxBytes, len as u64);
+    }
+    pub fn tx_frame(&self, id: usize, len: usize) {
+        self.add_queue(id, QueueCounter::TxPkts, 1);
+        self.add_queue(id, QueueCounter::TxBytes, len as u64);
+        self.counter(Self::hist_offset(STATS_TX_HIST_OFFSET, hist_bucket(len)))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn rx_hist(&self, len: usize) {
+        self.counter(Self::hist_offset(STATS_RX_HIST_OFFSET, hist_bucket(len)))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<const CHS: usize> Default for StatsRegs<CHS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CHS: usize> RegBus for StatsRegs<CHS> {
+    fn write(&self, addr: u64, data: u64) -> Option<()> {
+        if addr as usize != STATS_CTRL_OFFSET {
+            return None;
+        }
+        self.ctrl.write(addr, data)?;
+        let ctrl = self.ctrl.stats_ctrl();
+        if ctrl.snapshot() == 1 {
+            for (s, l) in self.snapshot.iter().zip(self.live.iter()) {
+                s.store(l.load(Ordering::Relaxed), Ordering::Relaxed);
+            }
+        }
+        if ctrl.clear() == 1 {
+            for (l, c) in self.live.iter().zip(self.to_clear.iter()) {
+                l.store(0, Ordering::Relaxed);
+                c.store(0, Ordering::Relaxed);
+            }
+        }
+        self.ctrl.stats_ctrl_mut().set_snapshot(0);
+        self.ctrl.stats_ctrl_mut().set_clear(0);
+        Some(())
+    }
+
+    fn read(&self, addr: u64) -> Option<u64> {
+        let addr = addr as usize;
+        if addr == STATS_CTRL_OFFSET {
+            return self.ctrl.read(addr as u64);
+        }
+        let ctrl = self.ctrl.stats_ctrl();
+        let counters = if ctrl.snapshot_en() == 1 {
+            &self.snapshot
+        } else {
+            &self.live
+        };
+        let i = addr / STATS_COUNTER_SIZE;
+        let c = counters.get(i)?;
+        if addr % STATS_COUNTER_SIZE == 0 {
+            let v = c.load(Ordering::Relaxed);
+            self.latched[i].store(v, Ordering::Relaxed);
+            self.to_clear[i].store(v, Ordering::Relaxed);
+            Some(v & 0xffff_ffff)
+        } else {
+            //only the latched value is cleared, counts after the lo read are kept
+            if ctrl.clear_on_read() == 1 {
+                let n = self.to_clear[i].swap(0, Ordering::Relaxed);
+                c.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                    Some(v.saturating_sub(n))
+                })
+                .unwrap();
+            }
+            Some(self.latched[i].load(Ordering::Relaxed) >> 32)
+        }
+    }
+}
+
+impl<const CHS: usize> GenHeader for StatsRegs<CHS> {
+    fn render_name() -> &'static str {
+        "StatsRegs"
+    }
+    fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
+        StatsCtrlRegs::gen_c_header(header)?;
+        StatsCtrl::gen_c_header(header)?;
+
+        writeln!(
+            header,
+            "#define STATS_COUNTER_SIZE {:#x}",
+            STATS_COUNTER_SIZE
+        )?;
+        for (name, c) in COUNTERS {
+            writeln!(
+                header,
+                "#define STATS_{}_LO_OFFSET {:#x}",
+                name,
+                Self::counter_offset(c)
+            )?;
+            writeln!(
+                header,
+                "#define STATS_{}_HI_OFFSET {:#x}",
+                name,
+                Self::counter_offset(c) + 1
+            )?;
+        }
+        writeln!(header, "#define STATS_HIST_BUCKETS {}", STATS_HIST_BUCKETS)?;
+        writeln!(
+            header,
+            "#define STATS_RX_HIST_OFFSET {:#x}",
+            STATS_RX_HIST_OFFSET
+        )?;
+        writeln!(
+            header,
+            "#define STATS_TX_HIST_OFFSET {:#x}",
+            STATS_TX_HIST_OFFSET
+        )?;
+        writeln!(
+            header,
+            "#define STATS_QUEUE_OFFSET {:#x}",
+            STATS_QUEUE_OFFSET
+        )?;
+        writeln!(header, "#define STATS_QUEUE_SIZE {:#x}", STATS_QUEUE_SIZE)?;
+        for (name, c) in QUEUE_COUNTERS {
+            writeln!(
+                header,
+                "#define STATS_QUEUE_{}_LO_OFFSET {:#x}",
+                name,
+                Self::queue_counter_offset(0, c) - STATS_QUEUE_OFFSET
+            )?;
+            writeln!(
+                header,
+                "#define STATS_QUEUE_{}_HI_OFFSET {:#x}",
+                name,
+                Self::queue_counter_offset(0, c) - STATS_QUEUE_OFFSET + 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_regs_test() {
+        let stats = StatsRegs::<4>::new();
+        for len in [60, 64, 65, 1518, 1519, 9000] {
+            stats.rx_hist(len);
+        }
+        stats.tx_frame(1, 100);
+        stats.inc(StatsCounter::RxNoQueue);
+        let hist = |offset: usize| {
+            (0..STATS_HIST_BUCKETS)
+                .map(|i| stats.read(StatsRegs::<4>::hist_offset(offset, i) as u64).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(hist(STATS_RX_HIST_OFFSET), [2, 1, 0, 0, 0, 1, 2]);
+        assert_eq!(hist(STATS_TX_HIST_OFFSET), [0, 1, 0, 0, 0, 0, 0]);
+        let tx_bytes = StatsRegs::<4>::queue_counter_offset(1, QueueCounter::TxBytes) as u64;
+        assert_eq!(stats.read(tx_bytes), Some(100));
+        assert_eq!(stats.read(StatsRegs::<4>::SIZE as u64), None);
+
+        //snapshot is frozen while live counters keep going
+        stats.write(STATS_CTRL_OFFSET as u64, 0x6).unwrap();
+        assert_eq!(stats.ctrl.stats_ctrl().snapshot(), 0);
+        stats.tx_frame(1, 100);
+        assert_eq!(stats.read(tx_bytes), Some(100));
+        stats.write(STATS_CTRL_OFFSET as u64, 0x0).unwrap();
+        assert_eq!(stats.read(tx_bytes), Some(200));
+
+        //hi is latched by the lo read, counts in between survive clear on read
+        let no_queue = StatsRegs::<4>::counter_offset(StatsCounter::RxNoQueue) as u64;
+        stats.write(STATS_CTRL_OFFSET as u64, 0x1).unwrap();
+        assert_eq!(stats.read(no_queue), Some(1));
+        stats.inc(StatsCounter::RxNoQueue);
+        assert_eq!(stats.read(no_queue + 1), Some(0));
+        assert_eq!(stats.read(no_queue), Some(1));
+        assert_eq!(stats.read(no_queue + 1), Some(0));
+        assert_eq!(stats.read(no_queue), Some(0));
+
+        let rx_bytes = StatsRegs::<4>::queue_counter_offset(2, QueueCounter::RxBytes);
+        stats.add_queue(2, QueueCounter::RxBytes, 0x1_2345_6789);
+        assert_eq!(stats.read(rx_bytes as u64), Some(0x2345_6789));
+        stats.add_queue(2, QueueCounter::RxBytes, 0xffff_ffff);
+        assert_eq!(stats.read(rx_bytes as u64 + 1), Some(0x1));
+        assert_eq!(stats.read(rx_bytes as u64), Some(0xffff_ffff));
+        assert_eq!(stats.read(rx_bytes as u64 + 1), Some(0));
+
+        stats.write(STATS_CTRL_OFFSET as u64, 0x8).unwrap();
+        assert_eq!(stats.ctrl.stats_ctrl().clear(), 0);
+        assert_eq!(stats.read(tx_bytes), Some(0));
+        assert_eq!(hist(STATS_RX_HIST_OFFSET), [0; STATS_HIST_BUCKETS]);
+    }
+}
@@ -0,0 +1,123 @@
+use etha_model_generator::*;
+
+//mac ports are 0..ports, the host port is behind them
+pub const SWITCH_MAX_PORTS: usize = 4;
+pub const SWITCH_HOST_PORT: usize = SWITCH_MAX_PORTS;
+pub const FDB_SIZE: usize = 1024;
+
+define_reg! {
+    SwitchCtrl {
+        fields {
+            en(RW): 0, 0;
+            learn_en(RW): 1, 1;
+        }
+    }
+}
+
+define_reg! {
+    SwitchInfo {
+        fields {
+            ports(RW): 3, 0;
+        }
+    }
+}
+
+//aging time in seconds, 0 disables aging
+define_reg! {
+    FdbAging {
+        fields {
+            time(RW): 31, 0;
+        }
+    }
+}
+
+//mac address bytes 0-1 are in hi and 2-5 in lo, in network order
+define_reg! {
+    FdbMacLo {
+        fields {
+            mac(RW): 31, 0;
+        }
+    }
+}
+
+define_reg! {
+    FdbMacHi {
+        fields {
+            mac(RW): 15, 0;
+            vid(RW): 27, 16;
+        }
+    }
+}
+
+define_reg! {
+    FdbEntryCtrl {
+        fields {
+            port(RW): 2, 0;
+            is_static(RW): 8, 8;
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FdbCmdOp {
+    Nop = 0,
+    Add = 1,
+    Del = 2,
+    Query = 3,
+    Flush = 4,
+}
+
+impl std::convert::From<u64> for FdbCmdOp {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => FdbCmdOp::Add,
+            2 => FdbCmdOp::Del,
+            3 => FdbCmdOp::Query,
+            4 => FdbCmdOp::Flush,
+            _ => FdbCmdOp::Nop,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FdbCmdResult {
+    Ok = 0,
+    NotFound = 1,
+    Full = 2,
+}
+
+//flush only removes dynamic entries
+define_reg! {
+    FdbCmd {
+        fields {
+            op(RW){nop:0, add:1, del:2, query:3, flush:4}: 2, 0;
+        }
+    }
+}
+
+define_reg! {
+    FdbStatus {
+        fields {
+            result(RW){ok:0, not_found:1, full:2}: 3, 0;
+            count(RW): 31, 16;
+        }
+    }
+}
+
+pub const SWITCH_REGS_SIZE: usize = 8;
+pub const FDB_CMD_OFFSET: usize = 5;
+
+reg_map! {
+    pub SwitchRegs(8) {
+        switch_ctrl(RW): SwitchCtrl, 0;
+        fdb_aging(RW): FdbAging, 1;
+        fdb_mac_lo(RW): FdbMacLo, 2;
+        fdb_mac_hi(RW): FdbMacHi, 3;
+        fdb_entry(RW): FdbEntryCtrl, 4;
+        fdb_cmd(RW): FdbCmd, 5;
+        fdb_status(RO): FdbStatus, 6;
+        switch_info(RO): SwitchInfo, 7;
+    }
+}
@@ -0,0 +1,108 @@
+use crate::reg_if::RegBus;
+use etha_model_generator::*;
+
+define_reg! {
+    TxShaperRate {
+        fields {
+            rate(RW): 23, 0;
+            en(RW): 31, 31;
+        }
+    }
+}
+
+define_reg! {
+    TxShaperBurst {
+        fields {
+            burst(RW): 23, 0;
+        }
+    }
+}
+
+//rate in Mbps, burst in bytes
+reg_map! {
+    pub TxShaperRegs(2) {
+        shaper_rate(RW): TxShaperRate, 0;
+        shaper_burst(RW): TxShaperBurst, 1;
+    }
+}
+
+pub const TX_SHAPER_REGS_SIZE: usize = 2;
+
+pub struct TxRegs<const CHS: usize> {
+    pub shapers: [LockedTxShaperRegs; CHS],
+    pub port_shaper: LockedTxShaperRegs,
+}
+
+impl<const CHS: usize> TxRegs<CHS> {
+    pub fn new() -> Self {
+        TxRegs {
+            shapers: array_init::array_init(|_| LockedTxShaperRegs::new(32)),
+            port_shaper: LockedTxShaperRegs::new(32),
+        }
+    }
+    const SHAPER_RANGE: std::ops::Range<u64> = 0..((CHS * TX_SHAPER_REGS_SIZE) as u64);
+    const PORT_SHAPER_RANGE: std::ops::Range<u64> =
+        Self::SHAPER_RANGE.end..(Self::SHAPER_RANGE.end + TX_SHAPER_REGS_SIZE as u64);
+    pub const fn shaper_offset(i: usize) -> usize {
+        Self::SHAPER_RANGE.start as usize + i * TX_SHAPER_REGS_SIZE
+    }
+    pub const fn port_shaper_offset() -> usize {
+        Self::PORT_SHAPER_RANGE.start as usize
+    }
+}
+
+impl<const CHS: usize> Default for TxRegs<CHS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CHS: usize> RegBus for TxRegs<CHS> {
+    fn write(&self, addr: u64, data: u64) -> Option<()> {
+        if Self::SHAPER_RANGE.contains(&addr) {
+            let offset = (addr - Self::SHAPER_RANGE.start) as usize;
+            self.shapers[offset / TX_SHAPER_REGS_SIZE]
+                .write((offset % TX_SHAPER_REGS_SIZE) as u64, data)
+        } else if Self::PORT_SHAPER_RANGE.contains(&addr) {
+            self.port_shaper
+                .write(addr - Self::PORT_SHAPER_RANGE.start, data)
+        } else {
+            None
+        }
+    }
+
+    fn read(&self, addr: u64) -> Option<u64> {
+        if Self::SHAPER_RANGE.contains(&addr) {
+            let offset = (addr - Self::SHAPER_RANGE.start) as usize;
+            self.shapers[offset / TX_SHAPER_REGS_SIZE].read((offset % TX_SHAPER_REGS_SIZE) as u64)
+        } else if Self::PORT_SHAPER_RANGE.contains(&addr) {
+            self.port_shaper.read(addr - Self::PORT_SHAPER_RANGE.start)
+        } else {
+            None
+        }
+    }
+}
+
+impl<const CHS: usize> GenHeader for TxRegs<CHS> {
+    fn render_name() -> &'static str {
+        "TxRegs"
+    }
+    fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
+        TxShaperRegs::gen_c_header(header)?;
+        TxShaperRate::gen_c_header(header)?;
+        TxShaperBurst::gen_c_header(header)?;
+
+        writeln!(
+            header,
+            "#define TX_SHAPER_OFFSET(base, name, i) ((base) + {:#x} + (i) * {} + TX_SHAPER_REGS_##name##_OFFSET)",
+            Self::SHAPER_RANGE.start,
+            TX_SHAPER_REGS_SIZE
+        )?;
+        writeln!(
+            header,
+            "#define TX_PORT_SHAPER_OFFSET(base, name) ((base) + {:#x} + TX_SHAPER_REGS_##name##_OFFSET)",
+            Self::PORT_SHAPER_RANGE.start
+        )?;
+        Ok(())
+    }
+}
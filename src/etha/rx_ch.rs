@@ -1,102 +1,344 @@
 use super::desc::buffer::*;
 use super::desc::rx::*;
+use super::l4_parser::{TCP_FLAG_ACK, TCP_FLAG_PSH};
 use super::parser::ParserInfo;
+use super::reg_if::{rx::LockedRxQueueRegs, ChRegs};
 use super::STATICS_TAR;
+use crate::clock::ModelClock;
 use crate::desc::*;
 use crate::irq::*;
 use crate::logger;
 use crate::reg_if::ring::*;
+use smoltcp::wire::{IpAddress, IpProtocol, Ipv4Packet, Ipv6Packet};
+use std::cell::Cell;
 use std::io::Write;
 use std::ops::Deref;
 use std::sync::Arc;
+
+//an open aggregation holds its entries until it is flushed,
+//the header is the one of the first segment, rewritten on flush
+struct LroCtx {
+    info: ParserInfo,
+    hdr: RxHdrSplitDesc,
+    //headers up to the end of l4 of the first segment
+    hdr_data: Vec<u8>,
+    //ack, data offset with flags and window of the last segment
+    tcp_tail: [u8; 8],
+    //bytes in the header buffer and in the data buffers
+    hdr_len: usize,
+    len: usize,
+    next_seq: u32,
+    segs: usize,
+    first: u64,
+}
+
 pub struct EthaRxCh {
     id: usize,
     irq_num: usize,
     ring: Arc<LockedRingRegs>,
+    cfg: Arc<LockedRxQueueRegs>,
+    coal: IrqCoalescer,
+    clock: Arc<ModelClock>,
+    lro: Cell<Option<LroCtx>>,
 }
 
 impl EthaRxCh {
-    pub fn new(id: usize, ring: &Arc<LockedRingRegs>, irqs: &mut IrqVec) -> Self {
+    pub fn new(id: usize, regs: &ChRegs, clock: &Arc<ModelClock>, irqs: &mut IrqVec) -> Self {
         EthaRxCh {
             id,
             irq_num: irqs.alloc(&format!("EthaRxChIrq{}", id)),
-            ring: ring.clone(),
+            ring: regs.rx.clone(),
+            cfg: regs.rx_queue.clone(),
+            coal: IrqCoalescer::new(),
+            clock: clock.clone(),
+            lro: Cell::new(None),
         }
     }
     fn mem_size_avail(&self) -> usize {
         self.mem_size().size() as usize * self.r_c_valids()
     }
-    pub fn write(&self, info: ParserInfo, data: &[u8]) -> Option<()> {
-        if self.mem_size_avail() >= data.len() {
-            let span = tracing::span!(
-                target: STATICS_TAR,
-                logger::STATICS_LEVEL,
-                "rx recive packet",
-                id = self.id
-            );
-            let _enter = span.enter();
-            let mut blocks = 0;
-            for (i, (req, resp)) in self.entries().enumerate() {
-                tracing::event!(
-                    target: STATICS_TAR,
-                    logger::STATICS_LEVEL,
-                    name = "rx read desc req",
-                    size = <Self as HwRing>::REQ_SIZE
-                );
-                let addr = unsafe { *req };
-                let desc =
-                    unsafe { &mut *resp.expect(&format!("rx[{}]: resp is disabled!", self.id)) };
-                let pos = self.mem_size().size() as usize * i;
-                let (len, end) = if data.len() - pos > self.mem_size().size() as usize {
-                    (self.mem_size().size() as usize, false)
-                } else {
-                    (data.len() - pos, true)
-                };
-                let start = i == 0;
-                let mut b = MemBlock { addr, size: len };
-                tracing::event!(
-                    target: STATICS_TAR,
-                    logger::STATICS_LEVEL,
-                    name = "rx write data",
-                    addr = b.addr,
-                    size = b.size
-                );
-                b.write(&data[pos..pos + len])
-                    .expect(&format!("rx[{}]: write error!", self.id));
-                desc.frame = FrameDesc::from(b);
-                desc.frame.set_start(start as u32);
-                desc.frame.set_end(end as u32);
-                if end {
-                    blocks = i;
-                    break;
-                }
-            }
-            let head = unsafe {
-                &mut *self
-                    .r_get_resp_at(self.r_c_ptr())
-                    .expect(&format!("rx[{}]: resp is disabled!", self.id))
-            };
-            head.frame.set_n_blocks(blocks as u32);
-            head.frame.set_total_size(data.len() as u32);
-            self.set_info(head, info);
+    //headers up to the end of l4, frames without l4 are not split
+    fn hdr_len(&self, info: &ParserInfo, len: usize) -> Option<usize> {
+        let cfg = self.cfg.hdr_split();
+        let hdr_len = info.l2.header_len + info.l3.header_len + info.l4.header_len;
+        if cfg.en() == 1
+            && info.l3.header_len != 0
+            && info.l4.header_len != 0
+            && hdr_len <= cfg.hdr_size() as usize
+            && hdr_len <= len
+        {
+            Some(hdr_len)
+        } else {
+            None
+        }
+    }
+    //header buffers are indexed by the head entry of the frame
+    fn write_hdr(&self, hdr: Option<&[u8]>) -> RxHdrSplitDesc {
+        let mut desc = RxHdrSplitDesc::default();
+        if self.cfg.hdr_split().en() == 0 {
+            return desc;
+        }
+        let Some(hdr) = hdr else {
+            desc.set_hdr_fallback(1);
+            return desc;
+        };
+        let idx = LockedRingRegs::r_ptr_l(self.r_c_ptr());
+        let addr = self.cfg.hdr_base() + (idx * self.cfg.hdr_split().hdr_size() as usize) as u64;
+        let mut b = MemBlock {
+            addr,
+            size: hdr.len(),
+        };
+        tracing::event!(
+            target: STATICS_TAR,
+            logger::STATICS_LEVEL,
+            name = "rx write header",
+            addr = b.addr,
+            size = b.size
+        );
+        b.write(hdr)
+            .expect(&format!("rx[{}]: write header error!", self.id));
+        desc.set_hdr_addr_lo(addr as DescEntryT);
+        desc.set_hdr_addr_hi((addr >> 32) as DescEntryT);
+        desc.set_hdr_len(hdr.len() as DescEntryT);
+        desc.set_hdr_split(1);
+        desc
+    }
+    //writes data at offset pos of the buffer chain from the head entry
+    fn write_blocks(&self, pos: usize, data: &[u8]) {
+        let size = self.mem_size().size() as usize;
+        let end = pos + data.len();
+        let first = pos / size;
+        let last = end.saturating_sub(1) / size;
+        for (i, (req, resp)) in self.entries().enumerate().take(last + 1).skip(first) {
             tracing::event!(
                 target: STATICS_TAR,
                 logger::STATICS_LEVEL,
-                name = "rx write desc resp",
-                size = <Self as HwRing>::RESP_SIZE
+                name = "rx read desc req",
+                size = <Self as HwRing>::REQ_SIZE
             );
-            self.r_advance_c_n(blocks + 1);
+            let addr = unsafe { *req };
+            let desc = unsafe { &mut *resp.expect(&format!("rx[{}]: resp is disabled!", self.id)) };
+            let block = size * i;
+            let start = std::cmp::max(pos, block);
+            let stop = std::cmp::min(end, block + size);
+            let mut b = MemBlock {
+                addr: addr + (start - block) as u64,
+                size: stop - start,
+            };
             tracing::event!(
                 target: STATICS_TAR,
                 logger::STATICS_LEVEL,
-                name = "rx receive done",
-                size = data.len()
+                name = "rx write data",
+                addr = b.addr,
+                size = b.size
             );
-            Some(())
+            b.write(&data[start - pos..stop - pos])
+                .expect(&format!("rx[{}]: write error!", self.id));
+            desc.frame = FrameDesc::from(MemBlock {
+                addr,
+                size: stop - block,
+            });
+        }
+    }
+    //overwrites the start of the frame in place, the entries keep their sizes
+    fn patch_blocks(&self, data: &[u8]) {
+        let size = self.mem_size().size() as usize;
+        for (i, (req, _)) in self.entries().enumerate().take(data.len().div_ceil(size)) {
+            let chunk = &data[i * size..std::cmp::min(data.len(), (i + 1) * size)];
+            let mut b = MemBlock {
+                addr: unsafe { *req },
+                size: chunk.len(),
+            };
+            b.write(chunk)
+                .expect(&format!("rx[{}]: write error!", self.id));
+        }
+    }
+    //len bytes in the data buffers, total is the size of the frame
+    fn complete(
+        &self,
+        info: ParserInfo,
+        hdr: RxHdrSplitDesc,
+        len: usize,
+        total: usize,
+        segs: usize,
+    ) {
+        let blocks = len.saturating_sub(1) / self.mem_size().size() as usize + 1;
+        for (i, (_, resp)) in self.entries().enumerate().take(blocks) {
+            let desc = unsafe { &mut *resp.expect(&format!("rx[{}]: resp is disabled!", self.id)) };
+            desc.frame.set_start((i == 0) as u32);
+            desc.frame.set_end((i == blocks - 1) as u32);
+        }
+        let head = unsafe {
+            &mut *self
+                .r_get_resp_at(self.r_c_ptr())
+                .expect(&format!("rx[{}]: resp is disabled!", self.id))
+        };
+        head.frame.set_n_blocks(blocks as u32 - 1);
+        head.frame.set_total_size(total as u32);
+        self.set_info(head, info);
+        head.hdr = hdr;
+        head.l4.set_l4_lro_segs(segs as u32);
+        tracing::event!(
+            target: STATICS_TAR,
+            logger::STATICS_LEVEL,
+            name = "rx write desc resp",
+            size = <Self as HwRing>::RESP_SIZE
+        );
+        self.r_advance_c_n(blocks);
+        self.coal.event(self.clock.now());
+        tracing::event!(
+            target: STATICS_TAR,
+            logger::STATICS_LEVEL,
+            name = "rx receive done",
+            size = total
+        );
+    }
+    //plain acks with payload and without padding, checksum errors are never merged
+    fn lro_hdr_len(&self, info: &ParserInfo, len: usize) -> Option<usize> {
+        let hdr_len = info.l2.header_len + info.l3.header_len + info.l4.header_len;
+        let flags = info.l4.tcp_flags & !TCP_FLAG_PSH;
+        if self.cfg.lro().en() == 1
+            && info.l3.protocol == IpProtocol::Tcp
+            && info.l3.frag.is_none()
+            && info.tunnel.is_none()
+            && info.l3.csum != Some(false)
+            && info.l4.csum != Some(false)
+            && flags == TCP_FLAG_ACK
+            && info.l3.header_len + info.l3.payload_len + info.l2.header_len == len
+            && hdr_len < len
+        {
+            Some(hdr_len)
         } else {
             None
         }
     }
+    fn lro_match(&self, ctx: &LroCtx, info: &ParserInfo, frame: &[u8]) -> bool {
+        let Some(hdr_len) = self.lro_hdr_len(info, frame.len()) else {
+            return false;
+        };
+        let payload = frame.len() - hdr_len;
+        let (l2, l3, l4) = (&ctx.info.l2, &ctx.info.l3, &ctx.info.l4);
+        //the merged frame carries the l2 header, vlan tags included, of the first segment
+        l2.header_len == info.l2.header_len
+            && ctx.hdr_data[..l2.header_len] == frame[..l2.header_len]
+            && l3.header_len == info.l3.header_len
+            && l3.src == info.l3.src
+            && l3.dst == info.l3.dst
+            && l4.src == info.l4.src
+            && l4.dst == info.l4.dst
+            && l4.header_len == info.l4.header_len
+            && ctx.next_seq == info.l4.tcp_seq
+            && ctx.segs < RX_LRO_MAX_SEGS
+            && ctx.hdr_len + ctx.len + payload <= self.cfg.lro().max_size() as usize
+    }
+    fn lro_update(&self, ctx: LroCtx, push: bool) {
+        if push {
+            self.lro_flush(ctx);
+        } else {
+            self.lro.set(Some(ctx));
+        }
+    }
+    //the merged header carries the lengths of the aggregate and the ack, window and flags
+    //of the last segment, its l4 checksum is not valid anymore
+    fn lro_rewrite_hdr(&self, ctx: &mut LroCtx) {
+        let total = ctx.hdr_len + ctx.len;
+        let (l2_len, l3_len) = (ctx.info.l2.header_len, ctx.info.l3.header_len);
+        let (l3, l4) = ctx.hdr_data[l2_len..].split_at_mut(l3_len);
+        match ctx.info.l3.src {
+            IpAddress::Ipv4(_) => {
+                let mut ip = Ipv4Packet::new_unchecked(l3);
+                ip.set_total_len((total - l2_len) as u16);
+                ip.fill_checksum();
+            }
+            IpAddress::Ipv6(_) => {
+                Ipv6Packet::new_unchecked(l3).set_payload_len((total - l2_len - 40) as u16);
+            }
+        }
+        l4[8..16].copy_from_slice(&ctx.tcp_tail);
+        if ctx.hdr.hdr_split() == 1 {
+            let mut b = MemBlock {
+                addr: ctx.hdr.hdr_addr(),
+                size: ctx.hdr_data.len(),
+            };
+            b.write(&ctx.hdr_data)
+                .expect(&format!("rx[{}]: write header error!", self.id));
+        } else {
+            self.patch_blocks(&ctx.hdr_data);
+        }
+        ctx.info.l4.csum = None;
+    }
+    fn lro_flush(&self, mut ctx: LroCtx) {
+        if ctx.segs > 1 {
+            self.lro_rewrite_hdr(&mut ctx);
+        }
+        self.complete(ctx.info, ctx.hdr, ctx.len, ctx.hdr_len + ctx.len, ctx.segs);
+    }
+    fn lro_tcp_tail(info: &ParserInfo, frame: &[u8]) -> [u8; 8] {
+        let l4 = info.l2.header_len + info.l3.header_len;
+        frame[l4 + 8..l4 + 16].try_into().unwrap()
+    }
+    //the segment matches ctx, it is not written if the ring is full
+    fn lro_merge(&self, ctx: LroCtx, info: &ParserInfo, frame: &[u8]) -> Option<()> {
+        let data = &frame[info.l2.header_len + info.l3.header_len + info.l4.header_len..];
+        if self.mem_size_avail() < ctx.len + data.len() {
+            self.lro_flush(ctx);
+            return None;
+        }
+        self.write_blocks(ctx.len, data);
+        let mut ctx = LroCtx {
+            tcp_tail: Self::lro_tcp_tail(info, frame),
+            len: ctx.len + data.len(),
+            next_seq: info.l4.tcp_seq.wrapping_add(data.len() as u32),
+            segs: ctx.segs + 1,
+            ..ctx
+        };
+        ctx.info.l4.tcp_flags = info.l4.tcp_flags;
+        self.lro_update(ctx, info.l4.tcp_flags & TCP_FLAG_PSH != 0);
+        Some(())
+    }
+    pub fn write(&self, info: ParserInfo, frame: &[u8]) -> Option<()> {
+        let span = tracing::span!(
+            target: STATICS_TAR,
+            logger::STATICS_LEVEL,
+            "rx recive packet",
+            id = self.id
+        );
+        let _enter = span.enter();
+        if let Some(ctx) = self.lro.take() {
+            if self.lro_match(&ctx, &info, frame) {
+                return self.lro_merge(ctx, &info, frame);
+            }
+            self.lro_flush(ctx);
+        }
+        let hdr_len = self.hdr_len(&info, frame.len());
+        let (hdr, data) = frame.split_at(hdr_len.unwrap_or(0));
+        //the head entry is taken even if the whole frame is in the header buffer
+        if self.r_c_valids() == 0 || self.mem_size_avail() < data.len() {
+            return None;
+        }
+        self.write_blocks(0, data);
+        let hdr = self.write_hdr(hdr_len.map(|_| hdr));
+        if let Some(lro_hdr_len) = self.lro_hdr_len(&info, frame.len()) {
+            let ctx = LroCtx {
+                info,
+                hdr,
+                hdr_data: frame[..lro_hdr_len].to_vec(),
+                tcp_tail: Self::lro_tcp_tail(&info, frame),
+                hdr_len: hdr_len.unwrap_or(0),
+                len: data.len(),
+                next_seq: info
+                    .l4
+                    .tcp_seq
+                    .wrapping_add((frame.len() - lro_hdr_len) as u32),
+                segs: 1,
+                first: self.clock.now(),
+            };
+            self.lro_update(ctx, info.l4.tcp_flags & TCP_FLAG_PSH != 0);
+        } else {
+            self.complete(info, hdr, data.len(), frame.len(), 0);
+        }
+        Some(())
+    }
     fn set_info(&self, desc: &mut RxResultDesc, info: ParserInfo) {
         let l2_payload_len = desc.frame.total_size() as usize - info.l2.header_len;
         let l3_payload_len = if info.l3.header_len == 0 {
@@ -112,19 +354,43 @@ impl EthaRxCh {
         desc.l2 = info.l2.into();
         desc.l3 = info.l3.into();
         desc.l4 = info.l4.into();
+        desc.rss = info.rss.into();
+        desc.tunnel = info.tunnel.map(|t| t.into()).unwrap_or_default();
+        desc.ecpri = info.ecpri.map(|e| e.into()).unwrap_or_default();
         desc.l2.set_l2_payload_len(l2_payload_len as u32);
         desc.l3.set_l3_payload_len(l3_payload_len as u32);
         desc.l4.set_l4_payload_len(l4_payload_len as u32);
+        desc.status = RxStatusDesc::default();
+        desc.ts = info.ts.map(|t| t.into()).unwrap_or_default();
+        desc.status.set_ts_valid(info.ts.is_some() as u32);
+        if let Some(ok) = info.l3.csum {
+            desc.status.set_l3_csum_valid(1);
+            desc.status.set_l3_csum_err(!ok as u32);
+        }
+        if let Some(ok) = info.l4.csum {
+            desc.status.set_l4_csum_valid(1);
+            desc.status.set_l4_csum_err(!ok as u32);
+        }
     }
 }
 
-impl WithIrq for EthaRxCh {
-    fn poll_irq(&self) -> Option<usize> {
-        if self.r_irq_pendings() == 0 {
-            None
-        } else {
-            Some(self.irq_num)
+impl WithIrqCauses for EthaRxCh {
+    fn poll_causes(&self) -> (usize, u64) {
+        //open aggregations time out without traffic
+        if let Some(ctx) = self.lro.take() {
+            let elapsed = self.clock.now().saturating_sub(ctx.first);
+            if elapsed >= self.cfg.lro_timeout().usecs() * 1000 {
+                self.lro_flush(ctx);
+            } else {
+                self.lro.set(Some(ctx));
+            }
         }
+        let done = self.coal.expire(
+            self.int_coal().max_frames() as usize,
+            self.int_coal().usecs(),
+            self.clock.now(),
+        );
+        (self.irq_num, self.r_int_latch(done))
     }
 }
 
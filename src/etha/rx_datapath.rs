@@ -2,11 +2,14 @@ use super::parser::*;
 use super::reg_if::TopRegs;
 use super::rx_dispatcher::*;
 use super::rx_filter::*;
+use super::rx_mac_filter::*;
 use super::*;
 use crate::irq::*;
 use std::sync::{Arc, Mutex};
 pub struct EthaRxDataPath {
+    pub parser: EthaRxParser,
     pub dispather: EthaRxDispatcher,
+    pub mac_filter: EthaRxMacFilter,
     pub filter: EthaRxFilter,
 }
 impl EthaRxDataPath {
@@ -15,14 +18,20 @@ impl EthaRxDataPath {
         irqs: &Arc<Mutex<IrqVec>>,
     ) -> Self {
         EthaRxDataPath {
+            parser: EthaRxParser::new(&regs.rx),
             dispather: EthaRxDispatcher::new(regs, irqs),
-            filter: EthaRxFilter::new(&regs.rx.filters),
+            mac_filter: EthaRxMacFilter::new(regs),
+            filter: EthaRxFilter::new(&regs.rx, &regs.clock),
         }
     }
     pub fn pipeline<'a>(&'a self) -> impl Pipeline<Input = (), Output = ()> + 'a {
-        EthaRxParser
+        self.parser
             .pipeline()
+            .comb(self.mac_filter.pipeline())
             .comb(self.filter.pipeline())
             .comb(self.dispather.pipeline())
     }
+    pub fn irqs<'a>(&'a self) -> impl Pipeline<Input = (), Output = ()> + 'a {
+        self.dispather.irqs()
+    }
 }
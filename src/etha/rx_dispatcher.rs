@@ -1,16 +1,28 @@
+use super::irq_table::IrqTable;
 use super::parser::ParserInfo;
+use super::ptp::{is_ptp, Phc};
+use super::reg_if::global::{LockedEthaGlobalRegs, RxTsMode};
 use super::reg_if::rx::*;
+use super::reg_if::stats::{QueueCounter, StatsCounter, StatsRegs};
 use super::reg_if::TopRegs;
 use super::rx_ch::EthaRxCh;
 use super::STATICS_TAR;
 use super::*;
 use crate::irq::*;
 use crate::logger;
+use smoltcp::wire::ETHERNET_HEADER_LEN;
+use std::cell::Cell;
 use std::sync::{Arc, Mutex};
 pub struct EthaRxDispatcher {
     irqs: Arc<Mutex<IrqVec>>,
+    irq_table: Arc<IrqTable<CHS>>,
     chs: [EthaRxCh; CHS],
     default_q: Arc<LockedDefaultFilterRegs>,
+    global: Arc<LockedEthaGlobalRegs>,
+    stats: Arc<StatsRegs<CHS>>,
+    phc: Arc<Phc>,
+    //blocked frames are only counted once
+    blocked: [Cell<bool>; CHS],
 }
 
 impl EthaRxDispatcher {
@@ -19,12 +31,17 @@ impl EthaRxDispatcher {
         irqs: &Arc<Mutex<IrqVec>>,
     ) -> Self {
         let chs = array_init::array_init(|i| {
-            EthaRxCh::new(i, &regs.chs[i].rx, &mut irqs.lock().unwrap())
+            EthaRxCh::new(i, &regs.chs[i], &regs.clock, &mut irqs.lock().unwrap())
         });
         EthaRxDispatcher {
             irqs: irqs.clone(),
+            irq_table: regs.irq.clone(),
             chs,
             default_q: regs.rx.default_q.clone(),
+            global: regs.global.clone(),
+            stats: regs.stats.clone(),
+            phc: regs.phc.clone(),
+            blocked: array_init::array_init(|_| Cell::new(false)),
         }
     }
 
@@ -35,17 +52,48 @@ impl EthaRxDispatcher {
         EthaRxDispatcherPipe {
             chs: &self.chs,
             default_q: &self.default_q,
+            global: &self.global,
+            stats: &self.stats,
+            phc: &self.phc,
+            blocked: &self.blocked,
         }
-        .comb(EthaIrqs::new(&self.chs, &self.irqs))
+    }
+    //polled every cycle, coalescing timers expire without traffic
+    pub fn irqs<'a>(&'a self) -> impl Pipeline<Input = (), Output = ()> + 'a {
+        EthaIrqs::new(&self.chs, &self.irq_table, &self.irqs)
     }
 }
 
 pub struct EthaRxDispatcherPipe<'a> {
     chs: &'a [EthaRxCh],
     default_q: &'a Arc<LockedDefaultFilterRegs>,
+    global: &'a Arc<LockedEthaGlobalRegs>,
+    stats: &'a StatsRegs<CHS>,
+    phc: &'a Phc,
+    blocked: &'a [Cell<bool>],
 }
 
 impl<'a> EthaRxDispatcherPipe<'a> {
+    fn write(&self, id: usize, info: ParserInfo, data: &[u8]) -> bool {
+        let written = self.chs[id].write(info, data).is_some();
+        if written {
+            self.stats.rx_frame(id, data.len());
+            self.blocked[id].set(false);
+        }
+        written
+    }
+
+    fn congestion(&self, id: usize, action: CongestionAction) {
+        let counter = match action {
+            CongestionAction::Blocking if self.blocked[id].replace(true) => return,
+            CongestionAction::Blocking => QueueCounter::CongestionBlocking,
+            CongestionAction::Drop => QueueCounter::CongestionDrop,
+            CongestionAction::Default => QueueCounter::CongestionDefault,
+            _ => return,
+        };
+        self.stats.add_queue(id, counter, 1);
+    }
+
     fn get_default_q(&self) -> Option<(usize, CongestionAction)> {
         if self.default_q.default_q().en() == 1 {
             let id = self.default_q.default_q().queue_id() as usize;
@@ -61,12 +109,17 @@ impl<'a> EthaRxDispatcherPipe<'a> {
 
     fn send_to_default_q(&self, info: ParserInfo, data: &[u8]) -> Result<()> {
         if let Some((id, action)) = self.get_default_q() {
-            self.chs[id].write(info, data).ok_or(match action {
+            if self.write(id, info, data) {
+                return Ok(());
+            }
+            self.congestion(id, action);
+            Err(match action {
                 CongestionAction::Drop => Error::Dropped,
                 CongestionAction::Blocking => Error::Blocking,
                 _ => unreachable!("Invalid default queue congestion action!"),
             })
         } else {
+            self.stats.inc(StatsCounter::RxNoQueue);
             Err(Error::Dropped)
         }
     }
@@ -76,7 +129,30 @@ impl<'a> Pipeline for EthaRxDispatcherPipe<'a> {
     type Input = (ParserInfo, Option<(usize, CongestionAction)>);
     type Output = ();
     fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
-        let (info, filter_result) = *i;
+        let (mut info, filter_result) = *i;
+        info.ts = match self.global.rx_ts_mode() {
+            RxTsMode::All => Some(self.phc.time()),
+            RxTsMode::Ptp if is_ptp(&info) => Some(self.phc.time()),
+            _ => None,
+        };
+        //stripped tags are only reported in the result desc
+        let stripped;
+        let buffer: &[u8] =
+            if self.global.rx_vlan_strip().en() == 1 && info.l2.header_len > ETHERNET_HEADER_LEN {
+                stripped = [
+                    &buffer[..ETHERNET_HEADER_LEN - 2],
+                    &buffer[info.l2.header_len - 2..],
+                ]
+                .concat();
+                if let Some(tunnel) = &mut info.tunnel {
+                    tunnel.offset -= info.l2.header_len - ETHERNET_HEADER_LEN;
+                }
+                info.l2.header_len = ETHERNET_HEADER_LEN;
+                info.l2.vlan_stripped = true;
+                &stripped
+            } else {
+                buffer
+            };
         let span = tracing::span!(
             target: STATICS_TAR,
             logger::STATICS_LEVEL,
@@ -86,7 +162,8 @@ impl<'a> Pipeline for EthaRxDispatcherPipe<'a> {
         tracing::debug!(target : "rx_dspatcher", "fitler result: {:?}", filter_result);
         let r = if let Some((id, action)) = filter_result {
             if id < CHS {
-                if self.chs[id].write(info, buffer).is_none() {
+                if !self.write(id, info, buffer) {
+                    self.congestion(id, action);
                     match action {
                         CongestionAction::Drop => Err(Error::Dropped),
                         CongestionAction::Blocking => Err(Error::Blocking),
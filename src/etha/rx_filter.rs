@@ -1,18 +1,28 @@
+use super::desc::rx::RxRssDesc;
 use super::l3_parser::L3Info;
 use super::l4_parser::L4Info;
 use super::parser::ParserInfo;
 use super::reg_if::rx::*;
 use super::*;
+use crate::clock::ModelClock;
 use smoltcp::wire::*;
 use std::sync::Arc;
 
 pub struct EthaRxFilter {
     regs: Arc<FilterRegs<RX_ET_FILTERS, RX_TP5_FILTERS>>,
+    rss: Arc<RssRegs>,
+    flow_table: Arc<FlowTable>,
+    clock: Arc<ModelClock>,
 }
 
 impl EthaRxFilter {
-    pub fn new(regs: &Arc<FilterRegs<RX_ET_FILTERS, RX_TP5_FILTERS>>) -> Self {
-        EthaRxFilter { regs: regs.clone() }
+    pub fn new(regs: &RxRegs<RX_ET_FILTERS, RX_TP5_FILTERS>, clock: &Arc<ModelClock>) -> Self {
+        EthaRxFilter {
+            regs: regs.filters.clone(),
+            rss: regs.rss.clone(),
+            flow_table: regs.flow_table.clone(),
+            clock: clock.clone(),
+        }
     }
     pub fn pipeline<'a>(
         &'a self,
@@ -21,9 +31,20 @@ impl EthaRxFilter {
         EtFilter {
             cfg: &self.regs.et_filters,
         }
+        .comb(VlanFilter {
+            cfg: &self.regs.vlan_filters,
+        })
+        .comb(EcpriFilter {
+            cfg: &self.regs.ecpri_filters,
+        })
+        .comb(FlowFilter {
+            table: &self.flow_table,
+            clock: &self.clock,
+        })
         .comb(Tp5Filter {
             cfg: &self.regs.tp5_filters,
         })
+        .comb(RssFilter { cfg: &self.rss })
     }
 }
 
@@ -61,6 +82,124 @@ impl<'a> Pipeline for EtFilter<'a> {
     }
 }
 
+pub struct VlanFilter<'a> {
+    cfg: &'a [LockedVlanFilterRegs],
+}
+impl<'a> VlanFilter<'a> {
+    fn filter(
+        &self,
+        filter: &LockedVlanFilterRegs,
+        info: &ParserInfo,
+    ) -> Option<(usize, CongestionAction)> {
+        let f = filter.vlan_filter();
+        let tag = if f.svlan() == 1 {
+            info.l2.svlan
+        } else {
+            info.l2.vlan
+        }?;
+        if f.en() == 1
+            && (f.vid_mask() == 1 || f.vid() as u16 == tag.vid)
+            && (f.pcp_mask() == 1 || f.pcp() as u8 == tag.pcp())
+        {
+            Some((f.queue_id() as usize, f.get_congestion_action()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Pipeline for VlanFilter<'a> {
+    type Input = (ParserInfo, Option<(usize, CongestionAction)>);
+    type Output = (ParserInfo, Option<(usize, CongestionAction)>);
+    fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let (info, i) = i;
+        Ok((
+            *info,
+            i.or_else(|| self.cfg.iter().find_map(|f| self.filter(f, info))),
+        ))
+    }
+}
+
+pub struct EcpriFilter<'a> {
+    cfg: &'a [LockedEcpriFilterRegs],
+}
+impl<'a> EcpriFilter<'a> {
+    fn filter(
+        &self,
+        filter: &LockedEcpriFilterRegs,
+        info: &ParserInfo,
+    ) -> Option<(usize, CongestionAction)> {
+        let ecpri = info.ecpri?;
+        let ctrl = filter.ecpri_filter_ctrl();
+        let range = filter.ecpri_filter_range();
+        if ctrl.en() == 1
+            && (ctrl.msg_type_mask() == 1 || ctrl.msg_type() as u8 == ecpri.msg_type)
+            && (range.id_min() as u16..=range.id_max() as u16).contains(&ecpri.id)
+        {
+            Some((ctrl.queue_id() as usize, ctrl.get_congestion_action()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Pipeline for EcpriFilter<'a> {
+    type Input = (ParserInfo, Option<(usize, CongestionAction)>);
+    type Output = (ParserInfo, Option<(usize, CongestionAction)>);
+    fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let (info, i) = i;
+        Ok((
+            *info,
+            i.or_else(|| self.cfg.iter().find_map(|f| self.filter(f, info))),
+        ))
+    }
+}
+
+//the flow key of a parsed frame, none for non ip frames
+fn flow_key(info: &ParserInfo) -> Option<FlowKey> {
+    let ipv6 = match info.l2.etype {
+        EthernetProtocol::Ipv4 => false,
+        EthernetProtocol::Ipv6 => true,
+        _ => return None,
+    };
+    if info.l3.header_len == 0 {
+        return None;
+    }
+    let mut key = FlowKey {
+        protocol: u8::from(info.l3.protocol),
+        ipv6,
+        ..Default::default()
+    };
+    let (src, dst) = (info.l3.src.as_bytes(), info.l3.dst.as_bytes());
+    key.src[..src.len()].copy_from_slice(src);
+    key.dst[..dst.len()].copy_from_slice(dst);
+    //ports are only part of the key for tcp and udp
+    if let IpProtocol::Tcp | IpProtocol::Udp = info.l3.protocol {
+        key.src_port = info.l4.src;
+        key.dst_port = info.l4.dst;
+    }
+    Some(key)
+}
+
+pub struct FlowFilter<'a> {
+    table: &'a FlowTable,
+    clock: &'a ModelClock,
+}
+
+impl<'a> Pipeline for FlowFilter<'a> {
+    type Input = (ParserInfo, Option<(usize, CongestionAction)>);
+    type Output = (ParserInfo, Option<(usize, CongestionAction)>);
+    fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let (info, i) = i;
+        Ok((
+            *info,
+            i.or_else(|| {
+                flow_key(info).and_then(|key| self.table.lookup(&key, self.clock.now()))
+            }),
+        ))
+    }
+}
+
 pub struct Tp5Filter<'a> {
     cfg: &'a [LockedTuplesFilterRegs],
 }
@@ -71,12 +210,18 @@ impl<'a> Tp5Filter<'a> {
         info: &ParserInfo,
     ) -> Option<(usize, usize, CongestionAction)> {
         if filter.tp5_ctrl().en() == 1 {
-            match info.l2.etype {
+            let (etype, l3, l4) = if filter.tp5_ctrl().inner() == 1 {
+                let tunnel = info.tunnel.as_ref()?;
+                (tunnel.etype, &tunnel.l3, &tunnel.l4)
+            } else {
+                (info.l2.etype, &info.l3, &info.l4)
+            };
+            match etype {
                 EthernetProtocol::Ipv4 if filter.tp5_ctrl().ipv6() == 0 => {
-                    self.ipv4_filter(filter, &info.l3, &info.l4)
+                    self.ipv4_filter(filter, l3, l4)
                 }
                 EthernetProtocol::Ipv6 if filter.tp5_ctrl().ipv6() == 1 => {
-                    self.ipv6_filter(filter, &info.l3, &info.l4)
+                    self.ipv6_filter(filter, l3, l4)
                 }
                 _ => None,
             }
@@ -91,33 +236,19 @@ impl<'a> Tp5Filter<'a> {
         l3: &L3Info,
         l4: &L4Info,
     ) -> Option<(usize, usize, CongestionAction)> {
-        if self.ipv4_addr_match(
-            Ipv4Address::from_bytes(l3.src.as_bytes()),
-            filter.tp5_src().get() as u32,
+        let prefix = filter.tp5_prefix();
+        if self.addr_match(
+            l3.src.as_bytes(),
+            &(filter.tp5_src().get() as u32).to_le_bytes(),
             filter.tp5_ctrl().src_mask() != 0,
-        ) && self.ipv4_addr_match(
-            Ipv4Address::from_bytes(l3.dst.as_bytes()),
-            filter.tp5_dst().get() as u32,
+            prefix.src_prefix() as usize,
+        ) && self.addr_match(
+            l3.dst.as_bytes(),
+            &(filter.tp5_dst().get() as u32).to_le_bytes(),
             filter.tp5_ctrl().dst_mask() != 0,
-        ) && self.protocal_match(
-            l3.protocol,
-            filter.tp5_ctrl().protocol() as u8,
-            filter.tp5_ctrl().protocol_mask() != 0,
-        ) && self.port_match(
-            l3.protocol,
-            l4.src,
-            filter.tp5_port().src() as u16,
-            filter.tp5_ctrl().src_port_mask() != 0,
-        ) && self.port_match(
-            l3.protocol,
-            l4.dst,
-            filter.tp5_port().dst() as u16,
-            filter.tp5_ctrl().dst_port_mask() != 0,
+            prefix.dst_prefix() as usize,
         ) {
-            let r_queue_id = filter.tp5_ctrl().queue_id() as usize;
-            let r_congestion_action = filter.tp5_ctrl().get_congestion_action();
-            let r_pri = filter.tp5_ctrl().pri() as usize;
-            Some((r_pri, r_queue_id, r_congestion_action))
+            self.l4_filter(filter, l3, l4)
         } else {
             None
         }
@@ -129,67 +260,123 @@ impl<'a> Tp5Filter<'a> {
         l3: &L3Info,
         l4: &L4Info,
     ) -> Option<(usize, usize, CongestionAction)> {
-        if self.ipv6_addr_match(
-            Ipv6Address::from_bytes(l3.src.as_bytes()),
-            &[
-                filter.tp5_src().get() as u32,
-                filter.tp5_v6_src_1().get() as u32,
-                filter.tp5_v6_src_2().get() as u32,
-                filter.tp5_v6_src_3().get() as u32,
-            ],
+        let prefix = filter.tp5_prefix();
+        let src: Vec<u8> = [
+            filter.tp5_src().get() as u32,
+            filter.tp5_v6_src_1().get() as u32,
+            filter.tp5_v6_src_2().get() as u32,
+            filter.tp5_v6_src_3().get() as u32,
+        ]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+        let dst: Vec<u8> = [
+            filter.tp5_dst().get() as u32,
+            filter.tp5_v6_dst_1().get() as u32,
+            filter.tp5_v6_dst_2().get() as u32,
+            filter.tp5_v6_dst_3().get() as u32,
+        ]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+        if self.addr_match(
+            l3.src.as_bytes(),
+            &src,
             filter.tp5_ctrl().src_mask() != 0,
-        ) && self.ipv6_addr_match(
-            Ipv6Address::from_bytes(l3.dst.as_bytes()),
-            &[
-                filter.tp5_dst().get() as u32,
-                filter.tp5_v6_dst_1().get() as u32,
-                filter.tp5_v6_dst_2().get() as u32,
-                filter.tp5_v6_dst_3().get() as u32,
-            ],
+            prefix.src_prefix() as usize,
+        ) && self.addr_match(
+            l3.dst.as_bytes(),
+            &dst,
             filter.tp5_ctrl().dst_mask() != 0,
-        ) && self.protocal_match(
+            prefix.dst_prefix() as usize,
+        ) {
+            self.l4_filter(filter, l3, l4)
+        } else {
+            None
+        }
+    }
+
+    fn l4_filter(
+        &self,
+        filter: &LockedTuplesFilterRegs,
+        l3: &L3Info,
+        l4: &L4Info,
+    ) -> Option<(usize, usize, CongestionAction)> {
+        let ctrl = filter.tp5_ctrl();
+        let port = filter.tp5_port();
+        let port_max = filter.tp5_port_max();
+        let tcp_flags = filter.tp5_tcp_flags();
+        if self.protocal_match(
             l3.protocol,
-            filter.tp5_ctrl().protocol() as u8,
-            filter.tp5_ctrl().protocol_mask() != 0,
+            ctrl.protocol() as u8,
+            ctrl.protocol_mask() != 0,
         ) && self.port_match(
             l3.protocol,
             l4.src,
-            filter.tp5_port().src() as u16,
-            filter.tp5_ctrl().src_port_mask() != 0,
+            port.src() as u16..=port_max.src() as u16,
+            ctrl.src_port_range() != 0,
+            ctrl.src_port_mask() != 0,
         ) && self.port_match(
             l3.protocol,
             l4.dst,
-            filter.tp5_port().dst() as u16,
-            filter.tp5_ctrl().dst_port_mask() != 0,
+            port.dst() as u16..=port_max.dst() as u16,
+            ctrl.dst_port_range() != 0,
+            ctrl.dst_port_mask() != 0,
+        ) && self.tcp_flags_match(
+            l3.protocol,
+            l4.tcp_flags,
+            tcp_flags.flags() as u16,
+            tcp_flags.flags_mask() as u16,
+            ctrl.tcp_flags_en() != 0,
         ) {
-            let r_queue_id = filter.tp5_ctrl().queue_id() as usize;
-            let r_congestion_action = filter.tp5_ctrl().get_congestion_action();
-            let r_pri = filter.tp5_ctrl().pri() as usize;
+            let r_queue_id = ctrl.queue_id() as usize;
+            let r_congestion_action = ctrl.get_congestion_action();
+            let r_pri = ctrl.pri() as usize;
             Some((r_pri, r_queue_id, r_congestion_action))
         } else {
             None
         }
     }
 
-    fn ipv4_addr_match(&self, addr: Ipv4Address, filter: u32, mask: bool) -> bool {
-        mask || addr == Ipv4Address::from_bytes(&filter.to_le_bytes())
-    }
-
-    fn ipv6_addr_match(&self, addr: Ipv6Address, filter: &[u32; 4], mask: bool) -> bool {
-        let array: Vec<u8> = filter.iter().flat_map(|v| v.to_le_bytes()).collect();
-        mask || addr == Ipv6Address::from_bytes(&array)
+    fn addr_match(&self, addr: &[u8], filter: &[u8], mask: bool, prefix: usize) -> bool {
+        let bits = match prefix {
+            0 => addr.len() * 8,
+            _ => std::cmp::min(prefix, addr.len() * 8),
+        };
+        let (bytes, rem) = (bits / 8, bits % 8);
+        mask || (addr[..bytes] == filter[..bytes]
+            && (rem == 0 || (addr[bytes] ^ filter[bytes]) & (0xff << (8 - rem)) == 0))
     }
 
     fn protocal_match(&self, protocol: IpProtocol, filter: u8, mask: bool) -> bool {
         mask || u8::from(protocol) == filter
     }
 
-    fn port_match(&self, protocol: IpProtocol, port: u16, filter: u16, mask: bool) -> bool {
+    fn port_match(
+        &self,
+        protocol: IpProtocol,
+        port: u16,
+        filter: std::ops::RangeInclusive<u16>,
+        range: bool,
+        mask: bool,
+    ) -> bool {
         mask || match protocol {
-            IpProtocol::Tcp | IpProtocol::Udp => port == filter,
+            IpProtocol::Tcp | IpProtocol::Udp if range => filter.contains(&port),
+            IpProtocol::Tcp | IpProtocol::Udp => port == *filter.start(),
             _ => true,
         }
     }
+
+    fn tcp_flags_match(
+        &self,
+        protocol: IpProtocol,
+        flags: u16,
+        filter: u16,
+        mask: u16,
+        en: bool,
+    ) -> bool {
+        !en || (protocol == IpProtocol::Tcp && flags & mask == filter & mask)
+    }
 }
 
 impl<'a> Pipeline for Tp5Filter<'a> {
@@ -219,3 +406,336 @@ impl<'a> Pipeline for Tp5Filter<'a> {
         ))
     }
 }
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RssInfo {
+    pub hash: u32,
+    pub ty: RssHashType,
+}
+
+impl From<RssInfo> for RxRssDesc {
+    fn from(info: RssInfo) -> Self {
+        let mut desc = RxRssDesc::default();
+        desc.set_rss_hash(info.hash);
+        desc.set_rss_type(info.ty as u32);
+        desc
+    }
+}
+
+pub fn toeplitz_hash(key: &[u8], input: &[u8]) -> u32 {
+    let key_bit = |i: usize| {
+        key.get(i / 8)
+            .map_or(0, |b| ((b >> (7 - i % 8)) & 1) as u32)
+    };
+    let mut window = (0..32).fold(0u32, |w, i| (w << 1) | key_bit(i));
+    let mut hash = 0;
+    for (i, b) in input.iter().enumerate() {
+        for j in 0..8 {
+            if b & (0x80 >> j) != 0 {
+                hash ^= window;
+            }
+            window = (window << 1) | key_bit(32 + i * 8 + j);
+        }
+    }
+    hash
+}
+
+pub struct RssFilter<'a> {
+    cfg: &'a RssRegs,
+}
+impl<'a> RssFilter<'a> {
+    fn hash_type(&self, info: &ParserInfo) -> RssHashType {
+        let ctrl = self.cfg.cfg.rss_ctrl();
+        let l4 = info.l4.header_len != 0;
+        if info.l3.header_len == 0 {
+            return RssHashType::None;
+        }
+        match (info.l2.etype, info.l3.protocol) {
+            (EthernetProtocol::Ipv4, IpProtocol::Tcp) if l4 && ctrl.tcp_ipv4() == 1 => {
+                RssHashType::TcpIpv4
+            }
+            (EthernetProtocol::Ipv4, IpProtocol::Udp) if l4 && ctrl.udp_ipv4() == 1 => {
+                RssHashType::UdpIpv4
+            }
+            (EthernetProtocol::Ipv4, _) if ctrl.ipv4() == 1 => RssHashType::Ipv4,
+            (EthernetProtocol::Ipv6, IpProtocol::Tcp) if l4 && ctrl.tcp_ipv6() == 1 => {
+                RssHashType::TcpIpv6
+            }
+            (EthernetProtocol::Ipv6, IpProtocol::Udp) if l4 && ctrl.udp_ipv6() == 1 => {
+                RssHashType::UdpIpv6
+            }
+            (EthernetProtocol::Ipv6, _) if ctrl.ipv6() == 1 => RssHashType::Ipv6,
+            _ => RssHashType::None,
+        }
+    }
+
+    fn hash(&self, info: &ParserInfo, ty: RssHashType) -> u32 {
+        let mut input = Vec::with_capacity(36);
+        input.extend_from_slice(info.l3.src.as_bytes());
+        input.extend_from_slice(info.l3.dst.as_bytes());
+        match ty {
+            RssHashType::TcpIpv4
+            | RssHashType::UdpIpv4
+            | RssHashType::TcpIpv6
+            | RssHashType::UdpIpv6 => {
+                input.extend_from_slice(&info.l4.src.to_be_bytes());
+                input.extend_from_slice(&info.l4.dst.to_be_bytes());
+            }
+            _ => {}
+        }
+        toeplitz_hash(&self.cfg.cfg.key(), &input)
+    }
+}
+
+impl<'a> Pipeline for RssFilter<'a> {
+    type Input = (ParserInfo, Option<(usize, CongestionAction)>);
+    type Output = (ParserInfo, Option<(usize, CongestionAction)>);
+    fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let (mut info, r) = *i;
+        let ctrl = self.cfg.cfg.rss_ctrl();
+        if ctrl.en() == 0 {
+            return Ok(*i);
+        }
+        let ty = self.hash_type(&info);
+        if ty == RssHashType::None {
+            return Ok(*i);
+        }
+        let hash = self.hash(&info, ty);
+        info.rss = RssInfo { hash, ty };
+        //exact match filters take precedence over rss
+        Ok((
+            info,
+            r.or_else(|| Some((self.cfg.queue_id(hash), ctrl.get_congestion_action()))),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::EthaRxParser;
+    use super::*;
+    use crate::reg_if::RegBus;
+    use crate::tests_frame_helper::*;
+
+    const KEY: [u8; 40] = [
+        0x6d, 0x5a, 0x56, 0xda, 0x25, 0x5b, 0x0e, 0xc2, 0x41, 0x67, 0x25, 0x3d, 0x43, 0xa3, 0x8f,
+        0xb0, 0xd0, 0xca, 0x2b, 0xcb, 0xae, 0x7b, 0x30, 0xb4, 0x77, 0xcb, 0x2d, 0xa3, 0x80, 0x30,
+        0xf2, 0x0c, 0x6a, 0x42, 0xb7, 0x3b, 0xbe, 0xac, 0x01, 0xfa,
+    ];
+
+    #[test]
+    fn toeplitz_hash_test() {
+        //verification suite from the microsoft rss spec
+        let ipv4 = [66, 9, 149, 187, 161, 142, 100, 80];
+        assert_eq!(toeplitz_hash(&KEY, &ipv4), 0x323e8fc2);
+        let tcp_ipv4 = [&ipv4[..], &2794u16.to_be_bytes(), &1766u16.to_be_bytes()].concat();
+        assert_eq!(toeplitz_hash(&KEY, &tcp_ipv4), 0x51ccc178);
+        let ipv4 = [199, 92, 111, 2, 65, 69, 140, 83];
+        assert_eq!(toeplitz_hash(&KEY, &ipv4), 0xd718262a);
+        let tcp_ipv4 = [&ipv4[..], &14230u16.to_be_bytes(), &4739u16.to_be_bytes()].concat();
+        assert_eq!(toeplitz_hash(&KEY, &tcp_ipv4), 0xc626b0ea);
+    }
+
+    #[test]
+    fn tp5_filter_regs_test() {
+        //tp5 filter registers are decoded to the tp5 filters, not to the ethertype filters
+        let regs = RxRegs::<RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        let base = regs.tp5_filter_offset(1) as u64;
+        regs.write(base + 9, set_filter_en(true) as u64).unwrap();
+        assert_eq!(regs.filters.tp5_filters[1].tp5_ctrl().en(), 1);
+        assert_eq!(regs.read(base + 9), Some(set_filter_en(true) as u64));
+        assert!(regs
+            .filters
+            .et_filters
+            .iter()
+            .all(|f| f.et_filter().en() == 0));
+    }
+
+    #[test]
+    fn ecpri_udp_filter_test() {
+        let regs = RxRegs::<RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        regs.ecpri
+            .write(0, set_filter_en(true) as u64 | 0x1234)
+            .unwrap();
+        let parser = EthaRxParser::new(&regs);
+        //real-time control with RTC_ID 0x100-0x1ff to queue 3
+        let filters: [LockedEcpriFilterRegs; 1] =
+            array_init::array_init(|_| LockedEcpriFilterRegs::new(32));
+        filters[0].write(1, 0x100 | 0x1ff << 16).unwrap();
+        filters[0]
+            .write(0, (2 | set_filter_queue_id(3) | set_filter_en(true)) as u64)
+            .unwrap();
+        let mut ecpri = EcpriFilter { cfg: &filters };
+
+        //revision 1 with the concatenation flag, 4 bytes payload behind the ids
+        let msg = |msg_type: u8, id: u16| {
+            [
+                &[0x11, msg_type, 0, 8][..],
+                &id.to_be_bytes(),
+                &0x8001u16.to_be_bytes(),
+                &[0x5a; 4],
+            ]
+            .concat()
+        };
+        for (msg_type, id, queue_id) in [(2, 0x180, Some(3)), (2, 0x200, None), (0, 0x180, None)] {
+            let udp = udp_datagram(0x4321, 0x1234, &msg(msg_type, id));
+            let mut frame = ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &udp);
+            let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+            let e = info.ecpri.unwrap();
+            assert_eq!(e.revision, 1);
+            assert!(e.concat);
+            assert_eq!(e.msg_type, msg_type);
+            assert_eq!(e.payload_size, 8);
+            assert_eq!(e.id, id);
+            assert_eq!(e.seq_id, 0x8001);
+            let (_, r) = ecpri.execute(&mut frame, &(info, None)).unwrap();
+            assert_eq!(r.map(|(id, _)| id), queue_id);
+        }
+
+        //other udp ports are not ecpri
+        let udp = udp_datagram(0x4321, 0x1235, &msg(2, 0x180));
+        let mut frame = ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &udp);
+        let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+        assert!(info.ecpri.is_none());
+        assert!(ecpri.execute(&mut frame, &(info, None)).unwrap().1.is_none());
+    }
+
+    #[test]
+    fn tp5_filter_test() {
+        let regs = RxRegs::<RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        let parser = EthaRxParser::new(&regs);
+        let filters: [LockedTuplesFilterRegs; 2] =
+            array_init::array_init(|_| LockedTuplesFilterRegs::new(32));
+        //192.168.0.0/16 to dst port 8000-8100, pri 2
+        filters[0]
+            .write(0, u32::from_le_bytes([192, 168, 0, 0]) as u64)
+            .unwrap();
+        filters[0].write(8, 8000 << 16).unwrap();
+        filters[0].write(10, 8100 << 16).unwrap();
+        filters[0].write(11, 16).unwrap();
+        filters[0]
+            .write(
+                9,
+                (u8::from(IpProtocol::Tcp) as usize
+                    | 2 << 8
+                    | 1 << 14
+                    | set_filter_queue_id(1)
+                    | 1 << 25
+                    | 1 << 27
+                    | set_filter_en(true)) as u64,
+            )
+            .unwrap();
+        //syn without ack, pri 1
+        filters[1].write(12, 0x002 | 0x012 << 16).unwrap();
+        filters[1]
+            .write(
+                9,
+                (1 << 8 | 1 << 15 | set_filter_queue_id(2) | 0x1f << 24 | set_filter_en(true))
+                    as u64,
+            )
+            .unwrap();
+        let mut tp5 = Tp5Filter { cfg: &filters };
+
+        for (src, dst_port, flags, queue_id) in [
+            ([192, 168, 3, 4], 8080, 0x02, Some(2)),
+            ([192, 168, 3, 4], 8080, 0x12, Some(1)),
+            ([192, 168, 3, 4], 8100, 0x10, Some(1)),
+            ([192, 169, 3, 4], 8080, 0x12, None),
+            ([192, 168, 3, 4], 8101, 0x12, None),
+            ([10, 0, 0, 1], 9000, 0x02, Some(2)),
+        ] {
+            let tcp = tcp_segment(0x1234, dst_port, flags, &[]);
+            let mut frame = ipv4_frame(src, [10, 0, 0, 2], IpProtocol::Tcp, &tcp);
+            let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+            assert_eq!(info.l4.tcp_flags, flags as u16);
+            let (_, r) = tp5.execute(&mut frame, &(info, None)).unwrap();
+            assert_eq!(r.map(|(id, _)| id), queue_id);
+        }
+    }
+
+    fn flow_cmd(table: &FlowTable, key: &FlowKey, op: FlowCmdOp, queue_id: u64) -> (u64, u64) {
+        for i in 0..4 {
+            let src = u32::from_le_bytes(key.src[i * 4..i * 4 + 4].try_into().unwrap());
+            let dst = u32::from_le_bytes(key.dst[i * 4..i * 4 + 4].try_into().unwrap());
+            table.write(i as u64, src as u64).unwrap();
+            table.write(i as u64 + 4, dst as u64).unwrap();
+        }
+        table
+            .write(8, key.src_port as u64 | (key.dst_port as u64) << 16)
+            .unwrap();
+        table
+            .write(9, key.protocol as u64 | (key.ipv6 as u64) << 8)
+            .unwrap();
+        table.write(10, queue_id << 16).unwrap();
+        table.write(FLOW_CMD_OFFSET as u64, op as u64).unwrap();
+        let status = table.read(12).unwrap();
+        (status & 0xf, status >> 16)
+    }
+
+    #[test]
+    fn flow_table_test() {
+        let regs = RxRegs::<RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        let parser = EthaRxParser::new(&regs);
+        let table = FlowTable::new();
+        let clock = ModelClock::new();
+        clock.set_virtual(true);
+        let mut flow = FlowFilter {
+            table: &table,
+            clock: &clock,
+        };
+        let tcp = tcp_segment(0x1234, 80, 0x10, &[]);
+        let mut frame = ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Tcp, &tcp);
+        let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+        let key = flow_key(&info).unwrap();
+
+        assert!(flow.execute(&mut frame, &(info, None)).unwrap().1.is_none());
+        let (result, index) = flow_cmd(&table, &key, FlowCmdOp::Add, 3);
+        assert_eq!(result, FlowCmdResult::Ok as u64);
+        assert!(key.bucket().contains(&(index as usize)));
+        let (result, _) = flow_cmd(&table, &key, FlowCmdOp::Add, 3);
+        assert_eq!(result, FlowCmdResult::Exists as u64);
+
+        let start = clock.now();
+        for i in 1..=2 {
+            clock.advance_to(start + i * 1000);
+            let (_, r) = flow.execute(&mut frame, &(info, None)).unwrap();
+            assert_eq!(r.map(|(id, _)| id), Some(3));
+        }
+        //earlier stages take precedence
+        let (_, r) = flow
+            .execute(&mut frame, &(info, Some((1, CongestionAction::Drop))))
+            .unwrap();
+        assert_eq!(r.map(|(id, _)| id), Some(1));
+
+        let (result, _) = flow_cmd(&table, &key, FlowCmdOp::Query, 0);
+        assert_eq!(result, FlowCmdResult::Ok as u64);
+        assert_eq!(table.read(10).unwrap() >> 16 & 0xff, 3);
+        assert_eq!(table.read(13).unwrap(), 2);
+        //the last hit is stamped with the model time
+        let ts = table.read(14).unwrap() | table.read(15).unwrap() << 32;
+        assert_eq!(ts, start + 2000);
+
+        let (result, _) = flow_cmd(&table, &key, FlowCmdOp::Del, 0);
+        assert_eq!(result, FlowCmdResult::Ok as u64);
+        let (result, _) = flow_cmd(&table, &key, FlowCmdOp::Query, 0);
+        assert_eq!(result, FlowCmdResult::NotFound as u64);
+        assert!(flow.execute(&mut frame, &(info, None)).unwrap().1.is_none());
+        //nop clears the status
+        assert_eq!(flow_cmd(&table, &key, FlowCmdOp::Nop, 0), (0, 0));
+
+        //one more flow than ways in the same bucket
+        let keys: Vec<FlowKey> = (0..=u16::MAX)
+            .map(|dst_port| FlowKey { dst_port, ..key })
+            .filter(|k| k.bucket() == key.bucket())
+            .take(FLOW_TABLE_WAYS + 1)
+            .collect();
+        for k in &keys[..FLOW_TABLE_WAYS] {
+            assert_eq!(
+                flow_cmd(&table, k, FlowCmdOp::Add, 1).0,
+                FlowCmdResult::Ok as u64
+            );
+        }
+        let (result, _) = flow_cmd(&table, &keys[FLOW_TABLE_WAYS], FlowCmdOp::Add, 1);
+        assert_eq!(result, FlowCmdResult::Full as u64);
+    }
+}
@@ -0,0 +1,154 @@
+use super::parser::ParserInfo;
+use super::reg_if::global::{LockedEthaGlobalRegs, RX_STATION_ADDRS};
+use super::reg_if::stats::{StatsCounter, StatsRegs};
+use super::reg_if::TopRegs;
+use super::*;
+use smoltcp::wire::EthernetAddress;
+use std::sync::Arc;
+
+const ETHERNET_POLYNOMIAL: u32 = 0x04c11db7;
+
+//same as ether_crc() in linux, multicast hash is the top 6 bits
+pub fn ether_crc(addr: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for octet in addr {
+        for bit in 0..8 {
+            let feedback = (crc >> 31) ^ ((*octet as u32 >> bit) & 1);
+            crc = (crc << 1) ^ (feedback * ETHERNET_POLYNOMIAL);
+        }
+    }
+    crc
+}
+
+pub fn mc_hash_bit(addr: &EthernetAddress) -> usize {
+    (ether_crc(addr.as_bytes()) >> 26) as usize
+}
+
+pub struct EthaRxMacFilter {
+    regs: Arc<LockedEthaGlobalRegs>,
+    stats: Arc<StatsRegs<CHS>>,
+}
+
+impl EthaRxMacFilter {
+    pub fn new(regs: &TopRegs<CHS, RX_ET_FILTERS, RX_TP5_FILTERS>) -> Self {
+        EthaRxMacFilter {
+            regs: regs.global.clone(),
+            stats: regs.stats.clone(),
+        }
+    }
+    pub fn pipeline<'a>(&'a self) -> impl Pipeline<Input = ParserInfo, Output = ParserInfo> + 'a {
+        MacFilter {
+            regs: &self.regs,
+            stats: &self.stats,
+        }
+    }
+}
+
+struct MacFilter<'a> {
+    regs: &'a LockedEthaGlobalRegs,
+    stats: &'a StatsRegs<CHS>,
+}
+
+impl<'a> MacFilter<'a> {
+    fn accept(&self, dst: &EthernetAddress) -> bool {
+        let mode = self.regs.rx_mode();
+        if mode.en() == 0 || mode.promisc() == 1 {
+            true
+        } else if dst.is_broadcast() {
+            mode.bcast_en() == 1
+        } else if dst.is_multicast() {
+            mode.allmulti() == 1 || self.regs.mc_hash(mc_hash_bit(dst))
+        } else {
+            (0..RX_STATION_ADDRS).any(|i| self.regs.station(i) == Some(dst.0))
+        }
+    }
+}
+
+impl<'a> Pipeline for MacFilter<'a> {
+    type Input = ParserInfo;
+    type Output = ParserInfo;
+    fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        if self.accept(&i.l2.dst) {
+            Ok(*i)
+        } else {
+            self.stats.inc(StatsCounter::RxAddrFilter);
+            Err(Error::Dropped)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::EthaRxParser;
+    use super::super::reg_if::global::*;
+    use super::*;
+    use crate::reg_if::RegBus;
+
+    #[test]
+    fn ether_crc_test() {
+        assert_eq!(ether_crc(&[0x01, 0x00, 0x5e, 0, 0, 0x01]), 0x7fa32d9b);
+        assert_eq!(
+            mc_hash_bit(&EthernetAddress([0x01, 0x00, 0x5e, 0, 0, 0x01])),
+            31
+        );
+        assert_eq!(
+            mc_hash_bit(&EthernetAddress([0x33, 0x33, 0, 0, 0, 0x01])),
+            62
+        );
+    }
+
+    #[test]
+    fn mac_filter_test() {
+        let regs = TopRegs::<CHS, RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        let parser = EthaRxParser::new(&regs.rx);
+        let filter = EthaRxMacFilter::new(&regs);
+        let station = EthernetAddress([0x02, 0, 0, 0, 0, 0x1]);
+        let other = EthernetAddress([0x02, 0, 0, 0, 0, 0x2]);
+        let v4_mc = EthernetAddress([0x01, 0x00, 0x5e, 0, 0, 0x01]);
+        let v6_mc = EthernetAddress([0x33, 0x33, 0, 0, 0, 0x01]);
+        let accept = |dst: EthernetAddress| {
+            let mut frame = [
+                &dst.0[..],
+                &[0x02, 0, 0, 0, 0, 0x9],
+                &[0x88, 0xb5],
+                &[0; 50],
+            ]
+            .concat();
+            parser
+                .pipeline()
+                .comb(filter.pipeline())
+                .execute(&mut frame, &())
+                .is_ok()
+        };
+        let global = |offset: usize, v: u64| regs.global.write(offset as u64, v).unwrap();
+
+        assert!(accept(other));
+        global(GLOBAL_RX_MODE_OFFSET, 0x1);
+        for dst in [station, other, v4_mc, v6_mc, EthernetAddress::BROADCAST] {
+            assert!(!accept(dst));
+        }
+        global(GLOBAL_STATION_OFFSET + 2, 0x0001);
+        global(GLOBAL_STATION_OFFSET + 3, 0x0200);
+        assert!(!accept(station));
+        global(GLOBAL_STATION_OFFSET + 3, 0x8000_0200);
+        assert!(accept(station));
+        assert!(!accept(other));
+
+        global(GLOBAL_MC_HASH_OFFSET, 1 << 31);
+        assert!(accept(v4_mc));
+        assert!(!accept(v6_mc));
+        global(GLOBAL_MC_HASH_OFFSET + 1, 1 << 30);
+        assert!(accept(v6_mc));
+
+        global(GLOBAL_RX_MODE_OFFSET, 0x3);
+        assert!(accept(EthernetAddress::BROADCAST));
+        global(GLOBAL_RX_MODE_OFFSET, 0x9);
+        global(GLOBAL_MC_HASH_OFFSET, 0);
+        assert!(accept(v4_mc));
+        assert!(!accept(other));
+        global(GLOBAL_RX_MODE_OFFSET, 0x5);
+        assert!(accept(other));
+        let dropped = StatsRegs::<CHS>::counter_offset(StatsCounter::RxAddrFilter);
+        assert_eq!(regs.stats.read(dropped as u64), Some(9));
+    }
+}
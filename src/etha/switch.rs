@@ -0,0 +1,296 @@
+use super::reg_if::switch::*;
+use crate::clock::ModelClock;
+use crate::reg_if::RegBus;
+use etha_model_generator::GenHeader;
+use smoltcp::wire::{EthernetAddress, EthernetFrame};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const NS_PER_SEC: u64 = 1_000_000_000;
+const VLAN_TPIDS: [u16; 2] = [0x8100, 0x88a8];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FdbKey {
+    pub mac: EthernetAddress,
+    //outer vlan id, 0 for untagged frames
+    pub vid: u16,
+}
+
+impl FdbKey {
+    fn from_regs(regs: &LockedSwitchRegs) -> Self {
+        let hi = (regs.fdb_mac_hi().mac() as u16).to_be_bytes();
+        let lo = (regs.fdb_mac_lo().mac() as u32).to_be_bytes();
+        FdbKey {
+            mac: EthernetAddress([hi[0], hi[1], lo[0], lo[1], lo[2], lo[3]]),
+            vid: regs.fdb_mac_hi().vid() as u16,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FdbEntry {
+    port: usize,
+    is_static: bool,
+    last_seen: u64,
+}
+
+pub struct Fdb {
+    pub regs: LockedSwitchRegs,
+    clock: Arc<ModelClock>,
+    entries: Mutex<HashMap<FdbKey, FdbEntry>>,
+}
+
+impl Fdb {
+    pub fn new(clock: &Arc<ModelClock>) -> Self {
+        Fdb {
+            regs: LockedSwitchRegs::new(32),
+            clock: clock.clone(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ports(&self) -> usize {
+        self.regs.switch_info().ports() as usize
+    }
+
+    pub fn set_ports(&self, ports: usize) {
+        assert!((1..=SWITCH_MAX_PORTS).contains(&ports));
+        self.regs.switch_info_mut().set_ports(ports as u64);
+    }
+
+    fn expired(&self, e: &FdbEntry, now: u64) -> bool {
+        let aging = self.regs.fdb_aging().time() * NS_PER_SEC;
+        !e.is_static && aging != 0 && now.saturating_sub(e.last_seen) > aging
+    }
+
+    fn execute(&self) {
+        let now = self.clock.now();
+        let key = FdbKey::from_regs(&self.regs);
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, e| !self.expired(e, now));
+        let result = match FdbCmdOp::from(self.regs.fdb_cmd().op()) {
+            //nop clears the result and refreshes the count of aged entries
+            FdbCmdOp::Nop => FdbCmdResult::Ok,
+            FdbCmdOp::Add if entries.len() >= FDB_SIZE && !entries.contains_key(&key) => {
+                FdbCmdResult::Full
+            }
+            FdbCmdOp::Add => {
+                entries.insert(
+                    key,
+                    FdbEntry {
+                        port: self.regs.fdb_entry().port() as usize,
+                        is_static: self.regs.fdb_entry().is_static() == 1,
+                        last_seen: now,
+                    },
+                );
+                FdbCmdResult::Ok
+            }
+            FdbCmdOp::Del => entries
+                .remove(&key)
+                .map_or(FdbCmdResult::NotFound, |_| FdbCmdResult::Ok),
+            FdbCmdOp::Query => match entries.get(&key) {
+                Some(e) => {
+                    self.regs.fdb_entry_mut().set_port(e.port as u64);
+                    self.regs.fdb_entry_mut().set_is_static(e.is_static as u64);
+                    FdbCmdResult::Ok
+                }
+                None => FdbCmdResult::NotFound,
+            },
+            FdbCmdOp::Flush => {
+                entries.retain(|_, e| e.is_static);
+                FdbCmdResult::Ok
+            }
+        };
+        self.regs.fdb_status_mut().set_result(result as u64);
+        self.regs.fdb_status_mut().set_count(entries.len() as u64);
+    }
+
+    fn parse(frame: &[u8]) -> Option<(EthernetAddress, EthernetAddress, u16)> {
+        let eth = EthernetFrame::new_checked(frame).ok()?;
+        let vid = if VLAN_TPIDS.contains(&u16::from(eth.ethertype())) {
+            let tci = eth.payload().get(..2)?;
+            u16::from_be_bytes([tci[0], tci[1]]) & 0xfff
+        } else {
+            0
+        };
+        Some((eth.src_addr(), eth.dst_addr(), vid))
+    }
+
+    fn learn(&self, entries: &mut HashMap<FdbKey, FdbEntry>, key: FdbKey, port: usize, now: u64) {
+        if let Some(e) = entries.get_mut(&key) {
+            //static entries never move
+            if !e.is_static {
+                e.port = port;
+                e.last_seen = now;
+            }
+        } else if entries.len() < FDB_SIZE {
+            entries.insert(
+                key,
+                FdbEntry {
+                    port,
+                    is_static: false,
+                    last_seen: now,
+                },
+            );
+        }
+    }
+
+    fn lookup(
+        &self,
+        entries: &mut HashMap<FdbKey, FdbEntry>,
+        key: &FdbKey,
+        now: u64,
+    ) -> Option<usize> {
+        let e = *entries.get(key)?;
+        if self.expired(&e, now) {
+            entries.remove(key);
+            None
+        } else {
+            Some(e.port)
+        }
+    }
+
+    //egress ports of a frame received from ingress, may include the host port
+    pub fn forward(&self, ingress: usize, frame: &[u8]) -> Vec<usize> {
+        let ctrl = self.regs.switch_ctrl();
+        //all mac ports are bridged to the host, the host transmits to port 0
+        if ctrl.en() == 0 {
+            return vec![if ingress == SWITCH_HOST_PORT {
+                0
+            } else {
+                SWITCH_HOST_PORT
+            }];
+        }
+        let Some((src, dst, vid)) = Self::parse(frame) else {
+            return vec![];
+        };
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().unwrap();
+        if ctrl.learn_en() == 1 && src.is_unicast() {
+            self.learn(&mut entries, FdbKey { mac: src, vid }, ingress, now);
+        }
+        let egress = if dst.is_unicast() {
+            self.lookup(&mut entries, &FdbKey { mac: dst, vid }, now)
+        } else {
+            None
+        };
+        let ports = self.ports();
+        match egress {
+            Some(port) if port == ingress || (port != SWITCH_HOST_PORT && port >= ports) => {
+                vec![]
+            }
+            Some(port) => vec![port],
+            //flood unknown unicast, multicast and broadcast
+            None => (0..ports)
+                .chain([SWITCH_HOST_PORT])
+                .filter(|p| *p != ingress)
+                .collect(),
+        }
+    }
+}
+
+impl RegBus for Fdb {
+    fn write(&self, addr: u64, data: u64) -> Option<()> {
+        self.regs.write(addr, data)?;
+        //commands are done once the command register is written
+        if addr == FDB_CMD_OFFSET as u64 {
+            self.execute();
+        }
+        Some(())
+    }
+
+    fn read(&self, addr: u64) -> Option<u64> {
+        self.regs.read(addr)
+    }
+}
+
+impl GenHeader for Fdb {
+    fn render_name() -> &'static str {
+        "Fdb"
+    }
+    fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
+        SwitchRegs::gen_c_header(header)?;
+        SwitchCtrl::gen_c_header(header)?;
+        SwitchInfo::gen_c_header(header)?;
+        FdbAging::gen_c_header(header)?;
+        FdbMacLo::gen_c_header(header)?;
+        FdbMacHi::gen_c_header(header)?;
+        FdbEntryCtrl::gen_c_header(header)?;
+        FdbCmd::gen_c_header(header)?;
+        FdbStatus::gen_c_header(header)?;
+
+        writeln!(header, "#define SWITCH_MAX_PORTS {}", SWITCH_MAX_PORTS)?;
+        writeln!(header, "#define SWITCH_HOST_PORT {}", SWITCH_HOST_PORT)?;
+        writeln!(header, "#define FDB_SIZE {}", FDB_SIZE)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: [u8; 6] = [0x02, 0, 0, 0, 0, 0xa];
+    const B: [u8; 6] = [0x02, 0, 0, 0, 0, 0xb];
+    const C: [u8; 6] = [0x02, 0, 0, 0, 0, 0xc];
+    const HOST: usize = SWITCH_HOST_PORT;
+
+    fn frame(dst: [u8; 6], src: [u8; 6], vid: Option<u16>) -> Vec<u8> {
+        let tag = vid.map_or(vec![], |v| [0x81, 0, (v >> 8) as u8, v as u8].to_vec());
+        [&dst[..], &src, &tag, &[0x88, 0xb5], &[0; 46]].concat()
+    }
+
+    fn fdb_cmd(fdb: &Fdb, mac: [u8; 6], port: usize, is_static: bool, op: FdbCmdOp) -> (u64, u64) {
+        fdb.write(2, u32::from_be_bytes(mac[2..].try_into().unwrap()) as u64)
+            .unwrap();
+        fdb.write(3, u16::from_be_bytes([mac[0], mac[1]]) as u64)
+            .unwrap();
+        fdb.write(4, (is_static as u64) << 8 | port as u64).unwrap();
+        fdb.write(FDB_CMD_OFFSET as u64, op as u64).unwrap();
+        let status = fdb.regs.fdb_status();
+        (status.result(), status.count())
+    }
+
+    #[test]
+    fn fdb_test() {
+        let clock = Arc::new(ModelClock::new());
+        clock.set_virtual(true);
+        let fdb = Fdb::new(&clock);
+        fdb.set_ports(3);
+        assert_eq!(fdb.forward(0, &frame(B, A, None)), [HOST]);
+        assert_eq!(fdb.forward(HOST, &frame(B, A, None)), [0]);
+
+        fdb.write(0, 0x3).unwrap();
+        assert_eq!(fdb.forward(0, &frame(B, A, None)), [1, 2, HOST]);
+        assert_eq!(fdb.forward(1, &frame(A, B, None)), [0]);
+        assert_eq!(fdb.forward(0, &frame(B, A, None)), [1]);
+        assert_eq!(fdb.forward(2, &frame(B, A, None)), [1]);
+        //A moved to port 2, frames to the same port are filtered
+        assert_eq!(fdb.forward(2, &frame(A, C, None)), Vec::<usize>::new());
+        //fdb is per vlan
+        assert_eq!(fdb.forward(2, &frame(B, C, Some(10))), [0, 1, HOST]);
+        assert_eq!(fdb.forward(HOST, &frame([0xff; 6], C, None)), [0, 1, 2]);
+        assert_eq!(fdb_cmd(&fdb, A, 0, false, FdbCmdOp::Query), (0, 4));
+        assert_eq!(fdb.regs.fdb_entry().port(), 2);
+
+        //aging
+        fdb.write(1, 1).unwrap();
+        clock.advance_to(clock.now() + 2 * NS_PER_SEC);
+        assert_eq!(fdb.forward(1, &frame(A, B, None)), [0, 2, HOST]);
+        assert_eq!(fdb_cmd(&fdb, A, 0, false, FdbCmdOp::Query), (1, 1));
+
+        //static entries are not learned over and never age
+        assert_eq!(fdb_cmd(&fdb, A, 0, true, FdbCmdOp::Add), (0, 2));
+        assert_eq!(fdb.forward(2, &frame(C, A, None)), [0, 1, HOST]);
+        clock.advance_to(clock.now() + 2 * NS_PER_SEC);
+        assert_eq!(fdb.forward(1, &frame(A, B, None)), [0]);
+        assert_eq!(fdb_cmd(&fdb, A, 0, false, FdbCmdOp::Flush), (0, 1));
+        assert_eq!(fdb_cmd(&fdb, A, 0, false, FdbCmdOp::Del), (0, 0));
+        assert_eq!(fdb_cmd(&fdb, A, 0, false, FdbCmdOp::Del), (1, 0));
+        assert_eq!(fdb_cmd(&fdb, A, 0, false, FdbCmdOp::Nop), (0, 0));
+        //learning disabled
+        fdb.write(0, 0x1).unwrap();
+        assert_eq!(fdb.forward(1, &frame(A, B, None)), [0, 2, HOST]);
+        assert_eq!(fdb.forward(0, &frame(B, A, None)), [1, 2, HOST]);
+    }
+}
@@ -0,0 +1,282 @@
+use super::desc::rx::RxTunnelDesc;
+use super::l2_parser::{L2Info, L2Parser};
+use super::l3_parser::{L3Info, L3Parser};
+use super::l4_parser::{L4Info, L4Parser};
+use super::parser::ParserInfo;
+use super::reg_if::rx::LockedTunnelRegs;
+use super::*;
+use smoltcp::wire::{EthernetAddress, EthernetProtocol, IpProtocol};
+
+pub const VXLAN_PORT: u16 = 4789;
+const VXLAN_HEADER_LEN: usize = 8;
+const VXLAN_FLAG_I: u8 = 0x08;
+const GRE_HEADER_LEN: usize = 4;
+const GRE_FLAG_C: u8 = 0x80;
+const GRE_FLAG_K: u8 = 0x20;
+const GRE_FLAG_S: u8 = 0x10;
+const GRE_VERSION_MASK: u8 = 0x7;
+//transparent ethernet bridging, carried by nvgre
+const GRE_PROTO_TEB: u16 = 0x6558;
+const IPPROTO_IPIP: u8 = 4;
+const IPPROTO_IPV6: u8 = 41;
+const IPPROTO_GRE: u8 = 47;
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TunnelType {
+    Vxlan = 1,
+    Gre = 2,
+    Nvgre = 3,
+    Ipv4InIp = 4,
+    Ipv6InIp = 5,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TunnelInfo {
+    pub ty: TunnelType,
+    //vni for vxlan, vsid for nvgre, key for gre
+    pub id: u32,
+    //start of the inner packet in the frame
+    pub offset: usize,
+    //none if the inner packet starts from l3
+    pub l2: Option<L2Info>,
+    pub etype: EthernetProtocol,
+    pub l3: L3Info,
+    pub l4: L4Info,
+}
+
+impl From<TunnelInfo> for RxTunnelDesc {
+    fn from(info: TunnelInfo) -> Self {
+        let mut desc = RxTunnelDesc::default();
+        let l3_offset = info.offset + info.l2.map_or(0, |l2| l2.header_len);
+        desc.set_tunnel_type(info.ty as u32);
+        desc.set_tunnel_inner_is_l2(info.l2.is_some() as u32);
+        desc.set_tunnel_id(info.id);
+        desc.set_tunnel_inner_l2_offset(info.offset as u32);
+        desc.set_tunnel_inner_l3_offset(l3_offset as u32);
+        if info.l3.header_len != 0 {
+            desc.set_tunnel_inner_l4_offset((l3_offset + info.l3.header_len) as u32);
+            desc.set_tunnel_inner_protocol(u8::from(info.l3.protocol) as u32);
+            desc.set_tunnel_inner_version(match info.etype {
+                EthernetProtocol::Ipv6 => 6,
+                _ => 4,
+            });
+        }
+        desc
+    }
+}
+
+struct Encap {
+    ty: TunnelType,
+    id: u32,
+    offset: usize,
+    //none for inner ethernet
+    etype: Option<EthernetProtocol>,
+}
+
+pub struct TunnelParser<'a> {
+    pub cfg: &'a LockedTunnelRegs,
+}
+
+impl<'a> TunnelParser<'a> {
+    fn vxlan(&self, buffer: &[u8], start: usize) -> Option<Encap> {
+        let hdr = buffer.get(start..start + VXLAN_HEADER_LEN)?;
+        if hdr[0] & VXLAN_FLAG_I == 0 {
+            return None;
+        }
+        Some(Encap {
+            ty: TunnelType::Vxlan,
+            id: u32::from_be_bytes([0, hdr[4], hdr[5], hdr[6]]),
+            offset: start + VXLAN_HEADER_LEN,
+            etype: None,
+        })
+    }
+
+    fn gre(&self, buffer: &[u8], start: usize) -> Option<Encap> {
+        let hdr = buffer.get(start..start + GRE_HEADER_LEN)?;
+        if hdr[1] & GRE_VERSION_MASK != 0 {
+            return None;
+        }
+        let (c, k, s) = (
+            hdr[0] & GRE_FLAG_C != 0,
+            hdr[0] & GRE_FLAG_K != 0,
+            hdr[0] & GRE_FLAG_S != 0,
+        );
+        let proto = u16::from_be_bytes([hdr[2], hdr[3]]);
+        //optional fields are in order of checksum, key and sequence number
+        let key_start = start + GRE_HEADER_LEN + 4 * c as usize;
+        let key = if k {
+            let key = buffer.get(key_start..key_start + 4)?;
+            u32::from_be_bytes(key.try_into().unwrap())
+        } else {
+            0
+        };
+        let offset = key_start + 4 * k as usize + 4 * s as usize;
+        if offset > buffer.len() {
+            return None;
+        }
+        Some(match proto {
+            GRE_PROTO_TEB => Encap {
+                ty: TunnelType::Nvgre,
+                id: key >> 8,
+                offset,
+                etype: None,
+            },
+            _ => Encap {
+                ty: TunnelType::Gre,
+                id: key,
+                offset,
+                etype: Some(EthernetProtocol::from(proto)),
+            },
+        })
+    }
+
+    fn decap(&self, buffer: &[u8], i: &ParserInfo) -> Option<Encap> {
+        let ctrl = self.cfg.tunnel_ctrl();
+        //tunnels are not decapsulated before reassembly
+        if i.l3.header_len == 0 || i.l3.frag.is_some() {
+            return None;
+        }
+        let start = i.l2.header_len + i.l3.header_len;
+        let vxlan_port = match ctrl.vxlan_port() as u16 {
+            0 => VXLAN_PORT,
+            port => port,
+        };
+        match (i.l3.protocol, u8::from(i.l3.protocol)) {
+            (IpProtocol::Udp, _)
+                if ctrl.vxlan_en() == 1 && i.l4.header_len != 0 && i.l4.dst == vxlan_port =>
+            {
+                self.vxlan(buffer, start + i.l4.header_len)
+            }
+            (_, IPPROTO_GRE) if ctrl.gre_en() == 1 => self.gre(buffer, start),
+            (_, IPPROTO_IPIP) if ctrl.ipip_en() == 1 => Some(Encap {
+                ty: TunnelType::Ipv4InIp,
+                id: 0,
+                offset: start,
+                etype: Some(EthernetProtocol::Ipv4),
+            }),
+            (_, IPPROTO_IPV6) if ctrl.ipip_en() == 1 => Some(Encap {
+                ty: TunnelType::Ipv6InIp,
+                id: 0,
+                offset: start,
+                etype: Some(EthernetProtocol::Ipv6),
+            }),
+            _ => None,
+        }
+    }
+
+    fn parse(&self, buffer: &mut [u8], i: &ParserInfo) -> Option<TunnelInfo> {
+        let encap = self.decap(buffer, i)?;
+        let end = std::cmp::min(
+            i.l2.header_len + i.l3.header_len + i.l3.payload_len,
+            buffer.len(),
+        );
+        let inner = buffer.get_mut(encap.offset..end)?;
+        let (l2, etype, l3, l4) = match encap.etype {
+            None => {
+                let ((l2, l3), l4) = L2Parser
+                    .comb(L3Parser)
+                    .comb(L4Parser)
+                    .execute(inner, &())
+                    .ok()?;
+                (Some(l2), l2.etype, l3, l4)
+            }
+            Some(etype) => {
+                let l2 = L2Info {
+                    src: EthernetAddress([0; 6]),
+                    dst: EthernetAddress([0; 6]),
+                    etype,
+                    header_len: 0,
+                    vlan: None,
+                    svlan: None,
+                    vlan_stripped: false,
+                };
+                let ((_, l3), l4) = L3Parser.comb(L4Parser).execute(inner, &l2).ok()?;
+                (None, etype, l3, l4)
+            }
+        };
+        Some(TunnelInfo {
+            ty: encap.ty,
+            id: encap.id,
+            offset: encap.offset,
+            l2,
+            etype,
+            l3,
+            l4,
+        })
+    }
+}
+
+impl<'a> Pipeline for TunnelParser<'a> {
+    type Input = ParserInfo;
+    type Output = ParserInfo;
+    fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let mut info = *i;
+        //malformed inner packets are reported as not tunneled
+        info.tunnel = self.parse(buffer, i);
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::EthaRxParser;
+    use super::super::reg_if::rx::RxRegs;
+    use super::*;
+    use crate::tests_frame_helper::*;
+    use smoltcp::wire::*;
+
+    #[test]
+    fn tunnel_parser_test() {
+        let regs = RxRegs::<RX_ET_FILTERS, RX_TP5_FILTERS>::new();
+        let parser = EthaRxParser::new(&regs);
+        let encap = |protocol: u8, payload: &[u8]| {
+            ipv4_frame([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::from(protocol), payload)
+        };
+        //ipv6 udp packet without payload
+        let mut inner_v6 = vec![0u8; 48];
+        inner_v6[0] = 0x60;
+        inner_v6[5] = 8;
+        inner_v6[6] = u8::from(IpProtocol::Udp);
+        inner_v6[40..44].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        inner_v6[45] = 8;
+        let inner_l2 = [&[0u8; 12][..], &[0x86, 0xdd], &inner_v6].concat();
+        let nvgre = [
+            &[0x20, 0, 0x65, 0x58, 0xab, 0xcd, 0xef, 0x11][..],
+            &inner_l2,
+        ]
+        .concat();
+
+        let mut frame = encap(IPPROTO_GRE, &nvgre);
+        let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+        assert!(info.tunnel.is_none());
+
+        regs.tunnel.write(0, 0x7).unwrap();
+        let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+        let tunnel = info.tunnel.unwrap();
+        assert_eq!(tunnel.ty, TunnelType::Nvgre);
+        assert_eq!(tunnel.id, 0xabcdef);
+        assert_eq!(tunnel.offset, 42);
+        assert_eq!(tunnel.l2.unwrap().header_len, ETHERNET_HEADER_LEN);
+        assert_eq!(tunnel.etype, EthernetProtocol::Ipv6);
+        assert_eq!(tunnel.l3.protocol, IpProtocol::Udp);
+        assert_eq!((tunnel.l4.src, tunnel.l4.dst), (0x1234, 0x5678));
+        let desc = RxTunnelDesc::from(tunnel);
+        assert_eq!(desc.tunnel_inner_l3_offset(), 56);
+        assert_eq!(desc.tunnel_inner_l4_offset(), 96);
+        assert_eq!(desc.tunnel_inner_version(), 6);
+
+        let mut frame = encap(IPPROTO_IPV6, &inner_v6);
+        let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+        let tunnel = info.tunnel.unwrap();
+        assert_eq!(tunnel.ty, TunnelType::Ipv6InIp);
+        assert_eq!(tunnel.offset, 34);
+        assert!(tunnel.l2.is_none());
+        assert_eq!((tunnel.l4.src, tunnel.l4.dst), (0x1234, 0x5678));
+
+        //truncated inner packets are not reported as tunneled
+        let mut frame = encap(IPPROTO_IPV6, &inner_v6[..30]);
+        let info = parser.pipeline().execute(&mut frame, &()).unwrap();
+        assert!(info.tunnel.is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use super::desc::tx::*;
 use super::STATICS_TAR;
+use crate::clock::ModelClock;
 use crate::desc::*;
 use crate::irq::*;
 use crate::logger;
@@ -11,14 +12,23 @@ pub struct EthaTxCh {
     id: usize,
     irq_num: usize,
     ring: Arc<LockedRingRegs>,
+    coal: IrqCoalescer,
+    clock: Arc<ModelClock>,
 }
 
 impl EthaTxCh {
-    pub fn new(id: usize, ring: &Arc<LockedRingRegs>, irqs: &mut IrqVec) -> Self {
+    pub fn new(
+        id: usize,
+        ring: &Arc<LockedRingRegs>,
+        clock: &Arc<ModelClock>,
+        irqs: &mut IrqVec,
+    ) -> Self {
         EthaTxCh {
             id,
             irq_num: irqs.alloc(&format!("EthaTxChIrq{}", id)),
             ring: ring.clone(),
+            coal: IrqCoalescer::new(),
+            clock: clock.clone(),
         }
     }
     pub fn req(&self) -> Option<TxReqDesc> {
@@ -100,16 +110,18 @@ impl EthaTxCh {
             };
         }
         self.r_advance_c_n(blocks + 1);
+        self.coal.event(self.clock.now());
     }
 }
 
-impl WithIrq for EthaTxCh {
-    fn poll_irq(&self) -> Option<usize> {
-        if self.r_irq_pendings() == 0 {
-            None
-        } else {
-            Some(self.irq_num)
-        }
+impl WithIrqCauses for EthaTxCh {
+    fn poll_causes(&self) -> (usize, u64) {
+        let done = self.coal.expire(
+            self.int_coal().max_frames() as usize,
+            self.int_coal().usecs(),
+            self.clock.now(),
+        );
+        (self.irq_num, self.r_int_latch(done))
     }
 }
 
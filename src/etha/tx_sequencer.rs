@@ -1,19 +1,42 @@
 use super::desc::tx::*;
-use super::reg_if::TopRegs;
+use super::irq_table::IrqTable;
+use super::l2_parser::{L2Parser, VLAN_SIZE, VLAN_TYPE};
+use super::l3_parser::L3Parser;
+use super::ptp::Phc;
+use super::reg_if::{
+    global::{LockedEthaGlobalRegs, TxArbMode},
+    stats::{StatsCounter, StatsRegs},
+    TopRegs,
+};
 use super::tx_ch::*;
+use super::tx_shaper::EthaTxShaper;
+use super::tx_tso::EthaTxTso;
 use super::Error;
 use super::STATICS_TAR;
 use super::*;
 use crate::arbiter::*;
 use crate::irq::*;
 use crate::logger;
+use crate::mac::MAC_MAX_LEN;
+use smoltcp::wire::{
+    EthernetProtocol, IpProtocol, Ipv4Packet, TcpPacket, UdpPacket, ETHERNET_HEADER_LEN,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 #[derive(Debug, Copy, Clone, Default)]
 pub struct TxLoadInfo {
     pub too_large: bool,
     pub too_small: bool,
     pub resp_en: bool,
+    pub l3_csum: bool,
+    pub l4_csum: bool,
+    pub vlan_tci: Option<u16>,
+    pub ts: bool,
+    pub tso: bool,
+    pub mss: usize,
     pub len: usize,
+    pub seg_len: usize,
+    pub segs: usize,
     pub ch_id: usize,
 }
 
@@ -23,51 +46,111 @@ impl TxLoadInfo {
     }
 }
 
-pub struct EthaTxSequencer<A: Arbiter> {
+pub struct EthaTxArbiter {
+    regs: Arc<LockedEthaGlobalRegs>,
+    dirty: Arc<AtomicBool>,
+    rr: RRArbiter<CHS>,
+    sp: SPArbiter<CHS>,
+    dwrr: DwrrArbiter<CHS>,
+    hybrid: HybridArbiter<CHS>,
+}
+
+impl EthaTxArbiter {
+    pub fn new(regs: &Arc<LockedEthaGlobalRegs>, dirty: &Arc<AtomicBool>) -> Self {
+        EthaTxArbiter {
+            regs: regs.clone(),
+            dirty: dirty.clone(),
+            rr: RRArbiter::new(),
+            sp: SPArbiter::new(),
+            dwrr: DwrrArbiter::new(),
+            hybrid: HybridArbiter::new(),
+        }
+    }
+    fn update_cfg(&mut self) {
+        for i in 0..CHS {
+            let (pri, weight) = (self.regs.tx_priority(i), self.regs.tx_weight(i));
+            self.sp.set_priority(i, pri);
+            self.dwrr.set_weight(i, weight);
+            self.hybrid.set_priority(i, pri);
+            self.hybrid.set_weight(i, weight);
+        }
+    }
+}
+
+impl Arbiter for EthaTxArbiter {
+    fn arbit_by<T: Clone, F: Fn(&T) -> usize>(
+        &mut self,
+        req: &[Option<T>],
+        cost: F,
+    ) -> Option<(usize, T)> {
+        //priorities and weights are reloaded only after they are written
+        if self.dirty.swap(false, Ordering::SeqCst) {
+            self.update_cfg();
+        }
+        match self.regs.tx_arb_mode() {
+            TxArbMode::RoundRobin => self.rr.arbit_by(req, cost),
+            TxArbMode::StrictPriority => self.sp.arbit_by(req, cost),
+            TxArbMode::Dwrr => self.dwrr.arbit_by(req, cost),
+            TxArbMode::Hybrid => self.hybrid.arbit_by(req, cost),
+        }
+    }
+}
+
+pub struct EthaTxSequencer {
     irqs: Arc<Mutex<IrqVec>>,
-    pub arbiter: A,
+    irq_table: Arc<IrqTable<CHS>>,
+    pub arbiter: EthaTxArbiter,
+    pub shaper: EthaTxShaper,
     pub chs: [EthaTxCh; CHS],
+    stats: Arc<StatsRegs<CHS>>,
+    phc: Arc<Phc>,
 }
-impl<A: Arbiter> EthaTxSequencer<A> {
+impl EthaTxSequencer {
     pub fn new(
-        arbiter: A,
         regs: &TopRegs<CHS, RX_ET_FILTERS, RX_TP5_FILTERS>,
         irqs: &Arc<Mutex<IrqVec>>,
     ) -> Self {
         let chs = array_init::array_init(|i| {
-            EthaTxCh::new(i, &regs.chs[i].tx, &mut irqs.lock().unwrap())
+            EthaTxCh::new(i, &regs.chs[i].tx, &regs.clock, &mut irqs.lock().unwrap())
         });
         EthaTxSequencer {
             irqs: irqs.clone(),
-            arbiter,
+            irq_table: regs.irq.clone(),
+            arbiter: EthaTxArbiter::new(&regs.global, &regs.tx_arb_dirty),
+            shaper: EthaTxShaper::new(&regs.tx, &regs.clock),
             chs,
+            stats: regs.stats.clone(),
+            phc: regs.phc.clone(),
         }
     }
     pub fn pipeline<'a>(&'a mut self) -> impl Pipeline<Input = (), Output = TxLoadInfo> + 'a {
-        EthaIrqs::new(&self.chs, &self.irqs)
-            .comb(EthaTxReqs(&self.chs))
-            .comb(EthaTxArbit(&mut self.arbiter))
-            .comb(EthaTxProcss(&self.chs))
+        EthaIrqs::new(&self.chs, &self.irq_table, &self.irqs)
+            .comb(EthaTxReqs(&self.chs, &self.shaper))
+            .comb(EthaTxArbit(&mut self.arbiter, &self.shaper))
+            .comb(EthaTxProcss(&self.chs, &self.stats, &self.phc))
     }
 }
 
-pub struct EthaTxReqs<'a>(&'a [EthaTxCh]);
+pub struct EthaTxReqs<'a>(&'a [EthaTxCh], &'a EthaTxShaper);
 
 impl<'a> Pipeline for EthaTxReqs<'a> {
     type Input = ();
     type Output = Vec<Option<TxReqDesc>>;
     fn execute(&mut self, _: &mut [u8], _: &Self::Input) -> Result<Self::Output> {
-        Ok(self.0.iter().map(|r| r.req()).collect::<_>())
+        let reqs = self.0.iter().map(|r| r.req()).collect::<Vec<_>>();
+        Ok(self.1.shape(&reqs))
     }
 }
 
-pub struct EthaTxArbit<'a, A: Arbiter>(&'a mut A);
+pub struct EthaTxArbit<'a, A: Arbiter>(&'a mut A, &'a EthaTxShaper);
 
 impl<'a, A: Arbiter> Pipeline for EthaTxArbit<'a, A> {
     type Input = Vec<Option<TxReqDesc>>;
     type Output = (usize, TxReqDesc);
     fn execute(&mut self, _: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
-        if let Some(r) = self.0.arbit(i) {
+        //dwrr arbiters are based on frame bytes
+        if let Some(r) = self.0.arbit_by(i, |r| r.frame.total_size() as usize) {
+            self.1.consume(r.0, r.1.frame.total_size() as usize);
             Ok(r)
         } else {
             Err(Error::Dropped)
@@ -75,7 +158,7 @@ impl<'a, A: Arbiter> Pipeline for EthaTxArbit<'a, A> {
     }
 }
 
-pub struct EthaTxProcss<'a>(&'a [EthaTxCh]);
+pub struct EthaTxProcss<'a>(&'a [EthaTxCh], &'a StatsRegs<CHS>, &'a Phc);
 impl<'a> Pipeline for EthaTxProcss<'a> {
     type Input = (usize, TxReqDesc);
     type Output = TxLoadInfo;
@@ -88,7 +171,10 @@ impl<'a> Pipeline for EthaTxProcss<'a> {
         );
         let _enter = span.enter();
         let r = EthaTxLoadFrame(self.0)
-            .comb(EthaTxStoreResp(self.0))
+            .comb(EthaTxVlanInsert)
+            .comb(EthaTxTso)
+            .comb(EthaTxCsumInsert)
+            .comb(EthaTxStoreResp(self.0, self.1, self.2))
             .execute(buffer, i);
         match &r {
             Ok(r) => tracing::event!(
@@ -117,18 +203,104 @@ impl<'a> Pipeline for EthaTxLoadFrame<'a> {
         let (id, req) = *i;
         let mut info = TxLoadInfo::default();
         info.len = req.frame.total_size() as usize;
-        info.too_large = info.len > buffer.len();
+        info.tso = req.ctrl.tso_en() == 1;
+        info.mss = req.ctrl.mss() as usize;
+        info.too_large = info.len > if info.tso { TSO_MAX_LEN } else { MAC_MAX_LEN };
         info.too_small = info.len < MIN_FRAME_LEN;
         info.resp_en = req.ctrl.resp_en() == 1;
+        info.l3_csum = req.ctrl.l3_csum_en() == 1;
+        info.l4_csum = req.ctrl.l4_csum_en() == 1;
+        info.ts = req.ctrl.ts_en() == 1;
+        if req.ctrl.vlan_insert() == 1 {
+            info.vlan_tci = Some(req.ctrl.vlan_tci() as u16);
+        }
         info.ch_id = id;
         if !info.dropped() {
             self.0[id].read(buffer);
+            info.seg_len = info.len;
+            info.segs = 1;
         }
         Ok(info)
     }
 }
 
-pub struct EthaTxStoreResp<'a>(&'a [EthaTxCh]);
+pub struct EthaTxVlanInsert;
+
+impl Pipeline for EthaTxVlanInsert {
+    type Input = TxLoadInfo;
+    type Output = TxLoadInfo;
+    fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        let tci = match i.vlan_tci {
+            Some(tci) if !i.dropped() => tci,
+            _ => return Ok(*i),
+        };
+        let mut info = *i;
+        if info.len + VLAN_SIZE > if info.tso { TSO_MAX_LEN } else { MAC_MAX_LEN } {
+            info.too_large = true;
+            info.segs = 0;
+            return Ok(info);
+        }
+        let etype_pos = ETHERNET_HEADER_LEN - 2;
+        buffer.copy_within(etype_pos..info.len, etype_pos + VLAN_SIZE);
+        buffer[etype_pos..etype_pos + 2].copy_from_slice(&VLAN_TYPE.to_be_bytes());
+        buffer[etype_pos + 2..etype_pos + VLAN_SIZE].copy_from_slice(&tci.to_be_bytes());
+        info.len += VLAN_SIZE;
+        info.seg_len = info.len;
+        tracing::event!(
+            target: STATICS_TAR,
+            logger::STATICS_LEVEL,
+            name = "tx vlan insert",
+            tci = tci
+        );
+        Ok(info)
+    }
+}
+
+pub struct EthaTxCsumInsert;
+
+impl Pipeline for EthaTxCsumInsert {
+    type Input = TxLoadInfo;
+    type Output = TxLoadInfo;
+    fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        if i.dropped() || !(i.l3_csum || i.l4_csum) {
+            return Ok(*i);
+        }
+        let frame = &mut buffer[..i.len];
+        //frames can not be parsed are sent as they are
+        if let Ok((l2, l3)) = L2Parser.comb(L3Parser).execute(frame, &()) {
+            let l3_buffer = &mut frame[l2.header_len..];
+            if i.l3_csum && l2.etype == EthernetProtocol::Ipv4 {
+                Ipv4Packet::new_unchecked(&mut l3_buffer[..]).fill_checksum();
+            }
+            if i.l4_csum && l3.header_len != 0 {
+                let l4_buffer = &mut l3_buffer[l3.header_len..l3.header_len + l3.payload_len];
+                match l3.protocol {
+                    IpProtocol::Tcp => {
+                        if let Ok(mut packet) = TcpPacket::new_checked(l4_buffer) {
+                            packet.fill_checksum(&l3.src, &l3.dst)
+                        }
+                    }
+                    IpProtocol::Udp => {
+                        if let Ok(mut packet) = UdpPacket::new_checked(l4_buffer) {
+                            packet.fill_checksum(&l3.src, &l3.dst)
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            tracing::event!(
+                target: STATICS_TAR,
+                logger::STATICS_LEVEL,
+                name = "tx csum insert",
+                l3 = i.l3_csum,
+                l4 = i.l4_csum
+            );
+        }
+        Ok(*i)
+    }
+}
+
+pub struct EthaTxStoreResp<'a>(&'a [EthaTxCh], &'a StatsRegs<CHS>, &'a Phc);
 
 impl<'a> Pipeline for EthaTxStoreResp<'a> {
     type Input = TxLoadInfo;
@@ -138,11 +310,27 @@ impl<'a> Pipeline for EthaTxStoreResp<'a> {
             let mut resp = TxResultDesc::default();
             resp.set_too_large(i.too_large as u32);
             resp.set_too_small(i.too_small as u32);
+            resp.set_segs(i.segs as u32);
+            //frames leave the model right after the resp is stored
+            if i.ts && !i.dropped() {
+                resp.set_ts_valid(1);
+                resp.ts = self.2.time().into();
+            }
             Some(resp)
         } else {
             None
         };
         self.0[i.ch_id].write_resp(&resp);
+        if i.too_large {
+            self.1.inc(StatsCounter::TxTooLarge);
+        }
+        if i.too_small {
+            self.1.inc(StatsCounter::TxTooSmall);
+        }
+        for seg in 0..i.segs {
+            self.1
+                .tx_frame(i.ch_id, std::cmp::min(i.seg_len, i.len - seg * i.seg_len));
+        }
         if i.dropped() {
             Err(Error::Dropped)
         } else {
@@ -0,0 +1,182 @@
+use super::desc::tx::TxReqDesc;
+use super::reg_if::tx::{LockedTxShaperRegs, TxRegs};
+use super::*;
+use crate::clock::ModelClock;
+use std::sync::{Arc, Mutex};
+
+//credits are in units of 1/1000 bit, so 1Mbps adds 1 credit per ns
+const CREDITS_PER_BYTE: u64 = 8000;
+
+#[derive(Debug, Copy, Clone)]
+struct TokenBucket {
+    rate: u64,
+    burst: u64,
+    credits: u64,
+    last: u64,
+}
+
+impl TokenBucket {
+    //buckets are full when enabled and unlimited when disabled
+    fn update<'a>(
+        bucket: &'a mut Option<TokenBucket>,
+        cfg: &LockedTxShaperRegs,
+        now: u64,
+    ) -> Option<&'a mut TokenBucket> {
+        if cfg.shaper_rate().en() == 0 {
+            *bucket = None;
+            return None;
+        }
+        let rate = cfg.shaper_rate().rate();
+        let burst = cfg.shaper_burst().burst() * CREDITS_PER_BYTE;
+        let b = bucket.get_or_insert(TokenBucket {
+            rate,
+            burst,
+            credits: burst,
+            last: now,
+        });
+        let refill = now.saturating_sub(b.last).saturating_mul(rate);
+        b.rate = rate;
+        b.burst = burst;
+        b.credits = std::cmp::min(burst, b.credits.saturating_add(refill));
+        b.last = now;
+        Some(b)
+    }
+
+    //frames larger than the burst size are sent with a full bucket
+    fn ready_at(&self, size: usize) -> u64 {
+        let need = std::cmp::min(size as u64 * CREDITS_PER_BYTE, self.burst);
+        if self.credits >= need {
+            self.last
+        } else if self.rate == 0 {
+            u64::MAX
+        } else {
+            self.last + (need - self.credits).div_ceil(self.rate)
+        }
+    }
+
+    fn consume(&mut self, size: usize) {
+        self.credits = self.credits.saturating_sub(size as u64 * CREDITS_PER_BYTE);
+    }
+}
+
+struct Buckets {
+    chs: [Option<TokenBucket>; CHS],
+    port: Option<TokenBucket>,
+}
+
+pub struct EthaTxShaper {
+    regs: Arc<TxRegs<CHS>>,
+    clock: Arc<ModelClock>,
+    buckets: Mutex<Buckets>,
+}
+
+impl EthaTxShaper {
+    pub fn new(regs: &Arc<TxRegs<CHS>>, clock: &Arc<ModelClock>) -> Self {
+        EthaTxShaper {
+            regs: regs.clone(),
+            clock: clock.clone(),
+            buckets: Mutex::new(Buckets {
+                chs: [None; CHS],
+                port: None,
+            }),
+        }
+    }
+
+    fn ready_at(&self, buckets: &mut Buckets, now: u64, id: usize, size: usize) -> u64 {
+        [
+            TokenBucket::update(&mut buckets.chs[id], &self.regs.shapers[id], now),
+            TokenBucket::update(&mut buckets.port, &self.regs.port_shaper, now),
+        ]
+        .iter()
+        .flatten()
+        .map(|b| b.ready_at(size))
+        .fold(now, std::cmp::max)
+    }
+
+    //requests of channels without enough tokens are masked
+    pub fn shape(&self, reqs: &[Option<TxReqDesc>]) -> Vec<Option<TxReqDesc>> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut now = self.clock.now();
+        let ready = reqs
+            .iter()
+            .enumerate()
+            .map(|(id, r)| {
+                r.map(|r| self.ready_at(&mut buckets, now, id, r.frame.total_size() as usize))
+            })
+            .collect::<Vec<_>>();
+        //virtual time jumps to the first channel getting ready
+        let next = ready.iter().flatten().min();
+        if let Some(t) = next.filter(|t| **t > now && **t != u64::MAX) {
+            self.clock.advance_to(*t);
+            now = self.clock.now();
+        }
+        reqs.iter()
+            .zip(ready.iter())
+            .map(|(r, t)| r.filter(|_| t.is_some_and(|t| t <= now)))
+            .collect()
+    }
+
+    pub fn consume(&self, id: usize, size: usize) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = self.clock.now();
+        if let Some(b) = TokenBucket::update(&mut buckets.chs[id], &self.regs.shapers[id], now) {
+            b.consume(size);
+        }
+        if let Some(b) = TokenBucket::update(&mut buckets.port, &self.regs.port_shaper, now) {
+            b.consume(size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::desc::buffer::FrameDesc;
+    use super::super::desc::tx::TxCtrlDesc;
+    use super::*;
+
+    fn req(size: usize) -> Option<TxReqDesc> {
+        let mut frame = FrameDesc::default();
+        frame.set_total_size(size as u32);
+        Some(TxReqDesc {
+            frame,
+            ctrl: TxCtrlDesc::default(),
+        })
+    }
+
+    #[test]
+    fn tx_shaper_test() {
+        let regs = Arc::new(TxRegs::<CHS>::new());
+        let clock = Arc::new(ModelClock::new());
+        clock.set_virtual(true);
+        let t0 = clock.now();
+        let shaper = EthaTxShaper::new(&regs, &clock);
+        let mut reqs = vec![None; CHS];
+        //8Mbps is 1 byte per us
+        regs.shapers[0].write(0, 1 << 31 | 8).unwrap();
+        regs.shapers[0].write(1, 512).unwrap();
+        reqs[0] = req(256);
+        reqs[1] = req(256);
+        assert_eq!(shaper.shape(&reqs).iter().flatten().count(), 2);
+        shaper.consume(0, 256);
+        shaper.consume(0, 256);
+        let r = shaper.shape(&reqs);
+        assert!(r[0].is_none() && r[1].is_some());
+        assert_eq!(clock.now(), t0);
+
+        //only shaped channels requesting
+        reqs[1] = None;
+        assert!(shaper.shape(&reqs)[0].is_some());
+        assert_eq!(clock.now(), t0 + 256_000);
+        shaper.consume(0, 256);
+
+        //port shaper applies to unshaped channels too
+        regs.port_shaper.write(0, 1 << 31 | 16).unwrap();
+        regs.port_shaper.write(1, 256).unwrap();
+        reqs[0] = None;
+        reqs[1] = req(256);
+        assert!(shaper.shape(&reqs)[1].is_some());
+        shaper.consume(1, 256);
+        assert!(shaper.shape(&reqs)[1].is_some());
+        assert_eq!(clock.now(), t0 + 256_000 + 128_000);
+    }
+}
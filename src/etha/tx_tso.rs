@@ -0,0 +1,135 @@
+use super::l2_parser::{L2Info, L2Parser};
+use super::l3_parser::{L3Info, L3Parser};
+use super::tx_sequencer::TxLoadInfo;
+use super::*;
+use crate::logger;
+use crate::mac::MAC_MAX_LEN;
+use smoltcp::wire::{EthernetProtocol, IpProtocol, Ipv4Packet, Ipv6Packet, TcpPacket};
+
+struct TsoLayout {
+    l2: L2Info,
+    l3: L3Info,
+    hdr_len: usize,
+    payload: usize,
+    mss: usize,
+}
+
+impl TsoLayout {
+    fn new(buffer: &mut [u8], i: &TxLoadInfo) -> Option<Self> {
+        if i.mss == 0 {
+            return None;
+        }
+        let (l2, l3) = L2Parser
+            .comb(L3Parser)
+            .execute(&mut buffer[..i.len], &())
+            .ok()?;
+        if l3.header_len == 0 || l3.protocol != IpProtocol::Tcp {
+            return None;
+        }
+        let l4_start = l2.header_len + l3.header_len;
+        let end = std::cmp::min(l4_start + l3.payload_len, i.len);
+        let tcp = TcpPacket::new_checked(&buffer[l4_start..end]).ok()?;
+        let hdr_len = l4_start + tcp.header_len() as usize;
+        let payload = end - hdr_len;
+        if payload <= i.mss {
+            return None;
+        }
+        Some(TsoLayout {
+            l2,
+            l3,
+            hdr_len,
+            payload,
+            mss: i.mss,
+        })
+    }
+    fn seg_len(&self) -> usize {
+        self.hdr_len + self.mss
+    }
+    fn segs(&self) -> usize {
+        self.payload.div_ceil(self.mss)
+    }
+    fn total_len(&self) -> usize {
+        self.segs() * self.hdr_len + self.payload
+    }
+    fn fits(&self, buffer_len: usize) -> bool {
+        self.seg_len() <= MAC_MAX_LEN && self.total_len() <= buffer_len
+    }
+    fn segment(&self, buffer: &mut [u8]) {
+        let (seg_len, segs, total) = (self.seg_len(), self.segs(), self.total_len());
+        let hdr = buffer[..self.hdr_len].to_vec();
+        //move payload chunks backward from the last one, so no chunk is overwritten before moved
+        for k in (1..segs).rev() {
+            let src = self.hdr_len + k * self.mss;
+            let len = std::cmp::min(self.mss, self.payload - k * self.mss);
+            buffer.copy_within(src..src + len, k * seg_len + self.hdr_len);
+            buffer[k * seg_len..k * seg_len + self.hdr_len].copy_from_slice(&hdr);
+        }
+        let l4_start = self.l2.header_len + self.l3.header_len;
+        let ident = if self.l2.etype == EthernetProtocol::Ipv4 {
+            Ipv4Packet::new_unchecked(&buffer[self.l2.header_len..]).ident()
+        } else {
+            0
+        };
+        let seq = TcpPacket::new_unchecked(&buffer[l4_start..]).seq_number();
+        for k in 0..segs {
+            let seg = &mut buffer[k * seg_len..std::cmp::min((k + 1) * seg_len, total)];
+            let l3_len = seg.len() - self.l2.header_len;
+            let l3_buffer = &mut seg[self.l2.header_len..];
+            if self.l2.etype == EthernetProtocol::Ipv4 {
+                let mut packet = Ipv4Packet::new_unchecked(&mut l3_buffer[..]);
+                packet.set_total_len(l3_len as u16);
+                packet.set_ident(ident.wrapping_add(k as u16));
+                packet.fill_checksum();
+            } else {
+                let mut packet = Ipv6Packet::new_unchecked(&mut l3_buffer[..]);
+                let header_len = packet.header_len();
+                packet.set_payload_len((l3_len - header_len) as u16);
+            }
+            let mut packet = TcpPacket::new_unchecked(&mut l3_buffer[self.l3.header_len..]);
+            packet.set_seq_number(seq + k * self.mss);
+            if k != segs - 1 {
+                packet.set_fin(false);
+                packet.set_psh(false);
+            }
+            packet.fill_checksum(&self.l3.src, &self.l3.dst);
+        }
+    }
+}
+
+pub struct EthaTxTso;
+
+impl Pipeline for EthaTxTso {
+    type Input = TxLoadInfo;
+    type Output = TxLoadInfo;
+    fn execute(&mut self, buffer: &mut [u8], i: &Self::Input) -> Result<Self::Output> {
+        if !i.tso || i.dropped() {
+            return Ok(*i);
+        }
+        let mut info = *i;
+        match TsoLayout::new(buffer, i) {
+            Some(layout) if layout.fits(buffer.len()) => {
+                layout.segment(buffer);
+                info.len = layout.total_len();
+                info.seg_len = layout.seg_len();
+                info.segs = layout.segs();
+                //checksums are already filled for each segment
+                info.l3_csum = false;
+                info.l4_csum = false;
+                tracing::event!(
+                    target: STATICS_TAR,
+                    logger::STATICS_LEVEL,
+                    name = "tx tso",
+                    mss = info.mss,
+                    segs = info.segs
+                );
+            }
+            Some(_) => info.too_large = true,
+            //frames can not be segmented are sent as they are
+            None => info.too_large = info.len > MAC_MAX_LEN,
+        }
+        if info.dropped() {
+            info.segs = 0;
+        }
+        Ok(info)
+    }
+}
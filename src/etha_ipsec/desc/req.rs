@@ -31,6 +31,7 @@ mod bitfields {
         pub resp_en, set_resp_en: 57, 57;
         pub aad_copy, set_aad_copy: 58, 58;
         pub iv_copy, set_iv_copy: 59, 59;
+        pub esp_en, set_esp_en: 60, 60;
     }
 }
 pub type IpsecFrameCfgDesc =
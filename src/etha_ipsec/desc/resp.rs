@@ -16,6 +16,11 @@ mod bitfields {
         pub invalid_session, set_invalid_session: 2, 2;
         pub ciper_err, set_ciper_err: 3, 3;
         pub auth_fail, set_auth_fail: 4, 4;
+        pub proto_err, set_proto_err: 5, 5;
+        pub replay_fail, set_replay_fail: 6, 6;
+        pub expired, set_expired: 7, 7;
+        pub seq_overflow, set_seq_overflow: 8, 8;
+        pub dst_len, set_dst_len: 63, 32;
     }
 }
 pub const IPSEC_RESULT_ENTRY_SIZE: usize = IPSEC_STATUS_ENTRY_SIZE;
@@ -29,6 +34,10 @@ impl IpsecStatusDesc {
             || self.invalid_session() != 0
             || self.ciper_err() != 0
             || self.auth_fail() != 0
+            || self.proto_err() != 0
+            || self.replay_fail() != 0
+            || self.expired() != 0
+            || self.seq_overflow() != 0
     }
 }
 #[desc_gen(padding_to = IPSEC_RESULT_ENTRY_SIZE)]
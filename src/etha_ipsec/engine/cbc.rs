@@ -1,13 +1,34 @@
-use super::IpsecEngineOpts;
+use super::{IpsecContext, IpsecEngineOpts};
 use aes::{
-    cipher::{block_padding::NoPadding, BlockDecryptMut, BlockEncryptMut, KeyIvInit},
-    Aes128, Aes256,
+    cipher::{
+        block_padding::NoPadding, BlockDecryptMut, BlockEncrypt, BlockEncryptMut, KeyInit,
+        KeyIvInit,
+    },
+    Aes128, Aes256, Block,
 };
 use cbc::{Decryptor, Encryptor};
 type Aes128CbcEnc = Encryptor<Aes128>;
 type Aes128CbcDec = Decryptor<Aes128>;
 type Aes256CbcEnc = Encryptor<Aes256>;
 type Aes256CbcDec = Decryptor<Aes256>;
+
+//a cbc iv has to be unpredictable, rfc3602 2.3, so the counter block is encrypted
+//under the sa key as in nist sp800-38a appendix c
+pub(super) fn aes_cbc_encrypt_iv(context: &IpsecContext, iv: &mut [u8]) {
+    if iv.len() != 16 {
+        return;
+    }
+    let key = context.aes_key.as_ref().unwrap();
+    if context.cipher_key_len() == 256 / 8 {
+        Aes256::new_from_slice(key)
+            .unwrap()
+            .encrypt_block(Block::from_mut_slice(iv))
+    } else {
+        Aes128::new_from_slice(key)
+            .unwrap()
+            .encrypt_block(Block::from_mut_slice(iv))
+    }
+}
 impl<'a> IpsecEngineOpts<'a> {
     pub(super) fn aes_cbc(&mut self) {
         let iv = self.iv();
@@ -0,0 +1,64 @@
+use super::IpsecEngineOpts;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305,
+};
+impl<'a> IpsecEngineOpts<'a> {
+    pub(super) fn chacha20_poly1305(&mut self) {
+        if self.context.icv_len() != 16 {
+            self.status.set_invalid_session(1);
+            println!(
+                "Ipsec Engine: chacha20-poly1305 Error! Invaid icv_len {}, valid value is 16",
+                self.context.icv_len()
+            );
+            return;
+        }
+        let chacha =
+            ChaCha20Poly1305::new_from_slice(self.context.aes_key.as_ref().unwrap()).unwrap();
+        //rfc7634, 4 bytes salt and 8 bytes iv
+        let nonce = self.iv();
+        if nonce.len() != 12 {
+            self.status.set_ciper_err(1);
+            println!(
+                "Ipsec Engine: chacha20-poly1305 Error! Nonce len is {}, which is expect to be 12",
+                nonce.len()
+            );
+            return;
+        }
+        if self.cfg.cfg.encrypt() == 1 {
+            chacha
+                .encrypt(
+                    (&nonce[..]).into(),
+                    Payload {
+                        aad: self.src_aad(),
+                        msg: self.src_text(),
+                    },
+                )
+                .map(|r| {
+                    let text_len = self.cfg.cfg.text_len() as usize;
+                    self.set_dst_text(&r[..text_len])
+                        .set_dst_icv(&r[text_len..]);
+                })
+                .unwrap_or_else(|e| {
+                    self.status.set_ciper_err(1);
+                    println!("Ipsec Engine: chacha20-poly1305 Error!{:?}", e);
+                })
+        } else {
+            chacha
+                .decrypt(
+                    (&nonce[..]).into(),
+                    Payload {
+                        aad: self.src_aad(),
+                        msg: &[self.src_text(), self.src_icv()].concat(),
+                    },
+                )
+                .map(|r| {
+                    self.set_dst_text(&r);
+                })
+                .unwrap_or_else(|e| {
+                    self.status.set_auth_fail(1);
+                    tracing::debug!(target : "ipsec-engine-chacha", "Ipsec Engine: chacha20-poly1305 auth failed!{:?}", e);
+                })
+        }
+    }
+}
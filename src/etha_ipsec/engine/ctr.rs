@@ -0,0 +1,41 @@
+use super::IpsecEngineOpts;
+use aes::{
+    cipher::{KeyIvInit, StreamCipher},
+    Aes128, Aes256,
+};
+type Aes128Ctr = ctr::Ctr32BE<Aes128>;
+type Aes256Ctr = ctr::Ctr32BE<Aes256>;
+impl<'a> IpsecEngineOpts<'a> {
+    pub(super) fn aes_ctr(&mut self) {
+        //rfc3686, 4 bytes salt as nonce, 8 bytes iv and a block counter from 1
+        let block = [&self.iv()[..], &[0, 0, 0, 1]].concat();
+        if block.len() != 16 {
+            self.status.set_ciper_err(1);
+            println!(
+                "Ipsec Engine: ctr Error! Nonce len is {}, which is expect to be 12",
+                block.len() - 4
+            );
+            return;
+        }
+        if self.context.cipher_key_len() == 256 / 8 {
+            self.do_aes_ctr(
+                Aes256Ctr::new_from_slices(self.context.aes_key.as_ref().unwrap(), &block).unwrap(),
+            )
+        } else {
+            self.do_aes_ctr(
+                Aes128Ctr::new_from_slices(self.context.aes_key.as_ref().unwrap(), &block).unwrap(),
+            )
+        }
+    }
+    fn do_aes_ctr<AES: StreamCipher>(&mut self, mut aes: AES) {
+        let mut text = self.src_text().to_vec();
+        if self.cfg.cfg.encrypt() == 1 {
+            aes.apply_keystream(&mut text);
+            self.set_dst_text(&text);
+            self.hmac_digest();
+        } else if self.hmac_verify() {
+            aes.apply_keystream(&mut text);
+            self.set_dst_text(&text);
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use super::*;
+use smoltcp::wire::{IpProtocol, Ipv4Address, Ipv4Packet, Ipv6Address, Ipv6Packet};
+
+const ESP_HEADER_LEN: usize = 8;
+const ESP_TRAILER_LEN: usize = 2;
+const ESP_NEXT_IPV4: u8 = 4;
+const ESP_NEXT_IPV6: u8 = 41;
+const ESP_HOP_LIMIT: u8 = 64;
+const IPV4_HEADER_LEN: usize = 20;
+const IPV6_HEADER_LEN: usize = 40;
+
+//header length, next header and total length of an ip packet
+fn ip_header(packet: &[u8]) -> Option<(usize, u8, usize)> {
+    match packet.first()? >> 4 {
+        4 => {
+            let ip = Ipv4Packet::new_checked(packet).ok()?;
+            Some((
+                ip.header_len() as usize,
+                ip.protocol().into(),
+                ip.total_len() as usize,
+            ))
+        }
+        6 => {
+            let ip = Ipv6Packet::new_checked(packet).ok()?;
+            Some((
+                ip.header_len(),
+                ip.next_header().into(),
+                ip.header_len() + ip.payload_len() as usize,
+            ))
+        }
+        _ => None,
+    }
+}
+
+//extension headers of ipv6 are not walked, they are protected as payload
+fn ip_fixup(hdr: &mut [u8], next: u8, payload_len: usize) {
+    if hdr[0] >> 4 == 4 {
+        let mut ip = Ipv4Packet::new_unchecked(hdr);
+        ip.set_protocol(next.into());
+        ip.set_total_len((ip.header_len() as usize + payload_len) as u16);
+        ip.fill_checksum();
+    } else {
+        let mut ip = Ipv6Packet::new_unchecked(hdr);
+        ip.set_next_header(next.into());
+        ip.set_payload_len(payload_len as u16);
+    }
+}
+
+fn tunnel_header(sa: &SecSessionRegs) -> Vec<u8> {
+    let (src, dst) = (sa.ext.tunnel_src(), sa.ext.tunnel_dst());
+    if sa.ext.esp_ctrl().tunnel_ip() == 1 {
+        let mut hdr = vec![0; IPV6_HEADER_LEN];
+        let mut ip = Ipv6Packet::new_unchecked(&mut hdr[..]);
+        ip.set_version(6);
+        ip.set_hop_limit(ESP_HOP_LIMIT);
+        ip.set_src_addr(Ipv6Address::from_bytes(&src));
+        ip.set_dst_addr(Ipv6Address::from_bytes(&dst));
+        hdr
+    } else {
+        let mut hdr = vec![0; IPV4_HEADER_LEN];
+        let mut ip = Ipv4Packet::new_unchecked(&mut hdr[..]);
+        ip.set_version(4);
+        ip.set_header_len(IPV4_HEADER_LEN as u8);
+        ip.set_hop_limit(ESP_HOP_LIMIT);
+        ip.set_src_addr(Ipv4Address::from_bytes(&src[..4]));
+        ip.set_dst_addr(Ipv4Address::from_bytes(&dst[..4]));
+        hdr
+    }
+}
+
+fn is_aead(context: &IpsecContext) -> bool {
+    matches!(
+        (context.cipher_alg, context.cipher_mode),
+        (
+            CipherAlg::AES128 | CipherAlg::AES256,
+            CipherMode::GCM | CipherMode::CCM | CipherMode::GMAC
+        ) | (CipherAlg::ChaCha20, CipherMode::Poly1305)
+    )
+}
+
+//the esp header, and the iv for non aead modes and gmac, are authenticated as aad,
+//with esn aead headers carry the high sequence bits between spi and sequence number
+fn esp_cfg(
+    context: &IpsecContext,
+    cfg: &IpsecFrameCfgDesc,
+    hdr_len: usize,
+    text_len: usize,
+    encrypt: bool,
+) -> IpsecCfgDesc {
+    let iv_len = context.iv_len();
+    let aad_len = if is_aead(context) && !matches!(context.cipher_mode, CipherMode::GMAC) {
+        hdr_len
+    } else {
+        hdr_len + iv_len
+    };
+    let mut fmt = IpsecFrameFmtDesc::default();
+    fmt.set_iv_offset(hdr_len as u32);
+    fmt.set_text_offset((hdr_len + iv_len) as u32);
+    fmt.set_icv_offset((hdr_len + iv_len + text_len) as u32);
+    let mut esp_cfg = IpsecFrameCfgDesc::default();
+    esp_cfg.set_session_id(cfg.session_id());
+    esp_cfg.set_aad_len(aad_len as u32);
+    esp_cfg.set_text_len(text_len as u32);
+    if encrypt {
+        esp_cfg.set_encrypt(1);
+        esp_cfg.set_aad_copy(1);
+        esp_cfg.set_iv_copy(1);
+        IpsecCfgDesc {
+            src: fmt,
+            dst: fmt,
+            cfg: esp_cfg,
+        }
+    } else {
+        IpsecCfgDesc {
+            src: fmt,
+            dst: IpsecFrameFmtDesc::default(),
+            cfg: esp_cfg,
+        }
+    }
+}
+
+impl IpsecEngine {
+    //returns the length of the protected ip packet
+    pub(super) fn esp(
+        &self,
+        context: &IpsecContext,
+        sa: &SecSessionRegs,
+        cfg: &IpsecFrameCfgDesc,
+        src: &[u8],
+        dst: &mut [u8],
+        status: &mut IpsecStatusDesc,
+    ) -> Option<usize> {
+        let packet = if cfg.encrypt() == 1 {
+            Self::esp_encap(context, sa, cfg, src, status)
+        } else {
+            Self::esp_decap(context, sa, cfg, src, status).map(|p| {
+                let len = p.len();
+                (p, len)
+            })
+        };
+        if let Some((packet, len)) = packet {
+            if packet.len() > dst.len() {
+                println!("Ipsec engine: Warning! esp packet size is bigger than dst buffer size!");
+                status.set_dst_err(1);
+                None
+            } else {
+                dst[..packet.len()].copy_from_slice(&packet);
+                status.set_dst_len(packet.len() as u32);
+                Some(len)
+            }
+        } else {
+            if !status.is_err() {
+                status.set_proto_err(1);
+            }
+            None
+        }
+    }
+
+    fn esp_encap(
+        context: &IpsecContext,
+        sa: &SecSessionRegs,
+        cfg: &IpsecFrameCfgDesc,
+        src: &[u8],
+        status: &mut IpsecStatusDesc,
+    ) -> Option<(Vec<u8>, usize)> {
+        let (hdr_len, next, len) = ip_header(src)?;
+        let (mut outer, inner, next) = if sa.ext.esp_ctrl().get_mode() == EspMode::Tunnel {
+            let next = if src[0] >> 4 == 4 {
+                ESP_NEXT_IPV4
+            } else {
+                ESP_NEXT_IPV6
+            };
+            (tunnel_header(sa), &src[..len], next)
+        } else {
+            (src[..hdr_len].to_vec(), &src[hdr_len..len], next)
+        };
+        //payload and trailer are aligned to the cipher block, or to 4 bytes
+        let align = match (context.cipher_alg, context.cipher_mode) {
+            (CipherAlg::AES128 | CipherAlg::AES256, CipherMode::CBC) => 16,
+            _ => 4,
+        };
+        let pad = (align - (inner.len() + ESP_TRAILER_LEN) % align) % align;
+        let text_len = inner.len() + pad + ESP_TRAILER_LEN;
+
+        let esn = sa.ext.esp_ctrl().esn() == 1;
+        let seq = (sa.ext.esp_seq_hi().seq() << 32 | sa.ext.esp_seq().seq()).wrapping_add(1);
+        //the sequence number never cycles, a new sa has to be set up
+        if seq == 0 || !esn && seq > u32::MAX as u64 {
+            status.set_seq_overflow(1);
+            return None;
+        }
+        sa.ext.esp_seq_mut().set_seq(seq & 0xffff_ffff);
+        sa.ext.esp_seq_hi_mut().set_seq(seq >> 32);
+        //the iv is derived from the sequence number, so it is unique in the sa,
+        //and it is encrypted for cbc, which needs an unpredictable iv
+        let iv_len = context.iv_len();
+        let n = std::cmp::min(iv_len, 8);
+        let mut iv = vec![0; iv_len];
+        iv[iv_len - n..].copy_from_slice(&seq.to_be_bytes()[8 - n..]);
+        if let (CipherAlg::AES128 | CipherAlg::AES256, CipherMode::CBC) =
+            (context.cipher_alg, context.cipher_mode)
+        {
+            super::cbc::aes_cbc_encrypt_iv(context, &mut iv);
+        }
+
+        let spi = (sa.ext.esp_spi().get() as u32).to_be_bytes();
+        let seq_hi = ((seq >> 32) as u32).to_be_bytes();
+        let seq_lo = (seq as u32).to_be_bytes();
+        let aead = is_aead(context);
+        let hdr = if esn && aead {
+            [spi, seq_hi, seq_lo].concat()
+        } else {
+            [spi, seq_lo].concat()
+        };
+        let esp = [
+            &hdr[..],
+            &iv,
+            inner,
+            &(1..=pad as u8).collect::<Vec<_>>(),
+            &[pad as u8, next],
+        ]
+        .concat();
+        let esp_cfg = esp_cfg(context, cfg, hdr.len(), text_len, true);
+        let mut esp_dst = vec![0; hdr.len() + iv_len + text_len + context.icv_len()];
+        IpsecEngineOpts {
+            context,
+            cfg: &esp_cfg,
+            src: &esp,
+            dst: &mut esp_dst,
+            status,
+            auth_tail: if esn && !aead { &seq_hi } else { &[] },
+        }
+        .xform();
+        if status.is_err() {
+            return None;
+        }
+        if esn && aead {
+            esp_dst.drain(4..8);
+        }
+        ip_fixup(&mut outer, IpProtocol::IpSecEsp.into(), esp_dst.len());
+        Some(([outer, esp_dst].concat(), len))
+    }
+
+    fn esp_decap(
+        context: &IpsecContext,
+        sa: &SecSessionRegs,
+        cfg: &IpsecFrameCfgDesc,
+        src: &[u8],
+        status: &mut IpsecStatusDesc,
+    ) -> Option<Vec<u8>> {
+        let (hdr_len, next, len) = ip_header(src)?;
+        if next != u8::from(IpProtocol::IpSecEsp) {
+            return None;
+        }
+        let esp = &src[hdr_len..len];
+        let overhead = ESP_HEADER_LEN + context.iv_len() + context.icv_len();
+        if esp.len() < overhead + ESP_TRAILER_LEN {
+            return None;
+        }
+        if u32::from_be_bytes(esp[..4].try_into().unwrap()) != sa.ext.esp_spi().get() as u32 {
+            status.set_invalid_session(1);
+            return None;
+        }
+        let esn = sa.ext.esp_ctrl().esn() == 1;
+        let seq_lo = u32::from_be_bytes(esp[4..8].try_into().unwrap());
+        let seq = if esn {
+            sa.esn_seq(seq_lo)
+        } else {
+            seq_lo as u64
+        };
+        if !sa.replay_check(seq) {
+            status.set_replay_fail(1);
+            return None;
+        }
+        let seq_hi = ((seq >> 32) as u32).to_be_bytes();
+        let aead = is_aead(context);
+        let (esp, esp_hdr_len) = if esn && aead {
+            ([&esp[..4], &seq_hi, &esp[4..]].concat(), ESP_HEADER_LEN + 4)
+        } else {
+            (esp.to_vec(), ESP_HEADER_LEN)
+        };
+        let text_len = esp.len() - esp_hdr_len - context.iv_len() - context.icv_len();
+        let esp_cfg = esp_cfg(context, cfg, esp_hdr_len, text_len, false);
+        let mut text = vec![0; text_len];
+        IpsecEngineOpts {
+            context,
+            cfg: &esp_cfg,
+            src: &esp,
+            dst: &mut text,
+            status,
+            auth_tail: if esn && !aead { &seq_hi } else { &[] },
+        }
+        .xform();
+        if status.is_err() {
+            return None;
+        }
+        sa.replay_update(seq);
+
+        let pad = text[text_len - 2] as usize;
+        let next = text[text_len - 1];
+        if pad + ESP_TRAILER_LEN > text_len
+            || !text[text_len - ESP_TRAILER_LEN - pad..text_len - ESP_TRAILER_LEN]
+                .iter()
+                .copied()
+                .eq(1..=pad as u8)
+        {
+            return None;
+        }
+        let inner = &text[..text_len - ESP_TRAILER_LEN - pad];
+        if sa.ext.esp_ctrl().get_mode() == EspMode::Tunnel {
+            if next != ESP_NEXT_IPV4 && next != ESP_NEXT_IPV6 {
+                return None;
+            }
+            let (_, _, len) = ip_header(inner)?;
+            Some(inner[..len].to_vec())
+        } else {
+            let mut hdr = src[..hdr_len].to_vec();
+            ip_fixup(&mut hdr, next, inner.len());
+            Some([&hdr[..], inner].concat())
+        }
+    }
+}
@@ -15,6 +15,17 @@ impl<'a> IpsecEngineOpts<'a> {
             )
         }
     }
+    pub(super) fn aes_gmac(&mut self) {
+        if self.context.cipher_key_len() == 256 / 8 {
+            self.do_aes_gmac(
+                Aes256Gcm::new_from_slice(self.context.aes_key.as_ref().unwrap()).unwrap(),
+            )
+        } else {
+            self.do_aes_gmac(
+                Aes128Gcm::new_from_slice(self.context.aes_key.as_ref().unwrap()).unwrap(),
+            )
+        }
+    }
     fn do_aes_gcm<AES: Aead>(&mut self, aes: AES) {
         let nonce = self.iv();
         if nonce.len() != 12 {
@@ -59,4 +70,56 @@ impl<'a> IpsecEngineOpts<'a> {
             })
         }
     }
+    //rfc4543, text is authenticated after the aad and is not encrypted
+    fn do_aes_gmac<AES: Aead>(&mut self, aes: AES) {
+        if self.context.icv_len() != 16 {
+            self.status.set_invalid_session(1);
+            println!(
+                "Ipsec Engine: gmac Error! Invaid icv_len {}, valid value is 16",
+                self.context.icv_len()
+            );
+            return;
+        }
+        let nonce = self.iv();
+        if nonce.len() != 12 {
+            self.status.set_ciper_err(1);
+            println!(
+                "Ipsec Engine: gmac Error! Nonce len is {}, which is expect to be 12",
+                nonce.len()
+            );
+            return;
+        }
+        let aad = [self.src_aad(), self.src_text()].concat();
+        if self.cfg.cfg.encrypt() == 1 {
+            aes.encrypt(
+                (&nonce[..]).into(),
+                Payload {
+                    aad: &aad,
+                    msg: &[],
+                },
+            )
+            .map(|r| {
+                self.copy_text().set_dst_icv(&r);
+            })
+            .unwrap_or_else(|e| {
+                self.status.set_ciper_err(1);
+                println!("Ipsec Engine: gmac Error!{:?}", e);
+            })
+        } else {
+            aes.decrypt(
+                (&nonce[..]).into(),
+                Payload {
+                    aad: &aad,
+                    msg: self.src_icv(),
+                },
+            )
+            .map(|_| {
+                self.copy_text();
+            })
+            .unwrap_or_else(|e| {
+                self.status.set_auth_fail(1);
+                tracing::debug!(target : "ipsec-engine-gcm", "Ipsec Engine: gmac auth failed!{:?}", e);
+            })
+        }
+    }
 }
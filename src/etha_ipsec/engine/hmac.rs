@@ -6,48 +6,63 @@ type HmacSha1 = Hmac<Sha1>;
 type HmacSha256 = Hmac<Sha256>;
 type HmacSha512 = Hmac<Sha512_256>;
 impl<'a> IpsecEngineOpts<'a> {
+    //the icv is over aad and text, esp authenticates its iv by putting it in the aad
     pub(super) fn hmac_digest(&mut self) {
         match self.context.hmac_alg {
             HmacAlg::SHA1 => {
                 let mut mac =
                     HmacSha1::new_from_slice(self.context.hash_key.as_ref().unwrap()).unwrap();
-                mac.update(&[self.dst_aad(), self.dst_text()].concat());
-                self.set_dst_icv(&mac.finalize().into_bytes());
+                mac.update(&[self.src_aad(), self.dst_text(), self.auth_tail].concat());
+                self.set_hmac_icv(&mac.finalize().into_bytes());
             }
             HmacAlg::SHA256 => {
                 let mut mac =
                     HmacSha256::new_from_slice(self.context.hash_key.as_ref().unwrap()).unwrap();
-                mac.update(&[self.dst_aad(), self.dst_text()].concat());
-                self.set_dst_icv(&mac.finalize().into_bytes());
+                mac.update(&[self.src_aad(), self.dst_text(), self.auth_tail].concat());
+                self.set_hmac_icv(&mac.finalize().into_bytes());
             }
             HmacAlg::SHA512 => {
                 let mut mac =
                     HmacSha512::new_from_slice(self.context.hash_key.as_ref().unwrap()).unwrap();
-                mac.update(&[self.dst_aad(), self.dst_text()].concat());
-                self.set_dst_icv(&mac.finalize().into_bytes());
+                mac.update(&[self.src_aad(), self.dst_text(), self.auth_tail].concat());
+                self.set_hmac_icv(&mac.finalize().into_bytes());
             }
             _ => {}
         };
     }
+    //the icv is the leftmost bytes of the digest, e.g. HMAC-SHA-256-128 of rfc4868
+    fn set_hmac_icv(&mut self, digest: &[u8]) {
+        let icv_len = self.context.icv_len();
+        if icv_len > digest.len() {
+            self.status.set_invalid_session(1);
+            println!(
+                "Ipsec Engine: hmac Error! Invaid icv_len {}, max value is {}",
+                icv_len,
+                digest.len()
+            );
+        } else {
+            self.set_dst_icv(&digest[..icv_len]);
+        }
+    }
     pub(super) fn hmac_verify(&mut self) -> bool {
         if match self.context.hmac_alg {
             HmacAlg::SHA1 => {
                 let mut mac =
                     HmacSha1::new_from_slice(self.context.hash_key.as_ref().unwrap()).unwrap();
-                mac.update(&[self.src_aad(), self.src_text()].concat());
-                mac.verify_slice(&self.src_icv())
+                mac.update(&[self.src_aad(), self.src_text(), self.auth_tail].concat());
+                mac.verify_truncated_left(&self.src_icv())
             }
             HmacAlg::SHA256 => {
                 let mut mac =
                     HmacSha256::new_from_slice(self.context.hash_key.as_ref().unwrap()).unwrap();
-                mac.update(&[self.src_aad(), self.src_text()].concat());
-                mac.verify_slice(&self.src_icv())
+                mac.update(&[self.src_aad(), self.src_text(), self.auth_tail].concat());
+                mac.verify_truncated_left(&self.src_icv())
             }
             HmacAlg::SHA512 => {
                 let mut mac =
                     HmacSha512::new_from_slice(self.context.hash_key.as_ref().unwrap()).unwrap();
-                mac.update(&[self.src_aad(), self.src_text()].concat());
-                mac.verify_slice(&self.src_icv())
+                mac.update(&[self.src_aad(), self.src_text(), self.auth_tail].concat());
+                mac.verify_truncated_left(&self.src_icv())
             }
             _ => Ok(()),
         }
@@ -9,6 +9,9 @@ use std::io::Read;
 use std::sync::Arc;
 mod cbc;
 mod ccm;
+mod chacha;
+mod ctr;
+mod esp;
 mod gcm;
 mod hmac;
 mod null;
@@ -18,6 +21,8 @@ struct IpsecEngineOpts<'a> {
     src: &'a [u8],
     dst: &'a mut [u8],
     status: &'a mut IpsecStatusDesc,
+    //authenticated after the text by hmac but not carried in src or dst
+    auth_tail: &'a [u8],
 }
 impl<'a> IpsecEngineOpts<'a> {
     fn src_aad(&'a self) -> &'a [u8] {
@@ -67,10 +72,6 @@ impl<'a> IpsecEngineOpts<'a> {
             true
         }
     }
-    fn dst_aad(&'a self) -> &'a [u8] {
-        &self.dst[self.cfg.dst.aad_offset() as usize
-            ..self.cfg.dst.aad_offset() as usize + self.cfg.cfg.aad_len() as usize]
-    }
     fn dst_text(&'a self) -> &'a [u8] {
         &self.dst[self.cfg.dst.text_offset() as usize
             ..self.cfg.dst.text_offset() as usize + self.cfg.cfg.text_len() as usize]
@@ -166,8 +167,11 @@ impl<'a> IpsecEngineOpts<'a> {
                 CipherMode::GCM => self.aes_gcm(),
                 CipherMode::CCM => self.aes_ccm(),
                 CipherMode::CBC => self.aes_cbc(),
+                CipherMode::CTR => self.aes_ctr(),
+                CipherMode::GMAC => self.aes_gmac(),
                 _ => unreachable!(),
             },
+            CipherAlg::ChaCha20 => self.chacha20_poly1305(),
             CipherAlg::Null => self.null(),
             _ => unreachable!(),
         }
@@ -225,16 +229,33 @@ impl IpsecEngine {
             .expect("Ipsec engine: dst sync error!");
         dst.truncate(req.dst.total_size() as usize);
         tracing::debug!(target : "ipsec-engine", "load dst!");
-        if let Some(context) = self.cache.get_context(req.cfg.cfg.session_id() as usize) {
-            let mut opts = IpsecEngineOpts {
-                context: &context,
-                cfg: &req.cfg,
-                src: &src,
-                dst: &mut dst,
-                status: &mut status,
-            };
-            tracing::debug!(target : "ipsec-engine", "begin xform!");
-            opts.xform();
+        let session = req.cfg.cfg.session_id() as usize;
+        if let Some(context) = self.cache.get_context(session) {
+            let sa = self.cache.get_session(session).unwrap();
+            if sa.hard_expired() {
+                status.set_expired(1);
+            } else if req.cfg.cfg.esp_en() == 1 {
+                tracing::debug!(target : "ipsec-engine", "begin esp!");
+                if let Some(bytes) =
+                    self.esp(&context, sa, &req.cfg.cfg, &src, &mut dst, &mut status)
+                {
+                    sa.account(bytes as u64);
+                }
+            } else {
+                let mut opts = IpsecEngineOpts {
+                    context: &context,
+                    cfg: &req.cfg,
+                    src: &src,
+                    dst: &mut dst,
+                    status: &mut status,
+                    auth_tail: &[],
+                };
+                tracing::debug!(target : "ipsec-engine", "begin xform!");
+                opts.xform();
+                if !status.is_err() {
+                    sa.account(req.cfg.cfg.text_len() as u64);
+                }
+            }
         } else {
             status.set_invalid_session(1);
         }
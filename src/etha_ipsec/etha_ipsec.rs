@@ -47,6 +47,7 @@ impl<A: Arbiter + Send + 'static> EthaIpsec<A> {
 mod tests {
     use super::tests_driver_helper::*;
     use super::*;
+    use crate::tests_frame_helper::*;
     use hex_literal::hex;
     macro_rules! algm_test {
         (
@@ -80,6 +81,7 @@ mod tests {
                 let iv = $iv;
                 let sa =
                     driver.alloc_session(&$key, &$salt, &$hash_key, iv.len(), $alg, $mode, $hmac);
+                sa.icv_len(icv.len());
                 let mut cipher_result: Vec<u8> = vec![0; iv.len() + plaintext.len() + icv.len()];
                 let r = ch0.xform(
                     &[&aad[..], &iv[..], &plaintext[..]],
@@ -241,6 +243,533 @@ mod tests {
             iv: hex!("84f4f13990750a9e")
         )
     );
+
+    //testvector from https://www.rfc-editor.org/rfc/rfc7539 Section 2.8.2
+    algm_test!(
+        chacha20_poly1305_test(
+            key: hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f"),
+            salt: hex!("07000000"),
+            hash_key: [],
+            alg: CipherAlg::ChaCha20,
+            mode: CipherMode::Poly1305,
+            hmac: HmacAlg::Null,
+            pt: *b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.",
+            aad: hex!("50515253c0c1c2c3c4c5c6c7"),
+            ct: hex!(
+                "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6
+                3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36
+                92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc
+                3ff4def08e4b7a9de576d26586cec64b6116"
+            ),
+            icv: hex!("1ae10b594f09e26a7e902ecbd0600691"),
+            iv: hex!("4041424344454647")
+        )
+    );
+
+    //testvector from https://www.rfc-editor.org/rfc/rfc3686 Test Vector #2
+    algm_test!(
+        ctr_test(
+            key: hex!("7E 24 06 78 17 FA E0 D7 43 D6 CE 1F 32 53 91 63"),
+            salt: hex!("00 6C B6 DB"),
+            hash_key: [],
+            alg: CipherAlg::AES128,
+            mode: CipherMode::CTR,
+            hmac: HmacAlg::Null,
+            pt: hex!(
+                "00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F
+                10 11 12 13 14 15 16 17 18 19 1A 1B 1C 1D 1E 1F"
+            ),
+            aad: [],
+            ct: hex!(
+                "51 04 A1 06 16 8A 72 D9 79 0D 41 EE 8E DA D3 88
+                EB 2E 1E FC 46 DA 57 C8 FC E6 30 DF 91 41 BE 28"
+            ),
+            icv: [],
+            iv: hex!("C0 54 3B 59 DA 48 D9 0B")
+        )
+    );
+
+    //testvector from https://www.rfc-editor.org/rfc/rfc3686 Test Vector #7
+    algm_test!(
+        ctr256_test(
+            key: hex!(
+                "77 6B EF F2 85 1D B0 6F 4C 8A 05 42 C8 69 6F 6C
+                6A 81 AF 1E EC 96 B4 D3 7F C1 D6 89 E6 C1 C1 04"
+            ),
+            salt: hex!("00 00 00 60"),
+            hash_key: [],
+            alg: CipherAlg::AES256,
+            mode: CipherMode::CTR,
+            hmac: HmacAlg::Null,
+            pt: *b"Single block msg",
+            aad: [],
+            ct: hex!("14 5A D0 1D BF 82 4E C7 56 08 63 DC 71 E3 E0 C0"),
+            icv: [],
+            iv: hex!("DB 56 72 C9 7A A8 F0 B2")
+        )
+    );
+
+    //ciphertext of rfc3686 Test Vector #2, the HMAC-SHA1-96 icv of rfc2404 is
+    //over the aad and the ciphertext, the iv is only authenticated by esp
+    algm_test!(
+        ctr_hmac_test(
+            key: hex!("7E 24 06 78 17 FA E0 D7 43 D6 CE 1F 32 53 91 63"),
+            salt: hex!("00 6C B6 DB"),
+            hash_key: [0xc; 16],
+            alg: CipherAlg::AES128,
+            mode: CipherMode::CTR,
+            hmac: HmacAlg::SHA1,
+            pt: hex!(
+                "00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F
+                10 11 12 13 14 15 16 17 18 19 1A 1B 1C 1D 1E 1F"
+            ),
+            aad: hex!("00000200 00000001"),
+            ct: hex!(
+                "51 04 A1 06 16 8A 72 D9 79 0D 41 EE 8E DA D3 88
+                EB 2E 1E FC 46 DA 57 C8 FC E6 30 DF 91 41 BE 28"
+            ),
+            icv: hex!("a540f54f9f2cb6528420cf5b"),
+            iv: hex!("C0 54 3B 59 DA 48 D9 0B")
+        )
+    );
+
+    //testvector from https://www.rfc-editor.org/rfc/rfc3686 Test Vector #7
+    algm_test!(
+        ctr256_test(
+            key: hex!(
+                "77 6B EF F2 85 1D B0 6F 4C 8A 05 42 C8 69 6F 6C
+                6A 81 AF 1E EC 96 B4 D3 7F C1 D6 89 E6 C1 C1 04"
+            ),
+            salt: hex!("00 00 00 60"),
+            hash_key: [],
+            alg: CipherAlg::AES256,
+            mode: CipherMode::CTR,
+            hmac: HmacAlg::Null,
+            pt: *b"Single block msg",
+            aad: [],
+            ct: hex!("14 5A D0 1D BF 82 4E C7 56 08 63 DC 71 E3 E0 C0"),
+            icv: [],
+            iv: hex!("DB 56 72 C9 7A A8 F0 B2")
+        )
+    );
+
+    //ciphertext of rfc3686 Test Vector #2, the HMAC-SHA1-96 icv of rfc2404 is
+    //over the aad and the ciphertext, the iv is only authenticated by esp
+    algm_test!(
+        ctr_hmac_test(
+            key: hex!("7E 24 06 78 17 FA E0 D7 43 D6 CE 1F 32 53 91 63"),
+            salt: hex!("00 6C B6 DB"),
+            hash_key: [0xc; 16],
+            alg: CipherAlg::AES128,
+            mode: CipherMode::CTR,
+            hmac: HmacAlg::SHA1,
+            pt: hex!(
+                "00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F
+                10 11 12 13 14 15 16 17 18 19 1A 1B 1C 1D 1E 1F"
+            ),
+            aad: hex!("00000200 00000001"),
+            ct: hex!(
+                "51 04 A1 06 16 8A 72 D9 79 0D 41 EE 8E DA D3 88
+                EB 2E 1E FC 46 DA 57 C8 FC E6 30 DF 91 41 BE 28"
+            ),
+            icv: {
+                use hmac::{Hmac, Mac};
+                let mut mac = Hmac::<sha1::Sha1>::new_from_slice(&[0xc; 16]).unwrap();
+                mac.update(&hex!(
+                    "00000200 00000001 C0543B59 DA48D90B
+                    5104A106 168A72D9 790D41EE 8EDAD388
+                    EB2E1EFC 46DA57C8 FCE630DF 9141BE28"
+                ));
+                mac.finalize().into_bytes()[..12].to_vec()
+            },
+            iv: hex!("C0 54 3B 59 DA 48 D9 0B")
+        )
+    );
+
+    //same vector as gmac_test, the text is authenticated after the aad and not encrypted
+    algm_test!(
+        gmac_mode_test(
+            key: hex!("dd95259bc8eefa3e493cb1a6ba1d8ee2b341d5230d50363094a2cc3433b3d9b9"),
+            salt:hex!("a1a6ced0"),
+            hash_key: [],
+            alg: CipherAlg::AES256,
+            mode: CipherMode::GMAC,
+            hmac: HmacAlg::Null,
+            pt: hex!("a0538fa1892c418aaad07aa08d3076f4a52bee8f130ff560db2b8d1009e9260fa6233fc22733e050c9e4f7cc699062765e261dffff1159e9060b26c8065dfab04055b58c82c340d987c9"),
+            aad: hex!("d46db90e13684b26149cb3b7f776e228"),
+            ct: hex!("a0538fa1892c418aaad07aa08d3076f4a52bee8f130ff560db2b8d1009e9260fa6233fc22733e050c9e4f7cc699062765e261dffff1159e9060b26c8065dfab04055b58c82c340d987c9"),
+            icv: hex!("9e120b01899fe2cb3e3a0b0c05045940"),
+            iv: hex!("84f4f13990750a9e")
+        )
+    );
+
+    //rfc4868 Test Case PRF-2 truncated to HMAC-SHA-256-128, the icv is over aad and text
+    algm_test!(
+        hmac_auth_test(
+            key: [],
+            salt: [],
+            hash_key: hex!(
+                "4a656665 00000000 00000000 00000000
+                00000000 00000000 00000000 00000000
+                00000000 00000000 00000000 00000000
+                00000000 00000000 00000000 00000000"
+            ),
+            alg: CipherAlg::Null,
+            mode: CipherMode::GCM,
+            hmac: HmacAlg::SHA256,
+            pt: hex!("666f72206e6f7468696e673f"),
+            aad: hex!("7768617420646f2079612077616e7420"),
+            ct: hex!("666f72206e6f7468696e673f"),
+            icv: hex!("5bdcc146bf60754e6a042426089575c7"),
+            iv: []
+        )
+    );
+
+    fn esp_cfg(sa: &SwSession, encrypt: bool) -> IpsecCfgDesc {
+        let mut cfg = IpsecFrameCfgDesc::default();
+        cfg.set_session_id(sa.id as u32);
+        cfg.set_resp_en(1);
+        cfg.set_encrypt(encrypt as u32);
+        cfg.set_esp_en(1);
+        IpsecCfgDesc {
+            src: IpsecFrameFmtDesc::default(),
+            dst: IpsecFrameFmtDesc::default(),
+            cfg,
+        }
+    }
+
+    #[test_log::test]
+    fn esp_packet_test() {
+        use smoltcp::wire::*;
+        let etha_ipsec = EthaIpsec::new(RRArbiter::<IPSEC_CH_NUM>::new());
+        let abort = etha_ipsec.abort();
+        let reg = etha_ipsec.regs();
+        let t = etha_ipsec.spawn(Some(CoreId { id: 0 }));
+        let driver = SwIpsec::new(&reg);
+        let mut ch0 = driver.alloc_ch(1);
+        let mut ch1 = driver.alloc_ch(1);
+        let packet = ipv4_udp_packet(b"esp packet mode payload");
+
+        //transport mode with gcm
+        let gcm = driver.alloc_session(
+            &[0x2; 32],
+            &hex!("a1a6ced0"),
+            &[],
+            8,
+            CipherAlg::AES256,
+            CipherMode::GCM,
+            HmacAlg::Null,
+        );
+        gcm.esp(0x1000, None);
+        let mut esp = vec![0u8; 256];
+        let r = ch0.xform(&[&packet], &[&mut esp], esp_cfg(&gcm, true));
+        assert!(!r.is_err());
+        esp.truncate(r.dst_len() as usize);
+        //udp is padded to 36 bytes with the trailer
+        assert_eq!(esp.len(), 20 + 8 + 8 + 36 + 16);
+        let ip = Ipv4Packet::new_checked(&esp[..]).unwrap();
+        assert!(ip.verify_checksum());
+        assert_eq!(ip.protocol(), IpProtocol::IpSecEsp);
+        assert_eq!(ip.total_len() as usize, esp.len());
+        assert_eq!(
+            ip.payload()[..16],
+            hex!("00001000 00000001 00000000 00000001")
+        );
+        let mut plain = vec![0u8; 256];
+        let r = ch1.xform(&[&esp], &[&mut plain], esp_cfg(&gcm, false));
+        assert!(!r.is_err());
+        assert_eq!(&plain[..r.dst_len() as usize], &packet[..]);
+
+        //each packet takes the next sequence number
+        let mut esp2 = vec![0u8; 256];
+        let r = ch0.xform(&[&packet], &[&mut esp2], esp_cfg(&gcm, true));
+        assert!(!r.is_err());
+        assert_eq!(esp2[24..28], hex!("00000002"));
+        assert_eq!(reg.read(addr(gcm.ext_base + SESS_ESP_SEQ_OFFSET)), Some(2));
+
+        //tampered packets fail authentication
+        esp[40] ^= 1;
+        let r = ch1.xform(&[&esp], &[&mut plain], esp_cfg(&gcm, false));
+        assert_eq!(r.auth_fail(), 1);
+
+        //tunnel mode with cbc and hmac
+        let cbc = driver.alloc_session(
+            &[0x1; 16],
+            &[],
+            &[0xb; 64],
+            16,
+            CipherAlg::AES128,
+            CipherMode::CBC,
+            HmacAlg::SHA256,
+        );
+        cbc.esp(0x2000, Some((&[10, 0, 0, 1], &[10, 0, 0, 2])));
+        let mut esp = vec![0u8; 256];
+        let r = ch0.xform(&[&packet], &[&mut esp], esp_cfg(&cbc, true));
+        assert!(!r.is_err());
+        esp.truncate(r.dst_len() as usize);
+        //inner packet is padded to 64 bytes with the trailer
+        assert_eq!(esp.len(), 20 + 8 + 16 + 64 + 32);
+        let ip = Ipv4Packet::new_checked(&esp[..]).unwrap();
+        assert!(ip.verify_checksum());
+        assert_eq!(ip.protocol(), IpProtocol::IpSecEsp);
+        assert_eq!(ip.src_addr(), Ipv4Address::new(10, 0, 0, 1));
+        assert_eq!(ip.dst_addr(), Ipv4Address::new(10, 0, 0, 2));
+        //the cbc iv is the sequence number encrypted under the sa key, not the counter itself
+        let mut iv = hex!("00000000 00000000 00000000 00000001");
+        {
+            use aes::cipher::{BlockEncrypt, KeyInit};
+            aes::Aes128::new_from_slice(&[0x1; 16])
+                .unwrap()
+                .encrypt_block(aes::Block::from_mut_slice(&mut iv));
+        }
+        assert_eq!(esp[28..44], iv);
+        let mut plain = vec![0u8; 256];
+        let r = ch1.xform(&[&esp], &[&mut plain], esp_cfg(&cbc, false));
+        assert!(!r.is_err());
+        assert_eq!(&plain[..r.dst_len() as usize], &packet[..]);
+        //esp authenticates the iv with the header as aad
+        esp[43] ^= 1;
+        let r = ch1.xform(&[&esp], &[&mut plain], esp_cfg(&cbc, false));
+        assert_eq!(r.auth_fail(), 1);
+        esp[43] ^= 1;
+
+        //tunnel mode over ipv6 with chacha20-poly1305
+        let chacha = driver.alloc_session(
+            &[0x3; 32],
+            &hex!("a0a1a2a3"),
+            &[],
+            8,
+            CipherAlg::ChaCha20,
+            CipherMode::Poly1305,
+            HmacAlg::Null,
+        );
+        let (src6, dst6) = (
+            hex!("20010db8000000000000000000000001"),
+            hex!("20010db8000000000000000000000002"),
+        );
+        chacha.esp(0x3000, Some((&src6, &dst6)));
+        let mut esp6 = vec![0u8; 256];
+        let r = ch0.xform(&[&packet], &[&mut esp6], esp_cfg(&chacha, true));
+        assert!(!r.is_err());
+        esp6.truncate(r.dst_len() as usize);
+        assert_eq!(esp6.len(), 40 + 8 + 8 + 56 + 16);
+        let ip6 = Ipv6Packet::new_checked(&esp6[..]).unwrap();
+        assert_eq!(ip6.next_header(), IpProtocol::IpSecEsp);
+        assert_eq!(ip6.src_addr(), Ipv6Address::from_bytes(&src6));
+        assert_eq!(ip6.dst_addr(), Ipv6Address::from_bytes(&dst6));
+        assert_eq!(ip6.payload_len() as usize, esp6.len() - 40);
+        let mut plain = vec![0u8; 256];
+        let r = ch1.xform(&[&esp6], &[&mut plain], esp_cfg(&chacha, false));
+        assert!(!r.is_err());
+        assert_eq!(&plain[..r.dst_len() as usize], &packet[..]);
+
+        //transport mode with integrity only gmac
+        let gmac = driver.alloc_session(
+            &[0x4; 16],
+            &hex!("b0b1b2b3"),
+            &[],
+            8,
+            CipherAlg::AES128,
+            CipherMode::GMAC,
+            HmacAlg::Null,
+        );
+        gmac.esp(0x4000, None);
+        let mut esp4 = vec![0u8; 256];
+        let r = ch0.xform(&[&packet], &[&mut esp4], esp_cfg(&gmac, true));
+        assert!(!r.is_err());
+        esp4.truncate(r.dst_len() as usize);
+        assert_eq!(esp4.len(), 20 + 8 + 8 + 36 + 16);
+        //udp is carried in clear
+        assert_eq!(esp4[36..36 + packet.len() - 20], packet[20..]);
+        let r = ch1.xform(&[&esp4], &[&mut plain], esp_cfg(&gmac, false));
+        assert!(!r.is_err());
+        assert_eq!(&plain[..r.dst_len() as usize], &packet[..]);
+        //the iv is authenticated too
+        esp4[30] ^= 1;
+        let r = ch1.xform(&[&esp4], &[&mut plain], esp_cfg(&gmac, false));
+        assert_eq!(r.auth_fail(), 1);
+
+        //spi of another sa is rejected
+        let r = ch1.xform(&[&esp], &[&mut plain], esp_cfg(&gcm, false));
+        assert_eq!(r.invalid_session(), 1);
+
+        //dst too small for the esp packet
+        let mut small = vec![0u8; 64];
+        let r = ch0.xform(&[&packet], &[&mut small], esp_cfg(&cbc, true));
+        assert_eq!(r.dst_err(), 1);
+
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn esp_replay_test() {
+        let etha_ipsec = EthaIpsec::new(RRArbiter::<IPSEC_CH_NUM>::new());
+        let abort = etha_ipsec.abort();
+        let reg = etha_ipsec.regs();
+        let t = etha_ipsec.spawn(Some(CoreId { id: 0 }));
+        let driver = SwIpsec::new(&reg);
+        let mut ch0 = driver.alloc_ch(1);
+        let mut ch1 = driver.alloc_ch(1);
+        let packet = ipv4_udp_packet(b"esp replay payload");
+        let gcm = driver.alloc_session(
+            &[0x2; 32],
+            &hex!("a1a6ced0"),
+            &[],
+            8,
+            CipherAlg::AES256,
+            CipherMode::GCM,
+            HmacAlg::Null,
+        );
+        let cbc = driver.alloc_session(
+            &[0x1; 16],
+            &[],
+            &[0xb; 64],
+            16,
+            CipherAlg::AES128,
+            CipherMode::CBC,
+            HmacAlg::SHA256,
+        );
+        let mut encrypt = |sa: &SwSession| {
+            let mut esp = vec![0u8; 256];
+            let r = ch0.xform(&[&packet], &[&mut esp], esp_cfg(sa, true));
+            esp.truncate(r.dst_len() as usize);
+            (r, esp)
+        };
+        let mut decrypt = |sa: &SwSession, esp: &[u8]| {
+            let mut plain = vec![0u8; 256];
+            let r = ch1.xform(&[esp], &[&mut plain], esp_cfg(sa, false));
+            if !r.is_err() {
+                assert_eq!(&plain[..r.dst_len() as usize], &packet[..]);
+            }
+            r
+        };
+
+        for sa in [&gcm, &cbc] {
+            sa.esp(0x1000 + sa.id as u32, None);
+            sa.esp_replay(64, false);
+            let esps = (0..70).map(|_| encrypt(sa).1).collect::<Vec<_>>();
+            //out of order inside the window, duplicates are rejected
+            assert!(!decrypt(sa, &esps[68]).is_err());
+            assert!(!decrypt(sa, &esps[10]).is_err());
+            assert!(!decrypt(sa, &esps[69]).is_err());
+            assert_eq!(decrypt(sa, &esps[69]).replay_fail(), 1);
+            assert_eq!(decrypt(sa, &esps[10]).replay_fail(), 1);
+            //older than the window
+            assert_eq!(decrypt(sa, &esps[5]).replay_fail(), 1);
+
+            //without esn the sequence number does not cycle
+            sa.esp_seq(0xffff_fffe);
+            assert!(!encrypt(sa).0.is_err());
+            let (r, _) = encrypt(sa);
+            assert_eq!(r.seq_overflow(), 1);
+            assert_eq!(r.replay_fail(), 0);
+
+            //with esn the high bits are authenticated but not sent
+            sa.esp_replay(64, true);
+            sa.esp_seq(0xffff_fffe);
+            let (r, esp0) = encrypt(sa);
+            assert!(!r.is_err());
+            let (r, esp1) = encrypt(sa);
+            assert!(!r.is_err());
+            assert_eq!(esp1.len(), esp0.len());
+            assert_eq!(esp1[24..28], hex!("00000000"));
+            assert_eq!(reg.read(addr(sa.ext_base + SESS_ESP_SEQ_HI_OFFSET)), Some(1));
+            assert!(!decrypt(sa, &esp0).is_err());
+            assert!(!decrypt(sa, &esp1).is_err());
+            assert_eq!(decrypt(sa, &esp1).replay_fail(), 1);
+            //high bits inferred by the receiver do not match
+            sa.esp_seq(0x2_0000_0000);
+            let (r, esp2) = encrypt(sa);
+            assert!(!r.is_err());
+            assert_eq!(decrypt(sa, &esp2).auth_fail(), 1);
+        }
+
+        abort.abort();
+        t.join().unwrap();
+    }
+
+    #[test_log::test]
+    fn lifetime_test() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let etha_ipsec = EthaIpsec::new(RRArbiter::<IPSEC_CH_NUM>::new());
+        let abort = etha_ipsec.abort();
+        let reg = etha_ipsec.regs();
+        let life_irqs = Arc::new(AtomicUsize::new(0));
+        {
+            let life_irqs = life_irqs.clone();
+            //life irq follows the channel irqs
+            etha_ipsec
+                .irqs()
+                .lock()
+                .unwrap()
+                .bind(IPSEC_CH_NUM, move |_| {
+                    life_irqs.fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap();
+        }
+        let t = etha_ipsec.spawn(Some(CoreId { id: 0 }));
+        let driver = SwIpsec::new(&reg);
+        let mut ch0 = driver.alloc_ch(1);
+        //the life irq is separate from the rings, which don't latch interrupts
+        let ring_int = addr(queue_base(0) + RING_INT_STATUS_OFFSET);
+        assert_eq!(reg.read(ring_int), None);
+        assert_eq!(reg.write(ring_int, RING_INT_DONE), None);
+        let packet = ipv4_udp_packet(b"esp lifetime payload");
+        let sa = driver.alloc_session(
+            &[0x2; 32],
+            &hex!("a1a6ced0"),
+            &[],
+            8,
+            CipherAlg::AES256,
+            CipherMode::GCM,
+            HmacAlg::Null,
+        );
+        sa.esp(0x1000, None);
+        let mut encrypt = || {
+            let mut esp = vec![0u8; 256];
+            ch0.xform(&[&packet], &[&mut esp], esp_cfg(&sa, true))
+        };
+        let read = |offset: usize| reg.read(addr(sa.ext_base + offset)).unwrap();
+        let write = |offset: usize, v: u64| reg.write(addr(sa.ext_base + offset), v).unwrap();
+
+        //packet limits
+        sa.life_limits((0, 2), (0, 3));
+        assert!(!encrypt().is_err());
+        assert_eq!(read(SESS_LIFE_STATUS_OFFSET), 0);
+        assert!(!encrypt().is_err());
+        assert_eq!(read(SESS_LIFE_STATUS_OFFSET), 1);
+        while life_irqs.load(Ordering::SeqCst) == 0 {}
+        //irq is raised once while the status stays latched
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(life_irqs.load(Ordering::SeqCst), 1);
+        write(SESS_LIFE_STATUS_OFFSET, 1);
+        assert_eq!(read(SESS_LIFE_STATUS_OFFSET), 0);
+        //and again when the cleared status is latched
+        assert!(!encrypt().is_err());
+        assert_eq!(read(SESS_LIFE_STATUS_OFFSET), 3);
+        while life_irqs.load(Ordering::SeqCst) != 2 {}
+        assert_eq!(encrypt().expired(), 1);
+        assert_eq!(read(SESS_PKT_CNT_OFFSET), 3);
+        assert_eq!(read(SESS_BYTE_CNT_OFFSET), 3 * packet.len() as u64);
+        //counters are reset by software
+        write(SESS_PKT_CNT_OFFSET, 0);
+        assert!(!encrypt().is_err());
+
+        //byte limits
+        write(SESS_LIFE_STATUS_OFFSET, 3);
+        write(SESS_PKT_CNT_OFFSET, 0);
+        write(SESS_BYTE_CNT_OFFSET, 0);
+        sa.life_limits((packet.len() as u64 + 1, 0), (2 * packet.len() as u64, 0));
+        assert!(!encrypt().is_err());
+        assert_eq!(read(SESS_LIFE_STATUS_OFFSET), 0);
+        assert!(!encrypt().is_err());
+        assert_eq!(read(SESS_LIFE_STATUS_OFFSET), 3);
+        assert_eq!(encrypt().expired(), 1);
+
+        abort.abort();
+        t.join().unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -378,14 +907,28 @@ mod tests_driver_helper {
     const SESS_CIPHER_KEY_HI_OFFSET: usize = 3;
     const SESS_HASH_KEY_LO_OFFSET: usize = 4;
     const SESS_HASH_KEY_HI_OFFSET: usize = 5;
+    const SESS_ESP_CTRL_OFFSET: usize = 0;
+    const SESS_ESP_SPI_OFFSET: usize = 1;
+    pub(super) const SESS_ESP_SEQ_OFFSET: usize = 2;
+    pub(super) const SESS_ESP_SEQ_HI_OFFSET: usize = 3;
+    pub(super) const SESS_PKT_CNT_OFFSET: usize = 8;
+    pub(super) const SESS_LIFE_STATUS_OFFSET: usize = 9;
+    pub(super) const SESS_BYTE_CNT_OFFSET: usize = 0x12;
+    const SESS_SOFT_BYTES_OFFSET: usize = 0x14;
+    const SESS_HARD_BYTES_OFFSET: usize = 0x16;
+    const SESS_SOFT_PKTS_OFFSET: usize = 0x18;
+    const SESS_HARD_PKTS_OFFSET: usize = 0x19;
+    const SESS_TUNNEL_SRC_OFFSET: usize = 0xa;
+    const SESS_TUNNEL_DST_OFFSET: usize = 0xe;
     const fn sess_valid(v: u64) -> u64 {
         v & 0x1
     }
     const fn sess_cipher_alg(v: u64) -> u64 {
         (v & 0x3) << 1
     }
+    //the third mode bit is cipher_mode_hi
     const fn sess_cipher_mode(v: u64) -> u64 {
-        (v & 0x3) << 3
+        (v & 0x3) << 3 | (v >> 2 & 0x1) << 28
     }
     const fn sess_hmac_alg(v: u64) -> u64 {
         (v & 0x3) << 5
@@ -396,11 +939,15 @@ mod tests_driver_helper {
     const fn sess_iv_len(v: u64) -> u64 {
         (v & 0x3f) << 10
     }
+    const fn sess_icv_len(v: u64) -> u64 {
+        (v & 0xfff) << 16
+    }
 
     pub(super) struct SwSession {
         regs: Arc<RegT>,
         pub id: usize,
-        base: usize,
+        pub base: usize,
+        pub ext_base: usize,
         cipher_key: &'static [u8],
         salt: &'static [u8],
         hash_key: &'static [u8],
@@ -425,6 +972,7 @@ mod tests_driver_helper {
                 regs: regs.clone(),
                 id,
                 base: SESSION_REGS_RANGE.start + id * SEC_SESSION_REGS_SIZE,
+                ext_base: SESSION_EXT_REGS_RANGE.start + id * SEC_SESSION_EXT_REGS_SIZE,
                 cipher_key,
                 salt,
                 hash_key,
@@ -507,6 +1055,69 @@ mod tests_driver_helper {
                     .unwrap();
             }
         }
+        //tunnel endpoints are (src, dst), none for transport mode
+        pub(super) fn esp(&self, spi: u32, tunnel: Option<(&[u8], &[u8])>) {
+            let write =
+                |offset: usize, v: u64| self.regs.write(addr(self.ext_base + offset), v).unwrap();
+            write(SESS_ESP_SPI_OFFSET, spi as u64);
+            write(SESS_ESP_SEQ_OFFSET, 0);
+            if let Some((src, dst)) = tunnel {
+                write(SESS_ESP_CTRL_OFFSET, 1 | ((src.len() == 16) as u64) << 1);
+                for (i, w) in src.chunks(4).enumerate() {
+                    write(
+                        SESS_TUNNEL_SRC_OFFSET + i,
+                        u32::from_be_bytes(w.try_into().unwrap()) as u64,
+                    );
+                }
+                for (i, w) in dst.chunks(4).enumerate() {
+                    write(
+                        SESS_TUNNEL_DST_OFFSET + i,
+                        u32::from_be_bytes(w.try_into().unwrap()) as u64,
+                    );
+                }
+            } else {
+                write(SESS_ESP_CTRL_OFFSET, 0);
+            }
+        }
+        pub(super) fn esp_replay(&self, win: usize, esn: bool) {
+            let ctrl = self
+                .regs
+                .read(addr(self.ext_base + SESS_ESP_CTRL_OFFSET))
+                .unwrap();
+            self.regs
+                .write(
+                    addr(self.ext_base + SESS_ESP_CTRL_OFFSET),
+                    ctrl & 0x3 | (esn as u64) << 2 | (win as u64) << 4,
+                )
+                .unwrap();
+        }
+        pub(super) fn icv_len(&self, len: usize) {
+            let ctx = self.regs.read(addr(self.base + SESS_CTX_OFFSET)).unwrap();
+            self.regs
+                .write(
+                    addr(self.base + SESS_CTX_OFFSET),
+                    ctx & !sess_icv_len(0xfff) | sess_icv_len(len as u64),
+                )
+                .unwrap();
+        }
+        pub(super) fn life_limits(&self, soft: (u64, u64), hard: (u64, u64)) {
+            let write =
+                |offset: usize, v: u64| self.regs.write(addr(self.ext_base + offset), v).unwrap();
+            write(SESS_SOFT_BYTES_OFFSET, soft.0 & 0xffff_ffff);
+            write(SESS_SOFT_BYTES_OFFSET + 1, soft.0 >> 32);
+            write(SESS_SOFT_PKTS_OFFSET, soft.1);
+            write(SESS_HARD_BYTES_OFFSET, hard.0 & 0xffff_ffff);
+            write(SESS_HARD_BYTES_OFFSET + 1, hard.0 >> 32);
+            write(SESS_HARD_PKTS_OFFSET, hard.1);
+        }
+        pub(super) fn esp_seq(&self, seq: u64) {
+            self.regs
+                .write(addr(self.ext_base + SESS_ESP_SEQ_OFFSET), seq & 0xffff_ffff)
+                .unwrap();
+            self.regs
+                .write(addr(self.ext_base + SESS_ESP_SEQ_HI_OFFSET), seq >> 32)
+                .unwrap();
+        }
     }
 
     pub(super) struct SwIpsec {
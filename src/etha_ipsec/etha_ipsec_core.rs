@@ -2,7 +2,7 @@ use super::ch::*;
 use super::desc::req::*;
 use super::desc::resp::*;
 use super::engine::*;
-use super::reg_if::TopRegs;
+use super::reg_if::{sessions::SecSessions, TopRegs};
 use super::STATICS_TAR;
 use super::*;
 use crate::aborter::*;
@@ -13,6 +13,7 @@ use std::sync::{Arc, Mutex};
 pub struct EthaIpsecCore<A: Arbiter> {
     arbiter: A,
     chs: [EthaIpsecCh; IPSEC_CH_NUM],
+    life: EthaIpsecLife,
     engine: IpsecEngine,
     abort: Arc<Aborter>,
     irqs: Arc<Mutex<IrqVec>>,
@@ -24,9 +25,14 @@ impl<A: Arbiter> EthaIpsecCore<A> {
         let chs = array_init::array_init(|i| {
             EthaIpsecCh::new(i, &regs.chs[i], &mut irqs.lock().unwrap())
         });
+        let life = EthaIpsecLife {
+            irq_num: irqs.lock().unwrap().alloc("EthaIpsecLifeIrq"),
+            sessions: regs.sessions.clone(),
+        };
         EthaIpsecCore {
             arbiter,
             chs,
+            life,
             engine: IpsecEngine::new(&regs.sessions),
             abort: Arc::new(Aborter::new()),
             irqs,
@@ -34,6 +40,7 @@ impl<A: Arbiter> EthaIpsecCore<A> {
     }
     pub fn run(&mut self) {
         let mut pipe = EthaIrqs::new(&self.chs, &self.irqs)
+            .comb(EthaIrqs::new(std::slice::from_ref(&self.life), &self.irqs))
             .comb(EthaIpsecReqs(&self.chs))
             .comb(EthaIpsecArbit(&mut self.arbiter))
             .comb(EthaIpsecProcess {
@@ -57,6 +64,22 @@ impl<A: Arbiter> EthaIpsecCore<A> {
     }
 }
 
+//sessions share one irq, raised when a lifetime status bit is latched
+pub struct EthaIpsecLife {
+    irq_num: usize,
+    sessions: Arc<SecSessions<IPSEC_SESSION_NUM>>,
+}
+
+impl WithIrq for EthaIpsecLife {
+    fn poll_irq(&self) -> Option<usize> {
+        if self.sessions.take_life_raised() {
+            Some(self.irq_num)
+        } else {
+            None
+        }
+    }
+}
+
 pub struct EthaIpsecReqs<'a>(&'a [EthaIpsecCh]);
 
 impl<'a> Pipeline for EthaIpsecReqs<'a> {
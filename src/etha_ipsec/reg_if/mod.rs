@@ -6,6 +6,8 @@ use std::sync::Arc;
 pub const SESSION_REGS_RANGE: std::ops::Range<usize> = 0..2048;
 pub const QUEUE_REGS_RANGE: std::ops::Range<usize> =
     SESSION_REGS_RANGE.end..SESSION_REGS_RANGE.end + 1024;
+pub const SESSION_EXT_REGS_RANGE: std::ops::Range<usize> =
+    QUEUE_REGS_RANGE.end..QUEUE_REGS_RANGE.end + 2048;
 
 pub const fn queue_base(i: usize) -> usize {
     QUEUE_REGS_RANGE.start + i * RING_REGS_SIZE
@@ -31,13 +33,15 @@ impl<const CHS: usize, const SESSIONS: usize> RegBus for TopRegs<CHS, SESSIONS>
         } else if QUEUE_REGS_RANGE.contains(&offset) {
             let offset = offset - QUEUE_REGS_RANGE.start;
             let idx = offset / RING_REGS_SIZE;
-            if idx < self.chs.len() {
+            if idx < self.chs.len() && !ring_int_offset(offset % RING_REGS_SIZE) {
                 let r = self.chs[idx].write((offset % RING_REGS_SIZE) as u64, data);
                 self.chs[idx].r_update_status();
                 r
             } else {
                 None
             }
+        } else if SESSION_EXT_REGS_RANGE.contains(&offset) {
+            self.sessions.write_ext((offset - SESSION_EXT_REGS_RANGE.start) as u64, data)
         } else {
             None
         }
@@ -50,12 +54,14 @@ impl<const CHS: usize, const SESSIONS: usize> RegBus for TopRegs<CHS, SESSIONS>
         } else if QUEUE_REGS_RANGE.contains(&offset) {
             let offset = offset - QUEUE_REGS_RANGE.start;
             let idx = offset / RING_REGS_SIZE;
-            if idx < self.chs.len() {
+            if idx < self.chs.len() && !ring_int_offset(offset % RING_REGS_SIZE) {
                 self.chs[idx].r_update_status();
                 self.chs[idx].read((offset % RING_REGS_SIZE) as u64)
             } else {
                 None
             }
+        } else if SESSION_EXT_REGS_RANGE.contains(&offset) {
+            self.sessions.read_ext((offset - SESSION_EXT_REGS_RANGE.start) as u64)
         } else {
             None
         }
@@ -74,6 +80,12 @@ impl<const CHS: usize, const SESSIONS: usize> GenHeader for TopRegs<CHS, SESSION
             SESSION_REGS_RANGE.start
         )?;
 
+        writeln!(
+            header,
+            "#define SEC_SESSIONS_EXT_OFFSET {:#x}",
+            SESSION_EXT_REGS_RANGE.start
+        )?;
+
         writeln!(header, "#define QUEUE_NUM {}", CHS)?;
         writeln!(
             header,
@@ -85,11 +97,20 @@ impl<const CHS: usize, const SESSIONS: usize> GenHeader for TopRegs<CHS, SESSION
             header,
             "#define RING(name, i) (QUEUE_REGS_OFFSET + (RING_REGS_SIZE * i) + RING_REGS_##name##_OFFSET)",
         )?;
+        writeln!(
+            header,
+            "// RING INT_STATUS and INT_COAL are not decoded, irqs are raised by STATUS & INT_MASK",
+        )?;
 
         writeln!(
             header,
             "#define SEC_SESSION(name, i) SEC_SESSION_OFFSET(SEC_SESSIONS_OFFSET, name, i)",
         )?;
+
+        writeln!(
+            header,
+            "#define SEC_SESSION_EXT(name, i) SEC_SESSION_EXT_OFFSET(SEC_SESSIONS_EXT_OFFSET, name, i)",
+        )?;
         Ok(())
     }
 }
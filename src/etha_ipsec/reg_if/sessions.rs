@@ -1,5 +1,7 @@
 use crate::reg_if::RegBus;
 use etha_model_generator::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 define_reg! {
     KeyAddress {
         fields {
@@ -21,7 +23,8 @@ pub enum CipherAlg {
     Null = 0,
     AES128 = 1,
     AES256 = 2,
-    Unknown = 3,
+    ChaCha20 = 3,
+    Unknown = 4,
 }
 
 impl std::convert::From<u8> for CipherAlg {
@@ -30,6 +33,7 @@ impl std::convert::From<u8> for CipherAlg {
             0 => CipherAlg::Null,
             1 => CipherAlg::AES128,
             2 => CipherAlg::AES256,
+            3 => CipherAlg::ChaCha20,
             _ => CipherAlg::Unknown,
         }
     }
@@ -40,7 +44,10 @@ pub enum CipherMode {
     CBC = 0,
     CCM = 1,
     GCM = 2,
-    Unknown = 3,
+    Poly1305 = 3,
+    CTR = 4,
+    GMAC = 5,
+    Unknown = 6,
 }
 
 impl std::convert::From<u8> for CipherMode {
@@ -49,6 +56,9 @@ impl std::convert::From<u8> for CipherMode {
             0 => CipherMode::CBC,
             1 => CipherMode::CCM,
             2 => CipherMode::GCM,
+            3 => CipherMode::Poly1305,
+            4 => CipherMode::CTR,
+            5 => CipherMode::GMAC,
             _ => CipherMode::Unknown,
         }
     }
@@ -78,12 +88,13 @@ define_reg! {
     XformCtx {
         fields {
             valid(RW, volatile){invalid: 0, valid: 1}: 0, 0;
-            cipher_alg(RW){null: 0, aes_128: 1, aes_256: 2}: 2, 1;
-            cipher_mode(RW){cbc: 0, ccm: 1, gcm: 2}: 4, 3;
+            cipher_alg(RW){null: 0, aes_128: 1, aes_256: 2, chacha20: 3}: 2, 1;
+            cipher_mode(RW){cbc: 0, ccm: 1, gcm: 2, poly1305: 3}: 4, 3;
             hmac_alg(RW){null: 0, sha1: 1, sha256: 2, sha512: 3}: 6, 5;
             salt_len(RW): 9, 7;
             iv_len(RW): 15, 10;
             icv_len(RW): 27, 16;
+            cipher_mode_hi(RW){std: 0, ctr_gmac: 1}: 28, 28;
         }
     }
 }
@@ -92,8 +103,9 @@ impl LockedXformCtx {
     pub fn get_cipher_alg(&self) -> CipherAlg {
         CipherAlg::from(self.cipher_alg() as u8)
     }
+    //cipher_mode_hi extends cipher_mode in a spare bit, ctr is 4 and gmac is 5
     pub fn get_cipher_mode(&self) -> CipherMode {
-        CipherMode::from(self.cipher_mode() as u8)
+        CipherMode::from((self.cipher_mode_hi() << 2 | self.cipher_mode()) as u8)
     }
     pub fn get_hmac_alg(&self) -> HmacAlg {
         HmacAlg::from(self.hmac_alg() as u8)
@@ -106,13 +118,102 @@ impl LockedXformCtx {
             return Err(format!("Unkonwn cipher algorithm: {:x}", self.cipher_alg()));
         }
         if let CipherMode::Unknown = self.get_cipher_mode() {
-            return Err(format!("Unkonwn cipher mode: {:x}", self.cipher_mode()));
+            return Err(format!(
+                "Unkonwn cipher mode: {:x}",
+                self.cipher_mode_hi() << 2 | self.cipher_mode()
+            ));
+        }
+        //chacha20 is only used as the rfc7634 aead
+        let (alg, mode) = (self.get_cipher_alg(), self.get_cipher_mode());
+        if !matches!(alg, CipherAlg::Null)
+            && matches!(alg, CipherAlg::ChaCha20) != matches!(mode, CipherMode::Poly1305)
+        {
+            return Err(format!("Invalid cipher mode {:?} for {:?}", mode, alg));
         }
         Ok(())
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EspMode {
+    Transport = 0,
+    Tunnel = 1,
+}
+
+impl std::convert::From<u8> for EspMode {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => EspMode::Tunnel,
+            _ => EspMode::Transport,
+        }
+    }
+}
+
+define_reg! {
+    EspCtrl {
+        fields {
+            mode(RW){transport: 0, tunnel: 1}: 0, 0;
+            tunnel_ip(RW){ipv4: 0, ipv6: 1}: 1, 1;
+            esn(RW): 2, 2;
+            replay_win(RW): 14, 4;
+        }
+    }
+}
+
+impl LockedEspCtrl {
+    pub fn get_mode(&self) -> EspMode {
+        EspMode::from(self.mode() as u8)
+    }
+}
+
+//last sequence number sent, incremented by the engine on each esp encryption
+define_reg! {
+    EspSeq {
+        fields {
+            seq(RW, volatile): 31, 0;
+        }
+    }
+}
+
+define_reg! {
+    EspReplayIdx {
+        fields {
+            idx(RW): 4, 0;
+        }
+    }
+}
+
+define_reg! {
+    LifeStatus {
+        fields {
+            soft(RW): 0, 0;
+            hard(RW): 1, 1;
+        }
+    }
+}
+
+define_reg! {
+    LifeCnt {
+        fields {
+            cnt(RW, volatile): 31, 0;
+        }
+    }
+}
+
+define_reg! {
+    LifeLimit {
+        fields {
+            limit(RW): 31, 0;
+        }
+    }
+}
+
 pub const SEC_SESSION_REGS_SIZE: usize = 8;
+pub const SEC_SESSION_EXT_REGS_SIZE: usize = 32;
+pub const ESP_REPLAY_WIN_MAX: usize = 1024;
+const SEC_SESSION_CTX_OFFSET: usize = 0;
+pub const ESP_REPLAY_BITMAP_OFFSET: usize = 0x7;
+pub const SEC_SESSION_LIFE_STATUS_OFFSET: usize = 0x9;
 
 reg_map! {
     pub SecSession(8) {
@@ -125,12 +226,60 @@ reg_map! {
     }
 }
 
+//esp and lifetime state of a session, in its own range so the session layout is unchanged
+//tunnel addresses are in network order, word 0 holds the first 4 bytes,
+//ipv4 endpoints only use word 0
+//replay window size is in packets, 0 disables the check and sizes above 1024 are clamped,
+//replay state is cleared when ctx is written, the bitmap word selected by replay_idx
+//can be read from replay_bitmap, bit i of the window marks replay_top - i as received
+//lifetime counters are cleared by writing 0, a limit of 0 is disabled,
+//soft and hard status are latched when the counters reach the limits, and the engine
+//refuses the session while a hard limit is reached
+reg_map! {
+    pub SecSessionExt(32) {
+        esp_ctrl(RW): EspCtrl, 0;
+        esp_spi(RW): KeyValue, 1;
+        esp_seq(RW): EspSeq, 2;
+        esp_seq_hi(RW): EspSeq, 3;
+        esp_replay_top_lo(RO): EspSeq, 4;
+        esp_replay_top_hi(RO): EspSeq, 5;
+        esp_replay_idx(RW): EspReplayIdx, 6;
+        esp_replay_bitmap(RO): KeyValue, 7;
+        pkt_cnt(RW): LifeCnt, 8;
+        life_status(RW1C): LifeStatus, 9;
+        tunnel_src0(RW): KeyValue, 0xa;
+        tunnel_src1(RW): KeyValue, 0xb;
+        tunnel_src2(RW): KeyValue, 0xc;
+        tunnel_src3(RW): KeyValue, 0xd;
+        tunnel_dst0(RW): KeyValue, 0xe;
+        tunnel_dst1(RW): KeyValue, 0xf;
+        tunnel_dst2(RW): KeyValue, 0x10;
+        tunnel_dst3(RW): KeyValue, 0x11;
+        byte_cnt_lo(RW): LifeCnt, 0x12;
+        byte_cnt_hi(RW): LifeCnt, 0x13;
+        soft_bytes_lo(RW): LifeLimit, 0x14;
+        soft_bytes_hi(RW): LifeLimit, 0x15;
+        hard_bytes_lo(RW): LifeLimit, 0x16;
+        hard_bytes_hi(RW): LifeLimit, 0x17;
+        soft_pkts(RW): LifeLimit, 0x18;
+        hard_pkts(RW): LifeLimit, 0x19;
+    }
+}
+
+fn tunnel_addr(words: [u64; 4]) -> [u8; 16] {
+    let mut addr = [0; 16];
+    for (i, w) in words.iter().enumerate() {
+        addr[i * 4..i * 4 + 4].copy_from_slice(&(*w as u32).to_be_bytes());
+    }
+    addr
+}
+
 impl LockedSecSession {
     pub fn cipher_key_addr(&self) -> Option<*mut u8> {
         let alg = self.ctx().get_cipher_alg();
         tracing::debug!(target : "ipsec-engine-cache", "cipher_key_addr!");
         match alg {
-            CipherAlg::AES128 | CipherAlg::AES256 => {
+            CipherAlg::AES128 | CipherAlg::AES256 | CipherAlg::ChaCha20 => {
                 let lo = self.cipher_key_lo().get() as usize;
                 let hi = self.cipher_key_hi().get() as usize;
                 Some(((hi << 32) | lo) as *mut u8)
@@ -160,9 +309,157 @@ impl LockedSecSession {
     }
 }
 
+impl LockedSecSessionExt {
+    pub fn tunnel_src(&self) -> [u8; 16] {
+        tunnel_addr([
+            self.tunnel_src0().get(),
+            self.tunnel_src1().get(),
+            self.tunnel_src2().get(),
+            self.tunnel_src3().get(),
+        ])
+    }
+    pub fn tunnel_dst(&self) -> [u8; 16] {
+        tunnel_addr([
+            self.tunnel_dst0().get(),
+            self.tunnel_dst1().get(),
+            self.tunnel_dst2().get(),
+            self.tunnel_dst3().get(),
+        ])
+    }
+}
+
+struct ReplayWindow {
+    top: u64,
+    bitmap: [u32; ESP_REPLAY_WIN_MAX / 32],
+}
+
+impl ReplayWindow {
+    fn new() -> Self {
+        ReplayWindow {
+            top: 0,
+            bitmap: [0; ESP_REPLAY_WIN_MAX / 32],
+        }
+    }
+    fn received(&self, i: u64) -> bool {
+        (i as usize) < ESP_REPLAY_WIN_MAX && self.bitmap[i as usize / 32] & 1 << (i % 32) != 0
+    }
+    fn set(&mut self, i: u64) {
+        if (i as usize) < ESP_REPLAY_WIN_MAX {
+            self.bitmap[i as usize / 32] |= 1 << (i % 32)
+        }
+    }
+    fn shift(&mut self, n: u64) {
+        let words = self.bitmap.len();
+        let w = std::cmp::min(n / 32, words as u64) as usize;
+        let b = (n % 32) as u32;
+        for i in (0..words).rev() {
+            let hi = if i >= w { self.bitmap[i - w] << b } else { 0 };
+            let lo = if i > w && b != 0 {
+                self.bitmap[i - w - 1] >> (32 - b)
+            } else {
+                0
+            };
+            self.bitmap[i] = hi | lo;
+        }
+    }
+}
+
 pub struct SecSessionRegs {
     pub id: usize,
     inner: LockedSecSession,
+    pub ext: LockedSecSessionExt,
+    replay: Mutex<ReplayWindow>,
+    //shared by all sessions, set when a life status bit is newly latched
+    life_raised: Arc<AtomicBool>,
+}
+
+impl SecSessionRegs {
+    fn replay_size(&self) -> u64 {
+        std::cmp::min(self.ext.esp_ctrl().replay_win() as usize, ESP_REPLAY_WIN_MAX) as u64
+    }
+    //infers the high bits of an esn sequence number, rfc4303 appendix A2.2
+    pub fn esn_seq(&self, seq_lo: u32) -> u64 {
+        let top = self.replay.lock().unwrap().top;
+        let (th, tl) = ((top >> 32) as u32, top as u32);
+        let w = std::cmp::max(self.replay_size(), 1) as u32;
+        let bottom = tl.wrapping_sub(w - 1);
+        let hi = if tl >= w - 1 {
+            if seq_lo >= bottom {
+                th
+            } else {
+                th.wrapping_add(1)
+            }
+        } else if seq_lo >= bottom {
+            th.saturating_sub(1)
+        } else {
+            th
+        };
+        (hi as u64) << 32 | seq_lo as u64
+    }
+    pub fn replay_check(&self, seq: u64) -> bool {
+        let size = self.replay_size();
+        let w = self.replay.lock().unwrap();
+        size == 0 || seq != 0 && (seq > w.top || w.top - seq < size && !w.received(w.top - seq))
+    }
+    //only called for authenticated packets
+    pub fn replay_update(&self, seq: u64) {
+        let mut w = self.replay.lock().unwrap();
+        if seq > w.top {
+            let n = seq - w.top;
+            w.shift(n);
+            w.top = seq;
+            w.set(0);
+        } else {
+            let i = w.top - seq;
+            w.set(i);
+        }
+        self.ext.esp_replay_top_lo_mut().set(w.top & 0xffff_ffff);
+        self.ext.esp_replay_top_hi_mut().set(w.top >> 32);
+    }
+    fn replay_reset(&self) {
+        *self.replay.lock().unwrap() = ReplayWindow::new();
+        self.ext.esp_replay_top_lo_mut().set(0);
+        self.ext.esp_replay_top_hi_mut().set(0);
+    }
+    fn byte_cnt(&self) -> u64 {
+        self.ext.byte_cnt_hi().get() << 32 | self.ext.byte_cnt_lo().get()
+    }
+    fn life_reached(&self, bytes_limit: u64, pkts_limit: u64) -> bool {
+        bytes_limit != 0 && self.byte_cnt() >= bytes_limit
+            || pkts_limit != 0 && self.ext.pkt_cnt().get() >= pkts_limit
+    }
+    pub fn hard_expired(&self) -> bool {
+        self.life_reached(
+            self.ext.hard_bytes_hi().get() << 32 | self.ext.hard_bytes_lo().get(),
+            self.ext.hard_pkts().get(),
+        )
+    }
+    pub fn soft_expired(&self) -> bool {
+        self.life_reached(
+            self.ext.soft_bytes_hi().get() << 32 | self.ext.soft_bytes_lo().get(),
+            self.ext.soft_pkts().get(),
+        )
+    }
+    //counts a processed packet and latches the limits it reaches
+    pub fn account(&self, bytes: u64) {
+        let bytes = self.byte_cnt().saturating_add(bytes);
+        self.ext.byte_cnt_lo_mut().set(bytes & 0xffff_ffff);
+        self.ext.byte_cnt_hi_mut().set(bytes >> 32);
+        let pkts = std::cmp::min(self.ext.pkt_cnt().get() + 1, u32::MAX as u64);
+        self.ext.pkt_cnt_mut().set(pkts);
+        let latched = self.ext.life_status().get();
+        let status = latched | self.soft_expired() as u64 | (self.hard_expired() as u64) << 1;
+        self.ext.life_status_mut().set(status);
+        if status & !latched != 0 {
+            self.life_raised.store(true, Ordering::Relaxed);
+        }
+    }
+    fn life_reset(&self) {
+        self.ext.pkt_cnt_mut().set(0);
+        self.ext.byte_cnt_lo_mut().set(0);
+        self.ext.byte_cnt_hi_mut().set(0);
+        self.ext.life_status_mut().set(0);
+    }
 }
 
 impl std::ops::Deref for SecSessionRegs {
@@ -174,15 +471,54 @@ impl std::ops::Deref for SecSessionRegs {
 
 pub struct SecSessions<const N: usize> {
     pub sessions: [SecSessionRegs; N],
+    life_raised: Arc<AtomicBool>,
 }
 
 impl<const N: usize> SecSessions<N> {
     pub fn new() -> Self {
+        let life_raised = Arc::new(AtomicBool::new(false));
         SecSessions {
             sessions: array_init::array_init(|i| SecSessionRegs {
                 id: i,
                 inner: LockedSecSession::new(32),
+                ext: LockedSecSessionExt::new(32),
+                replay: Mutex::new(ReplayWindow::new()),
+                life_raised: life_raised.clone(),
             }),
+            life_raised,
+        }
+    }
+    //bits stay latched until cleared, so a session raises again only after it is cleared
+    pub fn take_life_raised(&self) -> bool {
+        self.life_raised.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl<const N: usize> SecSessions<N> {
+    pub fn write_ext(&self, addr: u64, data: u64) -> Option<()> {
+        let idx = addr as usize / SEC_SESSION_EXT_REGS_SIZE;
+        let offset = addr as usize % SEC_SESSION_EXT_REGS_SIZE;
+        if idx < self.sessions.len() {
+            self.sessions[idx].ext.bus_write(offset as u64, data)
+        } else {
+            None
+        }
+    }
+
+    pub fn read_ext(&self, addr: u64) -> Option<u64> {
+        let idx = addr as usize / SEC_SESSION_EXT_REGS_SIZE;
+        let offset = addr as usize % SEC_SESSION_EXT_REGS_SIZE;
+        if idx < self.sessions.len() {
+            let s = &self.sessions[idx];
+            match offset {
+                ESP_REPLAY_BITMAP_OFFSET => {
+                    let i = s.ext.esp_replay_idx().idx() as usize;
+                    Some(s.replay.lock().unwrap().bitmap[i] as u64)
+                }
+                _ => s.ext.read(offset as u64),
+            }
+        } else {
+            None
         }
     }
 }
@@ -192,7 +528,12 @@ impl<const N: usize> RegBus for SecSessions<N> {
         let idx = addr as usize / SEC_SESSION_REGS_SIZE;
         let offset = addr as usize % SEC_SESSION_REGS_SIZE;
         if idx < self.sessions.len() {
-            self.sessions[idx].write(offset as u64, data)
+            let s = &self.sessions[idx];
+            if offset == SEC_SESSION_CTX_OFFSET {
+                s.replay_reset();
+                s.life_reset();
+            }
+            s.write(offset as u64, data)
         } else {
             None
         }
@@ -215,14 +556,65 @@ impl<const N: usize> GenHeader for SecSessions<N> {
     }
     fn gen_c_header<W: std::io::Write>(header: &mut W) -> std::io::Result<()> {
         SecSession::gen_c_header(header)?;
+        SecSessionExt::gen_c_header(header)?;
         XformCtx::gen_c_header(header)?;
+        EspCtrl::gen_c_header(header)?;
+        LifeStatus::gen_c_header(header)?;
 
         writeln!(header, "#define SEC_SESSIONS_NUM {}", N)?;
+        writeln!(header, "#define ESP_REPLAY_WIN_MAX {}", ESP_REPLAY_WIN_MAX)?;
 
         writeln!(
             header,
             "#define SEC_SESSION_OFFSET(base, name, i) ((base) + (SEC_SESSION_SIZE * i) + SEC_SESSION_##name##_OFFSET)",
         )?;
+        writeln!(
+            header,
+            "#define SEC_SESSION_EXT_OFFSET(base, name, i) ((base) + (SEC_SESSION_EXT_SIZE * i) + SEC_SESSION_EXT_##name##_OFFSET)",
+        )?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_window_test() {
+        let sessions = SecSessions::<1>::new();
+        let s = &sessions.sessions[0];
+        s.ext.esp_ctrl_mut().set_replay_win(64);
+        for seq in [1, 3, 2, 100] {
+            assert!(s.replay_check(seq));
+            s.replay_update(seq);
+        }
+        //duplicates and packets behind the window
+        assert!(!s.replay_check(100));
+        assert!(!s.replay_check(36));
+        assert!(!s.replay_check(0));
+        assert!(s.replay_check(37));
+        assert_eq!(s.ext.esp_replay_top_lo().get(), 100);
+        sessions.write_ext(0x6, 1).unwrap();
+        assert_eq!(sessions.read_ext(ESP_REPLAY_BITMAP_OFFSET as u64), Some(0));
+        sessions.write_ext(0x6, 0).unwrap();
+        assert_eq!(sessions.read_ext(ESP_REPLAY_BITMAP_OFFSET as u64), Some(1));
+        s.replay_update(98);
+        assert_eq!(sessions.read_ext(ESP_REPLAY_BITMAP_OFFSET as u64), Some(0x5));
+
+        //high bits of esn follow the window across the 32 bits boundary
+        s.replay_update(0xffff_fff0);
+        assert_eq!(s.esn_seq(0xffff_ffe0), 0xffff_ffe0);
+        assert_eq!(s.esn_seq(0x10), 0x1_0000_0010);
+        s.replay_update(0x1_0000_0010);
+        assert_eq!(s.ext.esp_replay_top_hi().get(), 1);
+        assert_eq!(s.esn_seq(0xffff_fff8), 0xffff_fff8);
+        assert!(s.replay_check(0xffff_fff8));
+        assert!(!s.replay_check(0xffff_fff0));
+
+        //window is cleared when the session is programmed
+        sessions.write(0, 0).unwrap();
+        assert_eq!(s.ext.esp_replay_top_lo().get(), 0);
+        assert!(s.replay_check(1));
+    }
+}
@@ -127,16 +127,22 @@ impl IpsecContextCfg {
     pub fn cipher_key_len(&self) -> usize {
         (match self.cipher_alg {
             CipherAlg::AES128 => 128,
-            CipherAlg::AES256 => 256,
+            CipherAlg::AES256 | CipherAlg::ChaCha20 => 256,
             _ => 0,
         }) / 8
     }
     pub fn iv_len(&self) -> usize {
         if self.iv_len != 0 {
             self.iv_len
+        } else if let CipherAlg::Null = self.cipher_alg {
+            0
         } else {
             match self.cipher_mode {
-                CipherMode::CCM | CipherMode::GCM => 8,
+                CipherMode::CCM
+                | CipherMode::GCM
+                | CipherMode::Poly1305
+                | CipherMode::CTR
+                | CipherMode::GMAC => 8,
                 CipherMode::CBC => 16,
                 _ => 0,
             }
@@ -154,14 +160,16 @@ impl IpsecContextCfg {
         if self.icv_len != 0 {
             self.icv_len
         } else {
-            match self.cipher_mode {
-                CipherMode::CCM => 8,
-                CipherMode::GCM => 16,
-                _ => match self.hmac_alg {
-                    HmacAlg::SHA1 => 20,
-                    HmacAlg::SHA256 | HmacAlg::SHA512 => 32,
-                    _ => 0,
-                },
+            match (self.cipher_alg, self.cipher_mode) {
+                (CipherAlg::Null, _) | (_, CipherMode::CBC | CipherMode::CTR) => {
+                    match self.hmac_alg {
+                        HmacAlg::SHA1 => 20,
+                        HmacAlg::SHA256 | HmacAlg::SHA512 => 32,
+                        _ => 0,
+                    }
+                }
+                (_, CipherMode::CCM) => 8,
+                _ => 16,
             }
         }
     }
@@ -232,7 +240,7 @@ impl IpsecSessionCache {
         })
     }
 
-    fn get_session<'a>(&'a self, session: usize) -> Option<&'a SecSessionRegs> {
+    pub fn get_session<'a>(&'a self, session: usize) -> Option<&'a SecSessionRegs> {
         if session < self.regs.sessions.len() {
             match self.regs.sessions[session].ctx().check_session() {
                 Ok(_) => Some(&self.regs.sessions[session]),
@@ -1,4 +1,5 @@
 use etha_model_generator::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 pub struct IrqBit {
     id: usize,
     name: String,
@@ -25,25 +26,49 @@ impl IrqBit {
     }
 }
 
+//handlers are bound to vectors, causes are routed to vectors by the device
 pub struct IrqVec {
     name: String,
+    causes: Vec<String>,
     v: Vec<IrqBit>,
+    //one vector is allocated for each cause if not fixed
+    fixed: bool,
 }
 
 impl IrqVec {
     pub fn new(name: &str) -> Self {
         IrqVec {
             name: name.to_string(),
+            causes: vec![],
             v: vec![],
+            fixed: false,
+        }
+    }
+
+    pub fn with_vectors(name: &str, n: usize) -> Self {
+        IrqVec {
+            name: name.to_string(),
+            causes: vec![],
+            v: (0..n)
+                .map(|i| IrqBit::new(i, &format!("{}Vec{}", name, i)))
+                .collect(),
+            fixed: true,
         }
     }
 
     pub fn alloc(&mut self, name: &str) -> usize {
-        let id = self.v.len();
-        self.v.push(IrqBit::new(id, name));
+        let id = self.causes.len();
+        self.causes.push(name.to_string());
+        if !self.fixed {
+            self.v.push(IrqBit::new(id, name));
+        }
         id
     }
 
+    pub fn vectors(&self) -> usize {
+        self.v.len()
+    }
+
     pub fn bind<F: Fn(usize) + Send + 'static>(&mut self, id: usize, handler: F) -> Option<()> {
         if id < self.v.len() {
             self.v[id].bind_handler(handler);
@@ -62,13 +87,87 @@ pub trait WithIrq {
     fn poll_irq(&self) -> Option<usize>;
 }
 
+//returns the cause id and the bitmap of newly raised sub causes
+pub trait WithIrqCauses {
+    fn poll_causes(&self) -> (usize, u64);
+}
+
+//counts completions since the last interrupt, time in ns
+pub struct IrqCoalescer {
+    frames: AtomicUsize,
+    first: AtomicU64,
+}
+
+impl IrqCoalescer {
+    pub fn new() -> Self {
+        IrqCoalescer {
+            frames: AtomicUsize::new(0),
+            first: AtomicU64::new(0),
+        }
+    }
+    pub fn event(&self, now: u64) {
+        if self.frames.fetch_add(1, Ordering::Relaxed) == 0 {
+            self.first.store(now, Ordering::Relaxed);
+        }
+    }
+    //pending completions are consumed once expired
+    pub fn expire(&self, max_frames: usize, usecs: u64, now: u64) -> bool {
+        let frames = self.frames.load(Ordering::Relaxed);
+        let elapsed = now.saturating_sub(self.first.load(Ordering::Relaxed));
+        let expired = frames != 0
+            && ((max_frames == 0 && usecs == 0)
+                || (max_frames != 0 && frames >= max_frames)
+                || (usecs != 0 && elapsed >= usecs * 1000));
+        if expired {
+            self.frames.store(0, Ordering::Relaxed);
+        }
+        expired
+    }
+}
+
+impl Default for IrqCoalescer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ObjGenHeader for IrqVec {
     fn gen_c_header<W: std::io::Write>(&self, header: &mut W) -> std::io::Result<()> {
         writeln!(header, "typedef enum {{")?;
-        for f in self.v.iter() {
-            writeln!(header, "    {} = {},", f.name, f.id)?;
+        for (id, name) in self.causes.iter().enumerate() {
+            writeln!(header, "    {} = {},", name, id)?;
         }
         writeln!(header, "}} {};", self.name)?;
+        if self.fixed {
+            writeln!(header, "typedef enum {{")?;
+            for f in self.v.iter() {
+                writeln!(header, "    {} = {},", f.name, f.id)?;
+            }
+            writeln!(header, "}} {}Vec;", self.name)?;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn irq_coalescer_test() {
+        let coal = IrqCoalescer::new();
+        assert!(!coal.expire(0, 0, 0));
+        coal.event(100);
+        assert!(coal.expire(0, 0, 100));
+        assert!(!coal.expire(0, 0, 100));
+        coal.event(1000);
+        coal.event(1500);
+        assert!(!coal.expire(3, 2, 2999));
+        assert!(coal.expire(3, 2, 3000));
+        coal.event(4000);
+        coal.event(4000);
+        assert!(!coal.expire(3, 0, 1_000_000));
+        coal.event(4000);
+        assert!(coal.expire(3, 0, 4000));
+    }
+}
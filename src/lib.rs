@@ -1,5 +1,6 @@
 mod aborter;
 pub mod arbiter;
+pub mod clock;
 pub mod desc;
 pub mod etha;
 pub mod etha_ipsec;
@@ -11,3 +12,5 @@ pub mod mac;
 pub mod reg_if;
 #[cfg(feature = "rohc")]
 pub mod rohc;
+#[cfg(test)]
+mod tests_frame_helper;
@@ -1,8 +1,17 @@
 mod pcap_mac;
-pub use pcap_mac::{pcap_cmp, PcapMac, PcapMacRx, PcapMacTx};
+pub use pcap_mac::{pcap_cmp, pcap_frames, PcapMac, PcapMacRx, PcapMacTx};
 use smoltcp::phy::{RxToken, TxToken};
 
 pub const MAC_MAX_LEN: usize = 0x4000;
+//preamble, fcs and inter frame gap
+const MAC_WIRE_OVERHEAD: usize = 24;
+//10Gbps
+const MAC_WIRE_PS_PER_BYTE: u64 = 800;
+
+//ns a frame takes on the wire, virtual time moves by it for each received frame
+pub fn wire_time(len: usize) -> u64 {
+    ((len + MAC_WIRE_OVERHEAD) as u64 * MAC_WIRE_PS_PER_BYTE).div_ceil(1000)
+}
 
 pub trait RxDevice<'a> {
     type RxToken: RxToken + 'a;
@@ -38,7 +38,8 @@ impl<'a, W: Write> TxToken for PcapMacTxToken<'a, W> {
 
 pub struct PcapMacRx {
     buffer: [u8; MAC_MAX_LEN],
-    reader: Box<dyn PcapReaderIterator>,
+    //none for ports without input
+    reader: Option<Box<dyn PcapReaderIterator>>,
 }
 
 impl PcapMacRx {
@@ -57,7 +58,14 @@ impl PcapMacRx {
         };
         PcapMacRx {
             buffer: [0; MAC_MAX_LEN],
-            reader,
+            reader: Some(reader),
+        }
+    }
+
+    pub fn empty() -> Self {
+        PcapMacRx {
+            buffer: [0; MAC_MAX_LEN],
+            reader: None,
         }
     }
 }
@@ -66,13 +74,14 @@ impl<'a> RxDevice<'a> for PcapMacRx {
     type RxToken = PcapMacRxToken<'a>;
 
     fn receive(&'a mut self) -> Option<Self::RxToken> {
+        let reader = self.reader.as_mut()?;
         loop {
-            match self.reader.next() {
+            match reader.next() {
                 Ok((offset, block)) => {
                     if let PcapBlockOwned::Legacy(b) = block {
                         let len = b.data.len();
                         self.buffer[..len].copy_from_slice(&b.data);
-                        self.reader.consume(offset);
+                        reader.consume(offset);
                         return Some(PcapMacRxToken(&mut self.buffer[..len]));
                     } else {
                         panic!("Invalid Pcap Block!")
@@ -80,7 +89,7 @@ impl<'a> RxDevice<'a> for PcapMacRx {
                 }
                 Err(PcapError::Eof) => return None,
                 Err(PcapError::Incomplete) => {
-                    self.reader.refill().unwrap();
+                    reader.refill().unwrap();
                 }
                 Err(e) => {
                     panic!("error while pcap reading: {:?}", e);
@@ -128,6 +137,22 @@ impl PcapMac {
             tx: PcapMacTx::new(tx_pcap_file),
         }
     }
+
+    //one mac per port, tx of port i is written to {tx_prefix}_port{i}.pcap
+    pub fn ports(rx_pcap_files: &[Option<&str>], tx_prefix: &str) -> Vec<Self> {
+        rx_pcap_files
+            .iter()
+            .enumerate()
+            .map(|(i, rx)| PcapMac {
+                rx: rx.map_or_else(PcapMacRx::empty, PcapMacRx::new),
+                tx: PcapMacTx::new(&Self::port_file(tx_prefix, i)),
+            })
+            .collect()
+    }
+
+    pub fn port_file(tx_prefix: &str, port: usize) -> String {
+        format!("{}_port{}.pcap", tx_prefix, port)
+    }
 }
 
 impl<'a> Device<'a> for PcapMac {
@@ -153,6 +178,19 @@ impl<'a> Device<'a> for PcapMac {
 
 unsafe impl Send for PcapMac {}
 
+pub fn pcap_frames(file: &str) -> Vec<Vec<u8>> {
+    let mut mac = PcapMacRx::new(file);
+    let mut frames = vec![];
+    while let Some(rx) = mac.receive() {
+        rx.consume(Instant::now(), |buffer| {
+            frames.push(buffer.to_vec());
+            Ok(())
+        })
+        .unwrap();
+    }
+    frames
+}
+
 pub fn pcap_cmp(lhs_file: &str, rhs_file: &str, verbose: bool) -> Result<()> {
     let mut lhs = PcapMacRx::new(lhs_file);
     let mut rhs = PcapMacRx::new(rhs_file);
@@ -33,6 +33,31 @@ RingStatus {
         empty(RW):1, 1;
         almost_full(RW):2, 2;
         almost_empty(RW):3, 3;
+        done(RW):4, 4;
+    }
+}
+}
+
+//causes masked by int_mask are latched until written with 1
+define_reg! {
+RingIntStatus {
+    fields {
+        full(RW1C):0, 0;
+        empty(RW1C):1, 1;
+        almost_full(RW1C):2, 2;
+        almost_empty(RW1C):3, 3;
+        done(RW1C):4, 4;
+    }
+}
+}
+
+//done is raised after max_frames completions or usecs since the first one,
+//every completion raises done if both are 0
+define_reg! {
+RingIntCoal {
+    fields {
+        max_frames(RW):15, 0;
+        usecs(RW):31, 16;
     }
 }
 }
@@ -45,6 +70,14 @@ RingCtrl {
 }
 }
 pub const RING_REGS_SIZE: usize = 0x10;
+pub const RING_INT_STATUS_OFFSET: usize = 0xb;
+pub const RING_INT_COAL_OFFSET: usize = 0xe;
+pub const RING_INT_DONE: u64 = 0x10;
+//int_status and int_coal are only decoded by rings which latch interrupts,
+//the others raise level irqs from status and int_mask
+pub const fn ring_int_offset(offset: usize) -> bool {
+    offset == RING_INT_STATUS_OFFSET || offset == RING_INT_COAL_OFFSET
+}
 
 reg_map! {
     pub RingRegs(0x10) {
@@ -59,8 +92,21 @@ reg_map! {
         p_producer(RW): RingPtr, 0x8;
         status(RO):RingStatus, 0x9;
         int_mask(RW):RingStatus, 0xa;
+        int_status(RW1C):RingIntStatus, 0xb;
         ctrl(RW):RingCtrl, 0xc;
         mem_size(RW):RingSize, 0xd;
+        int_coal(RW):RingIntCoal, 0xe;
+    }
+}
+
+impl LockedRingRegs {
+    //returns the newly latched causes
+    pub fn r_int_latch(&self, done: bool) -> u64 {
+        let causes =
+            (self.status().get() | if done { RING_INT_DONE } else { 0 }) & self.int_mask().get();
+        let latched = self.int_status().get();
+        self.int_status_mut().set(latched | causes);
+        causes & !latched
     }
 }
 
@@ -23,7 +23,7 @@ impl<const CHS: usize> RegBus for TopRegs<CHS> {
         if QUEUE_REGS_RANGE.contains(&offset) {
             let offset = offset - QUEUE_REGS_RANGE.start;
             let idx = offset / RING_REGS_SIZE;
-            if idx < self.chs.len() {
+            if idx < self.chs.len() && !ring_int_offset(offset % RING_REGS_SIZE) {
                 let r = self.chs[idx].write((offset % RING_REGS_SIZE) as u64, data);
                 self.chs[idx].r_update_status();
                 r
@@ -40,7 +40,7 @@ impl<const CHS: usize> RegBus for TopRegs<CHS> {
         if QUEUE_REGS_RANGE.contains(&offset) {
             let offset = offset - QUEUE_REGS_RANGE.start;
             let idx = offset / RING_REGS_SIZE;
-            if idx < self.chs.len() {
+            if idx < self.chs.len() && !ring_int_offset(offset % RING_REGS_SIZE) {
                 self.chs[idx].r_update_status();
                 self.chs[idx].read((offset % RING_REGS_SIZE) as u64)
             } else {
@@ -68,6 +68,10 @@ impl<const CHS: usize> GenHeader for TopRegs<CHS> {
             header,
             "#define RING(name, i) (QUEUE_REGS_OFFSET + (RING_REGS_SIZE * i) + RING_REGS_##name##_OFFSET)",
         )?;
+        writeln!(
+            header,
+            "// RING INT_STATUS and INT_COAL are not decoded, irqs are raised by STATUS & INT_MASK",
+        )?;
 
         Ok(())
     }
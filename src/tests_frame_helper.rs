@@ -0,0 +1,67 @@
+use smoltcp::wire::*;
+
+//frame factories of the unit tests, ipv4 header checksums are filled,
+//l4 checksums are left to the tests
+
+const DST_MAC: [u8; 6] = [0x0, 0x11, 0x22, 0x33, 0x44, 0x55];
+const SRC_MAC: [u8; 6] = [0x0, 0x11, 0x22, 0x33, 0x44, 0x66];
+
+pub fn tcp_segment(src_port: u16, dst_port: u16, flags: u8, payload: &[u8]) -> Vec<u8> {
+    let mut segment = vec![0u8; TCP_HEADER_LEN + payload.len()];
+    let mut tcp = TcpPacket::new_unchecked(&mut segment[..]);
+    tcp.set_src_port(src_port);
+    tcp.set_dst_port(dst_port);
+    tcp.set_header_len(TCP_HEADER_LEN as u8);
+    tcp.payload_mut().copy_from_slice(payload);
+    segment[13] = flags;
+    segment
+}
+
+pub fn udp_datagram(src_port: u16, dst_port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut datagram = vec![0u8; UDP_HEADER_LEN + payload.len()];
+    let mut udp = UdpPacket::new_unchecked(&mut datagram[..]);
+    udp.set_src_port(src_port);
+    udp.set_dst_port(dst_port);
+    udp.set_len((UDP_HEADER_LEN + payload.len()) as u16);
+    udp.payload_mut().copy_from_slice(payload);
+    datagram
+}
+
+pub fn ipv4_packet(src: [u8; 4], dst: [u8; 4], protocol: IpProtocol, payload: &[u8]) -> Vec<u8> {
+    let len = 20 + payload.len();
+    let mut packet = vec![0u8; len];
+    let mut ip = Ipv4Packet::new_unchecked(&mut packet[..]);
+    ip.set_version(4);
+    ip.set_header_len(20);
+    ip.set_total_len(len as u16);
+    ip.set_hop_limit(64);
+    ip.set_protocol(protocol);
+    ip.set_src_addr(Ipv4Address(src));
+    ip.set_dst_addr(Ipv4Address(dst));
+    ip.payload_mut().copy_from_slice(payload);
+    ip.fill_checksum();
+    packet
+}
+
+pub fn ipv4_frame(src: [u8; 4], dst: [u8; 4], protocol: IpProtocol, payload: &[u8]) -> Vec<u8> {
+    let packet = ipv4_packet(src, dst, protocol, payload);
+    let mut frame = vec![0u8; ETHERNET_HEADER_LEN + packet.len()];
+    let mut eth = EthernetFrame::new_unchecked(&mut frame[..]);
+    eth.set_dst_addr(EthernetAddress(DST_MAC));
+    eth.set_src_addr(EthernetAddress(SRC_MAC));
+    eth.set_ethertype(EthernetProtocol::Ipv4);
+    eth.payload_mut().copy_from_slice(&packet);
+    frame
+}
+
+//192.168.1.1:0x1234 to 192.168.1.2:0x80
+pub fn ipv4_tcp_frame(payload: &[u8]) -> Vec<u8> {
+    let tcp = tcp_segment(0x1234, 0x80, 0, payload);
+    ipv4_frame([192, 168, 1, 1], [192, 168, 1, 2], IpProtocol::Tcp, &tcp)
+}
+
+//192.168.1.1:0x1234 to 192.168.1.2:0x80
+pub fn ipv4_udp_packet(payload: &[u8]) -> Vec<u8> {
+    let udp = udp_datagram(0x1234, 0x80, payload);
+    ipv4_packet([192, 168, 1, 1], [192, 168, 1, 2], IpProtocol::Udp, &udp)
+}